@@ -0,0 +1,330 @@
+// Copyright (C) Pavel Grebnev 2025
+// Distributed under the MIT License (license terms are at http://opensource.org/licenses/MIT).
+
+// Handling of ANSI escape sequences that scripts print to color their output.
+// Only SGR (colors and text attributes) is interpreted, other sequences are dropped.
+
+use std::borrow::Cow;
+
+const ESC: u8 = 0x1b;
+const BEL: u8 = 0x07;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnsiColor {
+    // 0-15 are the standard and bright colors, 16-255 are the extended palette
+    Indexed(u8),
+    Rgb(u8, u8, u8),
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TextStyle {
+    pub foreground: Option<AnsiColor>,
+    pub background: Option<AnsiColor>,
+    pub is_bold: bool,
+    pub is_italic: bool,
+    pub is_underlined: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct StyledSpan {
+    pub text: String,
+    pub style: TextStyle,
+}
+
+enum Token<'a> {
+    Text(&'a str),
+    // parameters of a "Select Graphic Rendition" sequence, e.g. "1;31"
+    Sgr(&'a str),
+}
+
+pub fn parse_styled_spans(text: &str) -> Vec<StyledSpan> {
+    let mut spans: Vec<StyledSpan> = Vec::new();
+    let mut style = TextStyle::default();
+
+    tokenize(text, |token| match token {
+        Token::Text(text) => match spans.last_mut() {
+            Some(last_span) if last_span.style == style => last_span.text.push_str(text),
+            _ => spans.push(StyledSpan {
+                text: text.to_string(),
+                style,
+            }),
+        },
+        Token::Sgr(parameters) => apply_sgr(&mut style, parameters),
+    });
+
+    spans
+}
+
+pub fn strip_escape_sequences(text: &str) -> Cow<'_, str> {
+    if !text.as_bytes().contains(&ESC) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut result = String::with_capacity(text.len());
+    tokenize(text, |token| {
+        if let Token::Text(text) = token {
+            result.push_str(text);
+        }
+    });
+    Cow::Owned(result)
+}
+
+// progress bars redraw the same line by returning the carriage,
+// we keep only the last state of such line (and its line ending)
+pub fn collapse_carriage_returns(text: &str) -> Cow<'_, str> {
+    let line_ending = if text.ends_with("\r\n") {
+        "\r\n"
+    } else if text.ends_with('\n') {
+        "\n"
+    } else {
+        ""
+    };
+    let content = &text[..text.len() - line_ending.len()];
+
+    if !content.contains('\r') {
+        return Cow::Borrowed(text);
+    }
+
+    let last_state = content
+        .split('\r')
+        .rev()
+        .find(|segment| !strip_escape_sequences(segment).is_empty())
+        .unwrap_or("");
+    Cow::Owned(format!("{}{}", last_state, line_ending))
+}
+
+pub fn get_color_rgb(color: AnsiColor) -> (u8, u8, u8) {
+    const STANDARD_COLORS: [(u8, u8, u8); 16] = [
+        (0, 0, 0),
+        (205, 49, 49),
+        (13, 188, 121),
+        (229, 229, 16),
+        (36, 114, 200),
+        (188, 63, 188),
+        (17, 168, 205),
+        (229, 229, 229),
+        (102, 102, 102),
+        (241, 76, 76),
+        (35, 209, 139),
+        (245, 245, 67),
+        (59, 142, 234),
+        (214, 112, 214),
+        (41, 184, 219),
+        (255, 255, 255),
+    ];
+    const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+    match color {
+        AnsiColor::Rgb(r, g, b) => (r, g, b),
+        AnsiColor::Indexed(idx @ 0..=15) => STANDARD_COLORS[idx as usize],
+        AnsiColor::Indexed(idx @ 16..=231) => {
+            let idx = idx - 16;
+            (
+                CUBE_LEVELS[(idx / 36) as usize],
+                CUBE_LEVELS[(idx / 6 % 6) as usize],
+                CUBE_LEVELS[(idx % 6) as usize],
+            )
+        }
+        AnsiColor::Indexed(idx) => {
+            let level = 8 + (idx - 232) * 10;
+            (level, level, level)
+        }
+    }
+}
+
+fn tokenize<'a>(text: &'a str, mut on_token: impl FnMut(Token<'a>)) {
+    let bytes = text.as_bytes();
+    let mut text_start = 0;
+    let mut idx = 0;
+
+    while idx < bytes.len() {
+        if bytes[idx] != ESC {
+            idx += 1;
+            continue;
+        }
+
+        if text_start < idx {
+            on_token(Token::Text(&text[text_start..idx]));
+        }
+
+        let sequence_end = match bytes.get(idx + 1) {
+            // Control Sequence Introducer: parameters, then a final byte in the range of 0x40-0x7E
+            Some(b'[') => {
+                let mut end = idx + 2;
+                while end < bytes.len() && !(0x40..=0x7e).contains(&bytes[end]) {
+                    end += 1;
+                }
+                if bytes.get(end) == Some(&b'm') {
+                    on_token(Token::Sgr(&text[idx + 2..end]));
+                }
+                end + 1
+            }
+            // Operating System Command (window title, hyperlinks), ends with BEL or ESC \
+            Some(b']') => {
+                let mut end = idx + 2;
+                loop {
+                    match bytes.get(end) {
+                        None => break end,
+                        Some(&BEL) => break end + 1,
+                        Some(&ESC) if bytes.get(end + 1) == Some(&b'\\') => break end + 2,
+                        Some(_) => end += 1,
+                    }
+                }
+            }
+            // character set selection
+            Some(b'(' | b')' | b'*' | b'+') => idx + 3,
+            Some(_) => idx + 2,
+            None => idx + 1,
+        };
+
+        idx = sequence_end.min(bytes.len());
+        // a broken sequence can end in the middle of a multibyte character
+        while !text.is_char_boundary(idx) {
+            idx += 1;
+        }
+        text_start = idx;
+    }
+
+    if text_start < bytes.len() {
+        on_token(Token::Text(&text[text_start..]));
+    }
+}
+
+fn apply_sgr(style: &mut TextStyle, parameters: &str) {
+    // empty parameters mean reset, same as 0
+    let mut codes = parameters
+        .split([';', ':'])
+        .map(|code| code.parse::<u32>().unwrap_or(0));
+
+    while let Some(code) = codes.next() {
+        match code {
+            0 => *style = TextStyle::default(),
+            1 => style.is_bold = true,
+            3 => style.is_italic = true,
+            4 => style.is_underlined = true,
+            22 => style.is_bold = false,
+            23 => style.is_italic = false,
+            24 => style.is_underlined = false,
+            30..=37 => style.foreground = Some(AnsiColor::Indexed((code - 30) as u8)),
+            39 => style.foreground = None,
+            40..=47 => style.background = Some(AnsiColor::Indexed((code - 40) as u8)),
+            49 => style.background = None,
+            90..=97 => style.foreground = Some(AnsiColor::Indexed((code - 90 + 8) as u8)),
+            100..=107 => style.background = Some(AnsiColor::Indexed((code - 100 + 8) as u8)),
+            38 | 48 => {
+                let color = match codes.next() {
+                    Some(5) => codes.next().map(|idx| AnsiColor::Indexed(idx as u8)),
+                    Some(2) => match (codes.next(), codes.next(), codes.next()) {
+                        (Some(r), Some(g), Some(b)) => {
+                            Some(AnsiColor::Rgb(r as u8, g as u8, b as u8))
+                        }
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                if code == 38 {
+                    style.foreground = color;
+                } else {
+                    style.background = color;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_span(text: &str, style: TextStyle) -> StyledSpan {
+        StyledSpan {
+            text: text.to_string(),
+            style,
+        }
+    }
+
+    #[test]
+    fn test_plain_text_is_one_unstyled_span() {
+        assert_eq!(
+            parse_styled_spans("just text\n"),
+            vec![make_span("just text\n", TextStyle::default())]
+        );
+        assert!(parse_styled_spans("").is_empty());
+    }
+
+    #[test]
+    fn test_sgr_sequences_split_text_into_styled_spans() {
+        let spans = parse_styled_spans("\x1b[1;31merror\x1b[0m: \x1b[4mfile.rs\x1b[24m done");
+
+        assert_eq!(
+            spans,
+            vec![
+                make_span(
+                    "error",
+                    TextStyle {
+                        foreground: Some(AnsiColor::Indexed(1)),
+                        is_bold: true,
+                        ..Default::default()
+                    }
+                ),
+                make_span(": ", TextStyle::default()),
+                make_span(
+                    "file.rs",
+                    TextStyle {
+                        is_underlined: true,
+                        ..Default::default()
+                    }
+                ),
+                make_span(" done", TextStyle::default()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extended_and_bright_colors_are_parsed() {
+        let spans = parse_styled_spans("\x1b[38;5;208ma\x1b[48;2;10;20;30mb\x1b[92;39mc\x1b[mx");
+
+        assert_eq!(spans[0].style.foreground, Some(AnsiColor::Indexed(208)));
+        assert_eq!(spans[1].style.background, Some(AnsiColor::Rgb(10, 20, 30)));
+        assert_eq!(spans[2].style.foreground, None);
+        assert_eq!(spans[2].style.background, Some(AnsiColor::Rgb(10, 20, 30)));
+        assert_eq!(spans[3], make_span("x", TextStyle::default()));
+    }
+
+    #[test]
+    fn test_non_sgr_sequences_are_dropped() {
+        assert_eq!(
+            strip_escape_sequences("\x1b[2K\x1b[1Gline\x1b]0;title\x07 \x1b]8;;http://a\x1b\\link\x1b]8;;\x1b\\\x1b(B!"),
+            "line link!"
+        );
+        // broken sequences at the end of the text don't panic
+        assert_eq!(strip_escape_sequences("text\x1b["), "text");
+        assert_eq!(strip_escape_sequences("text\x1bé"), "text");
+        assert_eq!(strip_escape_sequences("text\x1b"), "text");
+    }
+
+    #[test]
+    fn test_carriage_returns_keep_only_the_last_state_of_the_line() {
+        assert_eq!(
+            collapse_carriage_returns("10%\r50%\r100%\n"),
+            Cow::<str>::Owned("100%\n".to_string())
+        );
+        assert_eq!(
+            collapse_carriage_returns("progress 100%\r\x1b[2K\r\n"),
+            Cow::<str>::Owned("progress 100%\r\n".to_string())
+        );
+        assert!(matches!(
+            collapse_carriage_returns("windows line\r\n"),
+            Cow::Borrowed("windows line\r\n")
+        ));
+    }
+
+    #[test]
+    fn test_palette_colors_are_converted_to_rgb() {
+        assert_eq!(get_color_rgb(AnsiColor::Indexed(16)), (0, 0, 0));
+        assert_eq!(get_color_rgb(AnsiColor::Indexed(196)), (255, 0, 0));
+        assert_eq!(get_color_rgb(AnsiColor::Indexed(255)), (238, 238, 238));
+        assert_eq!(get_color_rgb(AnsiColor::Rgb(1, 2, 3)), (1, 2, 3));
+    }
+}
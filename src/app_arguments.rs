@@ -13,6 +13,11 @@ pub struct AppArguments {
     pub custom_title: Option<String>,
     pub scenario: Option<String>,
     pub run_script: Option<String>,
+    pub is_headless: bool,
+    // format ("json" or "junit") and folder
+    pub execution_report: Option<(String, String)>,
+    // arguments after --send, the app sends them to an already open instance and exits
+    pub send_command: Option<Vec<String>>,
     pub read_error: Option<String>,
 }
 
@@ -26,6 +31,9 @@ impl AppArguments {
             custom_title: None,
             scenario: None,
             run_script: None,
+            is_headless: false,
+            execution_report: None,
+            send_command: None,
             read_error: Some(read_error),
         }
     }
@@ -94,6 +102,24 @@ pub fn get_app_arguments() -> AppArguments {
             description: "Run the script or preset with the gived uid",
             number_of_args: 1,
         },
+        ArgumentDefinition {
+            name: "--headless",
+            syntax: "--headless",
+            description: "Run --scenario or --run without opening the window, print the output to the terminal and exit with a non-zero code if any script failed",
+            number_of_args: 0,
+        },
+        ArgumentDefinition {
+            name: "--report",
+            syntax: "--report <json|junit> <folder>",
+            description: "Write a report in the given format to the given folder when an execution finishes (overrides the config setting)",
+            number_of_args: 2,
+        },
+        ArgumentDefinition {
+            name: "--send",
+            syntax: "--send <command> [<args>]",
+            description: "Send a command to the already open scripter with the same config and exit, e.g. \"--send run <uid>\". Use \"--send help\" to see the list of commands. Should be the last argument",
+            number_of_args: 1,
+        },
     ];
 
     let mut custom_config_path = None;
@@ -103,6 +129,9 @@ pub fn get_app_arguments() -> AppArguments {
     let mut custom_title = None;
     let mut scenario = None;
     let mut run_script = None;
+    let mut is_headless = false;
+    let mut execution_report = None;
+    let mut send_command = None;
 
     let args: Vec<String> = std::env::args().collect();
 
@@ -184,6 +213,21 @@ pub fn get_app_arguments() -> AppArguments {
             if i + 1 < args.len() {
                 run_script = Some(args[i + 1].clone());
             }
+        } else if arg == "--headless" {
+            is_headless = true;
+        } else if arg == "--report" {
+            let format = args[i + 1].clone();
+            if format != "json" && format != "junit" {
+                return AppArguments::with_read_error(format!(
+                    "Unknown report format '{}', supported formats are 'json' and 'junit'",
+                    format
+                ));
+            }
+            execution_report = Some((format, args[i + 2].clone()));
+        } else if arg == "--send" {
+            // everything after --send belongs to the command
+            send_command = Some(args[i + 1..].to_vec());
+            break;
         }
 
         i += 1 + found_arg.number_of_args;
@@ -197,6 +241,9 @@ pub fn get_app_arguments() -> AppArguments {
         custom_title,
         scenario,
         run_script,
+        is_headless,
+        execution_report,
+        send_command,
         read_error: None,
     }
 }
@@ -31,6 +31,40 @@ pub enum ConfigUpdateBehavior {
     OnManualSave,
 }
 
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize, Serialize)]
+pub enum ExecutionReportFormat {
+    None,
+    Json,
+    JUnitXml,
+}
+
+// how the lines are written to the output files of the scripts
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize, Serialize)]
+pub enum LogFileFormat {
+    Plain,
+    // every line starts with the time and the stream it came from
+    Timestamped,
+    // one JSON object per line with the time, stream, retry index and text
+    JsonLines,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize, Serialize)]
+pub enum LogPatternSeverity {
+    Error,
+    Warning,
+    Info,
+}
+
+// classifies the output lines that match the pattern, e.g. for tools that print errors and exit with 0
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct LogPatternRule {
+    pub pattern: String,
+    pub severity: LogPatternSeverity,
+    // the script fails if any line matches, even if its exit code is a success
+    pub fails_script: bool,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct PathConfig {
@@ -71,6 +105,28 @@ pub struct RewritableConfig {
     pub show_current_git_branch: bool,
     pub quick_launch_scripts: Vec<Guid>,
     pub file_associations: Vec<FileAssociation>,
+    pub execution_report_format: ExecutionReportFormat,
+    // empty path means the log folder of the execution
+    pub execution_report_folder: PathConfig,
+    pub keep_escape_sequences_in_logs: bool,
+    pub log_file_format: LogFileFormat,
+    // how many scripts of one execution can run at the same time, 0 means no limit
+    pub parallel_scripts_limit: usize,
+    pub schedules: Vec<ScriptSchedule>,
+    pub file_watches: Vec<ScriptFileWatch>,
+    // new launches with the same config are forwarded to the already open window
+    pub single_instance: bool,
+    // the open window accepts commands from other processes of the user, e.g. from --send
+    pub enable_control_socket: bool,
+    // running scripts that take longer than this percentage of their usual duration are highlighted, 0 to disable
+    pub slow_script_threshold_percent: u32,
+    pub log_retention: LogRetention,
+    pub log_compression: LogCompression,
+    // applied to the output of all the scripts, after the rules of the script itself
+    pub log_pattern_rules: Vec<LogPatternRule>,
+    // opens the file references from the logs, e.g. "code -g {file}:{line}:{column}"
+    // empty means opening the files with the default application
+    pub editor_command: String,
 }
 
 #[derive(Clone)]
@@ -122,6 +178,10 @@ pub struct AppConfig {
     #[serde(skip)]
     pub custom_title: Option<String>,
     #[serde(skip)]
+    pub is_headless: bool,
+    #[serde(skip)]
+    pub execution_report_override: Option<(ExecutionReportFormat, PathBuf)>,
+    #[serde(skip)]
     pub config_read_error: Option<ConfigReadError>,
     #[serde(skip)]
     pub local_config_body: Option<Box<LocalConfig>>,
@@ -154,6 +214,14 @@ pub struct ArgumentPlaceholder {
     pub is_required: bool,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct EnvironmentVariable {
+    pub name: String,
+    // can contain argument placeholders
+    pub value: String,
+}
+
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
 pub enum ArgumentRequirement {
     Required,
@@ -161,6 +229,37 @@ pub enum ArgumentRequirement {
     Hidden,
 }
 
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
+pub enum ExitCodeResult {
+    Success,
+    Warning,
+    Skipped,
+    Failure,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ExitCodeReaction {
+    pub exit_code: i32,
+    pub result: ExitCodeResult,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
+pub enum RunConditionType {
+    // the value is the name of a script from the same execution
+    ScriptSucceeded,
+    ScriptFailed,
+    // e.g. "{{outputs.branch}} != main"
+    Expression,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RunCondition {
+    pub condition_type: RunConditionType,
+    pub value: String,
+}
+
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
 pub enum ReactionToPreviousFailures {
     SkipOnFailure,
@@ -183,6 +282,8 @@ pub struct OriginalScriptDefinition {
     pub arguments_requirement: ArgumentRequirement,
     pub autorerun_count: usize,
     pub autorerun_delay_sec: f32,
+    // 0 means no timeout
+    pub timeout_sec: f32,
     pub reaction_to_previous_failures: ReactionToPreviousFailures,
     pub arguments_hint: String,
     pub custom_executor: Option<Vec<String>>,
@@ -190,6 +291,20 @@ pub struct OriginalScriptDefinition {
     pub is_hidden: bool,
     pub autoclean_on_success: bool,
     pub ignore_output: bool,
+    // when the script is stopped or times out, the processes it started are left running
+    pub keep_child_processes: bool,
+    // if set, the user can type lines that are sent to stdin of the running script
+    pub accept_input: bool,
+    // applied on top of the variables from the env file
+    pub env_vars: Vec<EnvironmentVariable>,
+    // lines in the "NAME=value" format, empty path means no file
+    pub env_file: PathConfig,
+    // exit codes that are not treated as success (0) or failure (any other code)
+    pub exit_codes: Vec<ExitCodeReaction>,
+    // all of them need to be true for the script to run, otherwise it is skipped
+    pub run_conditions: Vec<RunCondition>,
+    // checked before the global rules
+    pub log_pattern_rules: Vec<LogPatternRule>,
 }
 
 impl Default for OriginalScriptDefinition {
@@ -207,6 +322,7 @@ impl Default for OriginalScriptDefinition {
             argument_placeholders: Vec::new(),
             autorerun_count: 0,
             autorerun_delay_sec: 0.0,
+            timeout_sec: 0.0,
             reaction_to_previous_failures: ReactionToPreviousFailures::SkipOnFailure,
             arguments_requirement: ArgumentRequirement::Optional,
             arguments_hint: "\"arg1\" \"arg2\"".to_string(),
@@ -215,6 +331,13 @@ impl Default for OriginalScriptDefinition {
             is_hidden: false,
             autoclean_on_success: false,
             ignore_output: false,
+            keep_child_processes: false,
+            accept_input: false,
+            env_vars: Vec::new(),
+            env_file: PathConfig::default(),
+            exit_codes: Vec::new(),
+            run_conditions: Vec::new(),
+            log_pattern_rules: Vec::new(),
         }
     }
 }
@@ -230,8 +353,18 @@ pub struct PresetItem {
     pub overridden_placeholder_values: std::collections::HashMap<String, String>,
     pub autorerun_count: Option<usize>,
     pub autorerun_delay_sec: Option<f32>,
+    pub timeout_sec: Option<f32>,
     pub reaction_to_previous_failures: Option<ReactionToPreviousFailures>,
     pub autoclean_on_success: Option<bool>,
+    // added to the variables of the script, replacing the ones with the same name
+    pub env_vars: Vec<EnvironmentVariable>,
+    // added to the exit codes of the script, replacing the ones with the same code
+    pub exit_codes: Vec<ExitCodeReaction>,
+    // added to the run conditions of the script
+    pub run_conditions: Vec<RunCondition>,
+    // indexes of the items of this preset that need to finish before this item starts
+    // None means that the item waits for all the items before it
+    pub dependencies: Option<Vec<usize>>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -387,6 +520,80 @@ pub struct ScriptKeybind {
     pub keybind_type: ScriptKeybindType,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ScheduleTrigger {
+    Interval { interval_sec: u64 },
+    // five fields in local time: minute, hour, day of month, month, day of week
+    Cron { expression: String },
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MissedRunPolicy {
+    Skip,
+    RunOnce,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScriptSchedule {
+    pub script_uid: Guid,
+    pub trigger: ScheduleTrigger,
+    pub missed_run_policy: MissedRunPolicy,
+}
+
+// what to do with the previous run when the watched files change while it is still running
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FileWatchRunningScriptPolicy {
+    Restart,
+    Queue,
+    // for the scripts that write to the watched files, the changes made while they run are theirs
+    Ignore,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScriptFileWatch {
+    pub script_uid: Guid,
+    // paths relative to the working directory, can contain wildcards, e.g. "src/**/*.rs"
+    pub paths: Vec<String>,
+    // changes of these paths don't start the script even if they are watched, e.g. "target"
+    pub ignored_paths: Vec<String>,
+    pub debounce_ms: u64,
+    pub running_script_policy: FileWatchRunningScriptPolicy,
+}
+
+// limits for the log folders of the finished executions, 0 means no limit
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LogRetention {
+    pub max_age_days: u32,
+    pub max_total_size_mb: u64,
+    pub max_runs: u32,
+    // failed runs get twice the max age and are removed last when over the other limits
+    pub keep_failed_runs_longer: bool,
+}
+
+impl LogRetention {
+    pub fn is_enabled(&self) -> bool {
+        self.max_age_days > 0 || self.max_total_size_mb > 0 || self.max_runs > 0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LogCompressionMode {
+    Disabled,
+    WhenExecutionFinishes,
+    AfterDays,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LogCompression {
+    pub mode: LogCompressionMode,
+    // only used with LogCompressionMode::AfterDays
+    pub after_days: u32,
+}
+
 pub fn get_app_config_copy() -> AppConfig {
     GLOBAL_CONFIG.with(|config| config.clone())
 }
@@ -395,6 +602,10 @@ pub fn get_arguments_read_error() -> Option<String> {
     GLOBAL_CONFIG.with(|config| config.arguments_read_error.clone())
 }
 
+pub fn is_headless_mode() -> bool {
+    GLOBAL_CONFIG.with(|config| config.is_headless)
+}
+
 pub fn save_config_to_file(config: &AppConfig) -> bool {
     let data = serde_json::to_string_pretty(&config);
     let data = match data {
@@ -485,6 +696,23 @@ fn get_default_config(app_arguments: AppArguments, config_path: PathBuf) -> AppC
                     executor_arguments: vec![],
                 },
             ],
+            execution_report_format: ExecutionReportFormat::None,
+            execution_report_folder: PathConfig::default(),
+            keep_escape_sequences_in_logs: false,
+            log_file_format: LogFileFormat::Plain,
+            parallel_scripts_limit: 0,
+            schedules: Vec::new(),
+            file_watches: Vec::new(),
+            single_instance: false,
+            enable_control_socket: false,
+            slow_script_threshold_percent: 200,
+            log_retention: LogRetention::default(),
+            log_compression: LogCompression {
+                mode: LogCompressionMode::Disabled,
+                after_days: 7,
+            },
+            log_pattern_rules: Vec::new(),
+            editor_command: String::new(),
         },
         script_definitions: Vec::new(),
         is_read_only: !has_write_permission(&config_path),
@@ -505,12 +733,37 @@ fn get_default_config(app_arguments: AppArguments, config_path: PathBuf) -> AppC
         local_config_path: PathConfig::default(),
         env_vars: app_arguments.env_vars,
         custom_title: app_arguments.custom_title,
+        is_headless: app_arguments.is_headless,
+        execution_report_override: app_arguments.execution_report.map(|(format, folder)| {
+            (
+                if format == "junit" {
+                    ExecutionReportFormat::JUnitXml
+                } else {
+                    ExecutionReportFormat::Json
+                },
+                PathBuf::from(folder),
+            )
+        }),
         config_read_error: None,
         local_config_body: None,
         arguments_read_error: app_arguments.read_error,
     }
 }
 
+// the default config with all the paths pointing to the given folder
+#[cfg(test)]
+pub fn get_test_config(directory: &Path) -> AppConfig {
+    let path = directory.to_string_lossy().to_string();
+    get_default_config(
+        AppArguments {
+            custom_logs_path: Some(path.clone()),
+            custom_work_path: Some(path),
+            ..Default::default()
+        },
+        directory.join("config.json"),
+    )
+}
+
 pub fn get_default_local_config(shared_config: &AppConfig) -> LocalConfig {
     LocalConfig {
         version: LATEST_LOCAL_CONFIG_FORMAT_VERSION.to_string(),
@@ -680,6 +933,8 @@ pub fn read_config() -> AppConfig {
     config.is_read_only = default_config.is_read_only;
     config.env_vars = default_config.env_vars;
     config.custom_title = default_config.custom_title;
+    config.is_headless = default_config.is_headless;
+    config.execution_report_override = default_config.execution_report_override;
     config.arguments_read_error = default_config.arguments_read_error;
 
     if !config.local_config_path.path.is_empty() {
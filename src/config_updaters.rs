@@ -6,8 +6,8 @@ use crate::json_file_updater::{JsonFileUpdater, UpdateResult};
 use serde_json::{json, Value as JsonValue};
 
 static FORMAT_VERSION_FIELD_NAME: &str = "version";
-pub static LATEST_CONFIG_FORMAT_VERSION: &str = "1.2.4";
-pub static LATEST_LOCAL_CONFIG_FORMAT_VERSION: &str = "1.2.4";
+pub static LATEST_CONFIG_FORMAT_VERSION: &str = "1.2.26";
+pub static LATEST_LOCAL_CONFIG_FORMAT_VERSION: &str = "1.2.26";
 
 pub fn update_config_to_the_latest_version(config_json: &mut JsonValue) -> UpdateResult {
     let version = config_json[FORMAT_VERSION_FIELD_NAME].as_str();
@@ -146,6 +146,25 @@ fn register_config_updaters() -> JsonFileUpdater {
         .add_update_function("1.1.0", v1_1_0_rename_cursor_script_to_selected_scripts);
     json_config_updater.add_update_function("1.1.3", v1_1_3_add_immediate_script_keybind);
     json_config_updater.add_update_function("1.2.4", v1_2_4_add_autorerun_delay_field);
+    json_config_updater.add_update_function("1.2.8", v1_2_8_add_timeout_field);
+    json_config_updater.add_update_function("1.2.9", v1_2_9_add_execution_report_fields);
+    json_config_updater.add_update_function("1.2.10", v1_2_10_add_keep_escape_sequences_field);
+    json_config_updater.add_update_function("1.2.11", v1_2_11_add_accept_input_field);
+    json_config_updater.add_update_function("1.2.12", v1_2_12_add_script_dependency_fields);
+    json_config_updater.add_update_function("1.2.13", v1_2_13_add_schedules);
+    json_config_updater.add_update_function("1.2.14", v1_2_14_add_file_watches);
+    json_config_updater.add_update_function("1.2.15", v1_2_15_add_single_instance_field);
+    json_config_updater.add_update_function("1.2.16", v1_2_16_add_env_var_fields);
+    json_config_updater.add_update_function("1.2.17", v1_2_17_add_exit_codes_and_run_conditions);
+    json_config_updater.add_update_function("1.2.18", v1_2_18_add_slow_script_threshold_field);
+    json_config_updater.add_update_function("1.2.19", v1_2_19_add_log_retention);
+    json_config_updater.add_update_function("1.2.20", v1_2_20_add_log_compression);
+    json_config_updater.add_update_function("1.2.21", v1_2_21_add_log_file_format_field);
+    json_config_updater.add_update_function("1.2.22", v1_2_22_add_log_pattern_rules);
+    json_config_updater.add_update_function("1.2.23", v1_2_23_add_editor_command_field);
+    json_config_updater.add_update_function("1.2.24", v1_2_24_add_keep_child_processes_field);
+    json_config_updater.add_update_function("1.2.25", v1_2_25_add_enable_control_socket_field);
+    json_config_updater.add_update_function("1.2.26", v1_2_26_add_file_watch_ignored_paths);
     // add update functions above this line
     // don't forget to update LATEST_CONFIG_VERSION at the beginning of the file
 
@@ -236,6 +255,25 @@ fn register_local_config_updaters() -> JsonFileUpdater {
         .add_update_function("1.1.0", v1_1_0_rename_cursor_script_to_selected_scripts);
     json_config_updater.add_update_function("1.1.3", v1_1_3_add_immediate_script_keybind);
     json_config_updater.add_update_function("1.2.4", v1_2_4_add_autorerun_delay_field);
+    json_config_updater.add_update_function("1.2.8", v1_2_8_add_timeout_field);
+    json_config_updater.add_update_function("1.2.9", v1_2_9_add_execution_report_fields);
+    json_config_updater.add_update_function("1.2.10", v1_2_10_add_keep_escape_sequences_field);
+    json_config_updater.add_update_function("1.2.11", v1_2_11_add_accept_input_field);
+    json_config_updater.add_update_function("1.2.12", v1_2_12_add_script_dependency_fields);
+    json_config_updater.add_update_function("1.2.13", v1_2_13_add_schedules);
+    json_config_updater.add_update_function("1.2.14", v1_2_14_add_file_watches);
+    json_config_updater.add_update_function("1.2.15", v1_2_15_add_single_instance_field);
+    json_config_updater.add_update_function("1.2.16", v1_2_16_add_env_var_fields);
+    json_config_updater.add_update_function("1.2.17", v1_2_17_add_exit_codes_and_run_conditions);
+    json_config_updater.add_update_function("1.2.18", v1_2_18_add_slow_script_threshold_field);
+    json_config_updater.add_update_function("1.2.19", v1_2_19_add_log_retention);
+    json_config_updater.add_update_function("1.2.20", v1_2_20_add_log_compression);
+    json_config_updater.add_update_function("1.2.21", v1_2_21_add_log_file_format_field);
+    json_config_updater.add_update_function("1.2.22", v1_2_22_add_log_pattern_rules);
+    json_config_updater.add_update_function("1.2.23", v1_2_23_add_editor_command_field);
+    json_config_updater.add_update_function("1.2.24", v1_2_24_add_keep_child_processes_field);
+    json_config_updater.add_update_function("1.2.25", v1_2_25_add_enable_control_socket_field);
+    json_config_updater.add_update_function("1.2.26", v1_2_26_add_file_watch_ignored_paths);
     // add update functions above this line
     // don't forget to update LATEST_LOCAL_CONFIG_VERSION at the beginning of the file
 
@@ -786,3 +824,177 @@ fn v1_2_4_add_autorerun_delay_field(config_json: &mut JsonValue) {
         }
     });
 }
+
+fn v1_2_8_add_timeout_field(config_json: &mut JsonValue) {
+    for_each_script_original_definition_post_0_10_0(config_json, |item: &mut JsonValue| {
+        item["timeout_sec"] = json!(0.0);
+    });
+
+    for_each_script_preset(config_json, |preset| {
+        if let Some(items) = preset["items"].as_array_mut() {
+            items.iter_mut().for_each(|item: &mut JsonValue| {
+                item["timeout_sec"] = JsonValue::Null;
+            });
+        }
+    });
+}
+
+fn v1_2_9_add_execution_report_fields(config_json: &mut JsonValue) {
+    if let Some(rewritable_config) = config_json["rewritable"].as_object_mut() {
+        rewritable_config.insert("execution_report_format".to_string(), json!("None"));
+        rewritable_config.insert(
+            "execution_report_folder".to_string(),
+            json!({"path": "", "path_type": "WorkingDirRelative"}),
+        );
+    }
+}
+
+fn v1_2_10_add_keep_escape_sequences_field(config_json: &mut JsonValue) {
+    if let Some(rewritable_config) = config_json["rewritable"].as_object_mut() {
+        rewritable_config.insert("keep_escape_sequences_in_logs".to_string(), json!(false));
+    }
+}
+
+fn v1_2_11_add_accept_input_field(config_json: &mut JsonValue) {
+    for_each_script_original_definition_post_0_10_0(config_json, |script| {
+        script["accept_input"] = json!(false);
+    });
+}
+
+fn v1_2_12_add_script_dependency_fields(config_json: &mut JsonValue) {
+    if let Some(rewritable_config) = config_json["rewritable"].as_object_mut() {
+        rewritable_config.insert("parallel_scripts_limit".to_string(), json!(0));
+    }
+
+    for_each_script_preset(config_json, |preset| {
+        if let Some(items) = preset["items"].as_array_mut() {
+            items.iter_mut().for_each(|item: &mut JsonValue| {
+                item["dependencies"] = JsonValue::Null;
+            });
+        }
+    });
+}
+
+fn v1_2_13_add_schedules(config_json: &mut JsonValue) {
+    if let Some(rewritable_config) = config_json["rewritable"].as_object_mut() {
+        rewritable_config.insert("schedules".to_string(), json!([]));
+    }
+}
+
+fn v1_2_14_add_file_watches(config_json: &mut JsonValue) {
+    if let Some(rewritable_config) = config_json["rewritable"].as_object_mut() {
+        rewritable_config.insert("file_watches".to_string(), json!([]));
+    }
+}
+
+fn v1_2_15_add_single_instance_field(config_json: &mut JsonValue) {
+    if let Some(rewritable_config) = config_json["rewritable"].as_object_mut() {
+        rewritable_config.insert("single_instance".to_string(), json!(false));
+    }
+}
+
+fn v1_2_16_add_env_var_fields(config_json: &mut JsonValue) {
+    for_each_script_original_definition_post_0_10_0(config_json, |script| {
+        script["env_vars"] = json!([]);
+        script["env_file"] = json!({"path": "", "path_type": "WorkingDirRelative"});
+    });
+
+    for_each_script_preset(config_json, |preset| {
+        if let Some(items) = preset["items"].as_array_mut() {
+            items.iter_mut().for_each(|item: &mut JsonValue| {
+                item["env_vars"] = json!([]);
+            });
+        }
+    });
+}
+
+fn v1_2_17_add_exit_codes_and_run_conditions(config_json: &mut JsonValue) {
+    for_each_script_original_definition_post_0_10_0(config_json, |script| {
+        script["exit_codes"] = json!([]);
+        script["run_conditions"] = json!([]);
+    });
+
+    for_each_script_preset(config_json, |preset| {
+        if let Some(items) = preset["items"].as_array_mut() {
+            items.iter_mut().for_each(|item: &mut JsonValue| {
+                item["exit_codes"] = json!([]);
+                item["run_conditions"] = json!([]);
+            });
+        }
+    });
+}
+
+fn v1_2_18_add_slow_script_threshold_field(config_json: &mut JsonValue) {
+    if let Some(rewritable_config) = config_json["rewritable"].as_object_mut() {
+        rewritable_config.insert("slow_script_threshold_percent".to_string(), json!(200));
+    }
+}
+
+fn v1_2_19_add_log_retention(config_json: &mut JsonValue) {
+    if let Some(rewritable_config) = config_json["rewritable"].as_object_mut() {
+        rewritable_config.insert(
+            "log_retention".to_string(),
+            json!({
+                "max_age_days": 0,
+                "max_total_size_mb": 0,
+                "max_runs": 0,
+                "keep_failed_runs_longer": false,
+            }),
+        );
+    }
+}
+
+fn v1_2_20_add_log_compression(config_json: &mut JsonValue) {
+    if let Some(rewritable_config) = config_json["rewritable"].as_object_mut() {
+        rewritable_config.insert(
+            "log_compression".to_string(),
+            json!({"mode": "Disabled", "after_days": 7}),
+        );
+    }
+}
+
+fn v1_2_21_add_log_file_format_field(config_json: &mut JsonValue) {
+    if let Some(rewritable_config) = config_json["rewritable"].as_object_mut() {
+        rewritable_config.insert("log_file_format".to_string(), json!("Plain"));
+    }
+}
+
+fn v1_2_22_add_log_pattern_rules(config_json: &mut JsonValue) {
+    if let Some(rewritable_config) = config_json["rewritable"].as_object_mut() {
+        rewritable_config.insert("log_pattern_rules".to_string(), json!([]));
+    }
+
+    for_each_script_original_definition_post_0_10_0(config_json, |script| {
+        script["log_pattern_rules"] = json!([]);
+    });
+}
+
+fn v1_2_23_add_editor_command_field(config_json: &mut JsonValue) {
+    if let Some(rewritable_config) = config_json["rewritable"].as_object_mut() {
+        rewritable_config.insert("editor_command".to_string(), json!(""));
+    }
+}
+
+fn v1_2_24_add_keep_child_processes_field(config_json: &mut JsonValue) {
+    for_each_script_original_definition_post_0_10_0(config_json, |script| {
+        script["keep_child_processes"] = json!(false);
+    });
+}
+
+fn v1_2_25_add_enable_control_socket_field(config_json: &mut JsonValue) {
+    if let Some(rewritable_config) = config_json["rewritable"].as_object_mut() {
+        rewritable_config.insert("enable_control_socket".to_string(), json!(false));
+    }
+}
+
+fn v1_2_26_add_file_watch_ignored_paths(config_json: &mut JsonValue) {
+    if let Some(file_watches) = config_json
+        .get_mut("rewritable")
+        .and_then(|rewritable_config| rewritable_config.get_mut("file_watches"))
+        .and_then(|file_watches| file_watches.as_array_mut())
+    {
+        for file_watch in file_watches {
+            file_watch["ignored_paths"] = json!([]);
+        }
+    }
+}
@@ -0,0 +1,696 @@
+// Copyright (C) Pavel Grebnev 2025
+// Distributed under the MIT License (license terms are at http://opensource.org/licenses/MIT).
+
+use crate::config;
+use crate::headless_runner;
+use crate::parallel_execution_manager::ExecutionId;
+use crate::scenario;
+use crossbeam_channel::{bounded, unbounded, Receiver, Sender};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+// how long a client waits for the window to process its request
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+// one request per connection, both the request and the response are one line of JSON
+// e.g. "status" or {"run":{"uid":"...","arguments":"--fast"}}
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum ControlRequest {
+    ListScripts,
+    Run {
+        uid: config::Guid,
+        arguments: Option<String>,
+        placeholders: Option<HashMap<String, String>>,
+        // add the scripts to the execution list without starting them
+        only_schedule: Option<bool>,
+    },
+    Stop {
+        execution_id: u64,
+    },
+    Status,
+    TailLogs {
+        execution_id: u64,
+    },
+    // scripter was started again with the same config and single instance mode enabled
+    Launch {
+        scenario: Option<Result<scenario::Scenario, String>>,
+        env_vars: Vec<(String, String)>,
+    },
+}
+
+pub struct ReceivedRequest {
+    pub request: ControlRequest,
+    response_sender: Sender<JsonValue>,
+}
+
+impl ReceivedRequest {
+    pub fn respond(self, response: JsonValue) {
+        // the client could have already given up waiting
+        let _ = self.response_sender.send(response);
+    }
+}
+
+pub struct ControlServer {
+    request_receiver: Receiver<ReceivedRequest>,
+    endpoint_path: PathBuf,
+    // when only single instance mode is enabled, the socket takes nothing but the launches
+    is_api_enabled: Arc<AtomicBool>,
+    // execution ids can't be serialized, so the clients get numbers that are assigned on first use
+    external_execution_ids: HashMap<ExecutionId, u64>,
+    next_external_execution_id: u64,
+}
+
+impl ControlServer {
+    // returns None if the endpoint can't be created, e.g. when another instance already uses it
+    pub fn start(config_path: &Path, is_api_enabled: bool) -> Option<Self> {
+        let endpoint_path = match get_endpoint_path(config_path) {
+            Ok(endpoint_path) => endpoint_path,
+            Err(error) => {
+                eprintln!("Can't open control socket: {}", error);
+                return None;
+            }
+        };
+        let listener = match platform::bind(&endpoint_path) {
+            Ok(listener) => listener,
+            Err(error) => {
+                eprintln!(
+                    "Can't open control socket '{}': {}",
+                    endpoint_path.to_string_lossy(),
+                    error
+                );
+                return None;
+            }
+        };
+
+        let (request_sender, request_receiver) = unbounded();
+        let is_api_enabled = Arc::new(AtomicBool::new(is_api_enabled));
+        let spawn_result = std::thread::Builder::new()
+            .name("control_socket".to_string())
+            .spawn({
+                let is_api_enabled = is_api_enabled.clone();
+                move || loop {
+                    match listener.accept() {
+                        Ok(stream) => handle_connection(stream, &request_sender, &is_api_enabled),
+                        Err(error) => eprintln!("Control socket connection failed: {}", error),
+                    }
+                }
+            });
+        if let Err(error) = spawn_result {
+            eprintln!("Can't start control socket thread: {}", error);
+            let _ = std::fs::remove_file(&endpoint_path);
+            return None;
+        }
+
+        Some(Self {
+            request_receiver,
+            endpoint_path,
+            is_api_enabled,
+            external_execution_ids: HashMap::new(),
+            next_external_execution_id: 1,
+        })
+    }
+
+    pub fn set_api_enabled(&self, is_api_enabled: bool) {
+        self.is_api_enabled.store(is_api_enabled, Ordering::Release);
+    }
+
+    pub fn try_receive_request(&self) -> Option<ReceivedRequest> {
+        self.request_receiver.try_recv().ok()
+    }
+
+    pub fn get_external_execution_id(&mut self, execution_id: ExecutionId) -> u64 {
+        *self
+            .external_execution_ids
+            .entry(execution_id)
+            .or_insert_with(|| {
+                self.next_external_execution_id += 1;
+                self.next_external_execution_id - 1
+            })
+    }
+
+    pub fn find_execution_id(&self, external_execution_id: u64) -> Option<ExecutionId> {
+        self.external_execution_ids
+            .iter()
+            .find(|(_, id)| **id == external_execution_id)
+            .map(|(execution_id, _)| *execution_id)
+    }
+}
+
+impl Drop for ControlServer {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.endpoint_path);
+    }
+}
+
+pub fn error_response(error: impl Into<String>) -> JsonValue {
+    json!({ "ok": false, "error": error.into() })
+}
+
+fn handle_connection(
+    stream: platform::Stream,
+    request_sender: &Sender<ReceivedRequest>,
+    is_api_enabled: &AtomicBool,
+) {
+    let _ = stream.set_read_timeout(Some(RESPONSE_TIMEOUT));
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    if let Err(error) = reader.read_line(&mut request_line) {
+        eprintln!("Can't read control socket request: {}", error);
+        return;
+    }
+    let mut stream = reader.into_inner();
+
+    let response = match serde_json::from_str::<ControlRequest>(&request_line) {
+        Ok(request)
+            if !is_api_enabled.load(Ordering::Acquire)
+                && !matches!(request, ControlRequest::Launch { .. }) =>
+        {
+            error_response("\"Allow control from the command line\" is not enabled in the settings of the running scripter")
+        }
+        Ok(request) => {
+            let (response_sender, response_receiver) = bounded(1);
+            let _ = request_sender.send(ReceivedRequest {
+                request,
+                response_sender,
+            });
+            match response_receiver.recv_timeout(RESPONSE_TIMEOUT) {
+                Ok(response) => response,
+                Err(_) => error_response("scripter didn't respond in time"),
+            }
+        }
+        Err(error) => error_response(format!("Can't parse the request: {}", error)),
+    };
+
+    let _ = writeln!(stream, "{}", response);
+}
+
+// different configs get different endpoints, so scripter instances with different configs don't interfere
+fn get_endpoint_path(config_path: &Path) -> std::io::Result<PathBuf> {
+    let config_path = std::fs::canonicalize(config_path).unwrap_or(config_path.to_path_buf());
+    Ok(platform::get_endpoint_directory()?.join(format!(
+        "scripter-{:016x}.sock",
+        get_stable_hash(config_path.as_os_str().as_encoded_bytes())
+    )))
+}
+
+// FNV-1a, unlike DefaultHasher it gives the same value in the builds made with any Rust version
+fn get_stable_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn send_request(config_path: &Path, request: &str) -> Result<JsonValue, String> {
+    let endpoint_path = get_endpoint_path(config_path)
+        .map_err(|error| format!("Can't find the endpoint of a running scripter: {}", error))?;
+    let mut stream = platform::connect(&endpoint_path).map_err(|error| {
+        format!(
+            "Can't connect to a running scripter (\"Allow control from the command line\" should be enabled in its settings): {}",
+            error
+        )
+    })?;
+    let _ = stream.set_read_timeout(Some(RESPONSE_TIMEOUT + Duration::from_secs(1)));
+
+    writeln!(stream, "{}", request)
+        .map_err(|error| format!("Can't send the request: {}", error))?;
+
+    let mut response_line = String::new();
+    BufReader::new(stream)
+        .read_line(&mut response_line)
+        .map_err(|error| format!("Can't read the response: {}", error))?;
+    serde_json::from_str(&response_line)
+        .map_err(|error| format!("Can't parse the response: {}", error))
+}
+
+// hands the arguments of this launch to the already open window if the config asks for it
+// returns the exit code for the process if the open window took over the launch
+pub fn forward_launch_to_open_instance() -> Option<i32> {
+    let app_config = config::get_app_config_copy();
+    if app_config.config_read_error.is_some()
+        || !config::get_main_rewritable_config(&app_config).single_instance
+    {
+        return None;
+    }
+
+    let request = ControlRequest::Launch {
+        scenario: scenario::get_scenario_copy(),
+        env_vars: app_config
+            .env_vars
+            .iter()
+            .map(|(name, value)| {
+                (
+                    name.to_string_lossy().to_string(),
+                    value.to_string_lossy().to_string(),
+                )
+            })
+            .collect(),
+    };
+    let request = serde_json::to_string(&request).ok()?;
+
+    // if there is no open window, this instance opens it
+    let response = send_request(&app_config.paths.config_path, &request).ok()?;
+    if response["ok"] == json!(true) {
+        Some(0)
+    } else {
+        headless_runner::attach_to_parent_console();
+        eprintln!(
+            "The open scripter couldn't take over the launch: {}",
+            response["error"].as_str().unwrap_or_default()
+        );
+        Some(1)
+    }
+}
+
+// converts the arguments of "--send" to a request
+fn get_request_from_arguments(arguments: &[String]) -> Result<String, String> {
+    let request = match arguments
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<&str>>()
+        .as_slice()
+    {
+        ["list"] => ControlRequest::ListScripts,
+        ["status"] => ControlRequest::Status,
+        [command @ ("run" | "schedule"), uid, rest @ ..] => ControlRequest::Run {
+            uid: config::Guid::from_string(uid.to_string())
+                .map_err(|error| format!("Can't read script uid: {}", error))?,
+            arguments: if rest.is_empty() {
+                None
+            } else {
+                Some(rest.join(" "))
+            },
+            placeholders: None,
+            only_schedule: Some(*command == "schedule"),
+        },
+        [command @ ("stop" | "logs"), execution_id] => {
+            let execution_id = execution_id
+                .parse()
+                .map_err(|_| format!("Execution id should be a number, got '{}'", execution_id))?;
+            if *command == "stop" {
+                ControlRequest::Stop { execution_id }
+            } else {
+                ControlRequest::TailLogs { execution_id }
+            }
+        }
+        // the request as it is sent over the socket, allows to use all the options
+        ["json", request] => return Ok(request.to_string()),
+        _ => {
+            return Err(format!(
+                "Unknown command '{}'. Supported commands:\n{}",
+                arguments.join(" "),
+                SEND_COMMANDS_HELP
+            ))
+        }
+    };
+
+    serde_json::to_string(&request).map_err(|error| error.to_string())
+}
+
+const SEND_COMMANDS_HELP: &str = "  list                          list the scripts and presets
+  status                        show the started executions and their scripts
+  run <uid> [<arguments>]       run a script or a preset
+  schedule <uid> [<arguments>]  add a script or a preset to the execution list without running it
+  stop <execution_id>           stop an execution
+  logs <execution_id>           print the recent output of an execution
+  json <request>                send a JSON request, e.g. '{\"run\":{\"uid\":\"...\",\"placeholders\":{\"NAME\":\"value\"}}}'";
+
+// client mode, sends the request to an already open scripter and prints the response
+// returns the exit code for the process
+pub fn run_send_command(arguments: &[String]) -> i32 {
+    headless_runner::attach_to_parent_console();
+
+    if arguments == ["help"] {
+        println!("Supported commands:\n{}", SEND_COMMANDS_HELP);
+        return 0;
+    }
+
+    let request = match get_request_from_arguments(arguments) {
+        Ok(request) => request,
+        Err(error) => {
+            eprintln!("{}", error);
+            return 2;
+        }
+    };
+
+    let config_path = config::get_app_config_copy().paths.config_path;
+    match send_request(&config_path, &request) {
+        Ok(response) => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&response).unwrap_or_default()
+            );
+            if response["ok"] == json!(true) {
+                0
+            } else {
+                1
+            }
+        }
+        Err(error) => {
+            eprintln!("{}", error);
+            2
+        }
+    }
+}
+
+#[cfg(unix)]
+mod platform {
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::path::{Path, PathBuf};
+
+    extern "C" {
+        fn getuid() -> u32;
+    }
+
+    pub type Stream = UnixStream;
+
+    pub struct Listener(UnixListener);
+
+    impl Listener {
+        pub fn accept(&self) -> std::io::Result<UnixStream> {
+            self.0.accept().map(|(stream, _)| stream)
+        }
+    }
+
+    pub fn bind(endpoint_path: &Path) -> std::io::Result<Listener> {
+        if endpoint_path.exists() {
+            if UnixStream::connect(endpoint_path).is_ok() {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::AddrInUse,
+                    "another scripter instance is already listening",
+                ));
+            }
+            // left from an instance that didn't exit cleanly
+            std::fs::remove_file(endpoint_path)?;
+        }
+        let listener = UnixListener::bind(endpoint_path)?;
+        std::fs::set_permissions(endpoint_path, std::fs::Permissions::from_mode(0o600))?;
+        Ok(Listener(listener))
+    }
+
+    // the requests (and the launches with their env vars) should not go to a socket of another user
+    pub fn connect(endpoint_path: &Path) -> std::io::Result<UnixStream> {
+        if std::fs::symlink_metadata(endpoint_path)?.uid() != unsafe { getuid() } {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::PermissionDenied,
+                "the socket belongs to another user",
+            ));
+        }
+        UnixStream::connect(endpoint_path)
+    }
+
+    // other users can't create the socket before us in a folder that only the current user can access
+    pub fn get_endpoint_directory() -> std::io::Result<PathBuf> {
+        let user_id = unsafe { getuid() };
+        let directory = match std::env::var_os("XDG_RUNTIME_DIR") {
+            Some(runtime_directory) if !runtime_directory.is_empty() => {
+                PathBuf::from(runtime_directory)
+            }
+            _ => {
+                let directory = std::env::temp_dir().join(format!("scripter-{}", user_id));
+                match std::fs::DirBuilder::new().mode(0o700).create(&directory) {
+                    Ok(()) => {}
+                    Err(error) if error.kind() == std::io::ErrorKind::AlreadyExists => {}
+                    Err(error) => return Err(error),
+                }
+                directory
+            }
+        };
+
+        // the folder in the shared temp folder could have been made by another user
+        let metadata = std::fs::symlink_metadata(&directory)?;
+        if !metadata.is_dir() || metadata.uid() != user_id || metadata.mode() & 0o077 != 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::PermissionDenied,
+                format!(
+                    "'{}' is not a folder that only the current user can access",
+                    directory.to_string_lossy()
+                ),
+            ));
+        }
+        Ok(directory)
+    }
+}
+
+// there is no named pipe support in std, so a loopback TCP port is used, which any local process can
+// connect to, so every connection has to start with a random token from the endpoint file
+// the file is in the temp folder of the user (e.g. "AppData\Local\Temp"), other users can't read it
+#[cfg(not(unix))]
+mod platform {
+    use std::io::{Read, Write};
+    use std::net::{Ipv4Addr, TcpListener, TcpStream};
+    use std::path::{Path, PathBuf};
+
+    pub type Stream = TcpStream;
+
+    const TOKEN_LENGTH: usize = 32;
+
+    pub struct Listener {
+        listener: TcpListener,
+        token: String,
+    }
+
+    impl Listener {
+        // the connections that don't start with the token are dropped without reading the request
+        pub fn accept(&self) -> std::io::Result<TcpStream> {
+            let (mut stream, _) = self.listener.accept()?;
+            stream.set_read_timeout(Some(super::RESPONSE_TIMEOUT))?;
+            let mut token = [0u8; TOKEN_LENGTH];
+            stream.read_exact(&mut token)?;
+            if token != self.token.as_bytes() {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::PermissionDenied,
+                    "the connection didn't provide the right token",
+                ));
+            }
+            Ok(stream)
+        }
+    }
+
+    pub fn bind(endpoint_path: &Path) -> std::io::Result<Listener> {
+        if connect(endpoint_path).is_ok() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::AddrInUse,
+                "another scripter instance is already listening",
+            ));
+        }
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+        let token = format!("{:032x}", rand::random::<u128>());
+        std::fs::write(
+            endpoint_path,
+            format!("{} {}", listener.local_addr()?.port(), token),
+        )?;
+        Ok(Listener { listener, token })
+    }
+
+    pub fn connect(endpoint_path: &Path) -> std::io::Result<TcpStream> {
+        let endpoint = std::fs::read_to_string(endpoint_path)?;
+        let invalid_endpoint = || {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "the endpoint file is not in the \"<port> <token>\" format",
+            )
+        };
+        let (port, token) = endpoint
+            .trim()
+            .split_once(' ')
+            .ok_or_else(invalid_endpoint)?;
+        let port = port.parse::<u16>().map_err(|_| invalid_endpoint())?;
+        if token.len() != TOKEN_LENGTH {
+            return Err(invalid_endpoint());
+        }
+
+        let mut stream = TcpStream::connect((Ipv4Addr::LOCALHOST, port))?;
+        stream.write_all(token.as_bytes())?;
+        Ok(stream)
+    }
+
+    pub fn get_endpoint_directory() -> std::io::Result<PathBuf> {
+        Ok(std::env::temp_dir())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestDirectory;
+
+    #[test]
+    fn test_send_arguments_are_converted_to_requests() {
+        let to_arguments = |line: &str| {
+            line.split_whitespace()
+                .map(str::to_string)
+                .collect::<Vec<String>>()
+        };
+
+        let request = get_request_from_arguments(&to_arguments(
+            "run 00000000-0000-0000-0000-000000000001 --release -j 4",
+        ))
+        .unwrap();
+        match serde_json::from_str::<ControlRequest>(&request).unwrap() {
+            ControlRequest::Run {
+                uid,
+                arguments,
+                placeholders,
+                only_schedule,
+            } => {
+                assert_eq!(uid.data, 1);
+                assert_eq!(arguments, Some("--release -j 4".to_string()));
+                assert!(placeholders.is_none());
+                assert_eq!(only_schedule, Some(false));
+            }
+            _ => panic!("expected a run request"),
+        }
+
+        let request = get_request_from_arguments(&to_arguments("stop 3")).unwrap();
+        assert!(matches!(
+            serde_json::from_str::<ControlRequest>(&request).unwrap(),
+            ControlRequest::Stop { execution_id: 3 }
+        ));
+
+        assert!(get_request_from_arguments(&to_arguments("stop first")).is_err());
+        assert!(get_request_from_arguments(&to_arguments("unknown")).is_err());
+    }
+
+    #[test]
+    fn test_launch_request_keeps_the_scenario_and_env_vars() {
+        let request = ControlRequest::Launch {
+            scenario: Some(Ok(scenario::Scenario {
+                format_version: "1.0.0".to_string(),
+                start_focused: Some(true),
+                parallel_executions: vec![scenario::Execution {
+                    scripts: vec![scenario::Script {
+                        uid: config::Guid { data: 5 },
+                        name: None,
+                        arguments: Some("--all".to_string()),
+                        placeholders: None,
+                        timeout_sec: None,
+                    }],
+                    only_schedule: None,
+                }],
+            })),
+            env_vars: vec![("BUILD_TYPE".to_string(), "release".to_string())],
+        };
+
+        let request = serde_json::to_string(&request).unwrap();
+        match serde_json::from_str::<ControlRequest>(&request).unwrap() {
+            ControlRequest::Launch {
+                scenario: Some(Ok(scenario)),
+                env_vars,
+            } => {
+                assert_eq!(scenario.start_focused, Some(true));
+                assert_eq!(scenario.parallel_executions[0].scripts[0].uid.data, 5);
+                assert_eq!(
+                    env_vars,
+                    vec![("BUILD_TYPE".to_string(), "release".to_string())]
+                );
+            }
+            _ => panic!("expected a launch request with a scenario"),
+        }
+    }
+
+    #[test]
+    fn test_endpoint_name_does_not_depend_on_the_rust_version() {
+        // the published FNV-1a test vectors
+        assert_eq!(get_stable_hash(b""), 0xcbf29ce484222325);
+        assert_eq!(get_stable_hash(b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(get_stable_hash(b"foobar"), 0x85944171f73967e8);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_endpoint_is_in_a_folder_of_the_current_user() {
+        use std::os::unix::fs::MetadataExt;
+
+        let config_directory = TestDirectory::new("control_socket_endpoint");
+        let endpoint_path = get_endpoint_path(&config_directory.join("config.json")).unwrap();
+        let metadata = std::fs::metadata(endpoint_path.parent().unwrap()).unwrap();
+        assert_eq!(
+            metadata.uid(),
+            std::fs::metadata(&*config_directory).unwrap().uid()
+        );
+        assert_eq!(metadata.mode() & 0o077, 0);
+    }
+
+    #[cfg(not(unix))]
+    #[test]
+    fn test_connections_without_the_token_are_rejected() {
+        let config_directory = TestDirectory::new("control_socket_token");
+        let config_path = config_directory.join("config.json");
+        let control_server = ControlServer::start(&config_path, true).unwrap();
+
+        let endpoint = std::fs::read_to_string(get_endpoint_path(&config_path).unwrap()).unwrap();
+        let port: u16 = endpoint.split_once(' ').unwrap().0.parse().unwrap();
+        let mut stream =
+            std::net::TcpStream::connect((std::net::Ipv4Addr::LOCALHOST, port)).unwrap();
+        writeln!(stream, "{}\"status\"", "0".repeat(32)).unwrap();
+        let mut response = String::new();
+        let _ = BufReader::new(stream).read_line(&mut response);
+
+        assert!(response.is_empty());
+        assert!(control_server.try_receive_request().is_none());
+    }
+
+    #[test]
+    fn test_request_sent_to_control_server_gets_the_response() {
+        let config_directory = TestDirectory::new("control_socket");
+        let config_path = config_directory.join("config.json");
+        let control_server = ControlServer::start(&config_path, true).unwrap();
+        // the second instance with the same config can't take over the endpoint
+        assert!(ControlServer::start(&config_path, true).is_none());
+
+        let client_thread = std::thread::spawn(move || send_request(&config_path, "\"status\""));
+
+        let received_request = loop {
+            if let Some(received_request) = control_server.try_receive_request() {
+                break received_request;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        };
+        assert!(matches!(received_request.request, ControlRequest::Status));
+        received_request.respond(json!({ "ok": true, "executions": [] }));
+
+        let response = client_thread.join().unwrap().unwrap();
+        assert_eq!(response, json!({ "ok": true, "executions": [] }));
+    }
+
+    #[test]
+    fn test_only_launches_are_taken_when_the_api_is_not_enabled() {
+        let config_directory = TestDirectory::new("control_socket_single_instance");
+        let config_path = config_directory.join("config.json");
+        let control_server = ControlServer::start(&config_path, false).unwrap();
+
+        let response = send_request(&config_path, "\"status\"").unwrap();
+        assert_eq!(response["ok"], json!(false));
+        assert!(control_server.try_receive_request().is_none());
+
+        let launch_request = serde_json::to_string(&ControlRequest::Launch {
+            scenario: None,
+            env_vars: Vec::new(),
+        })
+        .unwrap();
+        let client_thread = std::thread::spawn(move || send_request(&config_path, &launch_request));
+        let received_request = loop {
+            if let Some(received_request) = control_server.try_receive_request() {
+                break received_request;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        };
+        assert!(matches!(
+            received_request.request,
+            ControlRequest::Launch { .. }
+        ));
+        received_request.respond(json!({ "ok": true }));
+        assert_eq!(
+            client_thread.join().unwrap().unwrap(),
+            json!({ "ok": true })
+        );
+    }
+}
@@ -0,0 +1,228 @@
+// Copyright (C) Pavel Grebnev 2025
+// Distributed under the MIT License (license terms are at http://opensource.org/licenses/MIT).
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::config;
+use crate::execution_thread;
+
+const DURATIONS_FILE_NAME: &str = "script_durations.json";
+// how many of the latest successful runs of a script are used for the estimates
+const MAX_DURATIONS_PER_SCRIPT: usize = 10;
+// the scripts that were not run for a long time are forgotten first
+const MAX_SCRIPTS: usize = 500;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct DurationHistoryEntry {
+    uid: config::Guid,
+    // the same script can take very different time with different arguments
+    arguments: String,
+    durations_sec: Vec<f32>,
+}
+
+// durations of the latest successful runs of the scripts, kept between the app launches
+pub struct DurationHistory {
+    file_path: PathBuf,
+    // the most recently updated entry is the last
+    entries: Vec<DurationHistoryEntry>,
+    // added since the last save, they are applied on top of what other instances saved meanwhile
+    unsaved_durations: Vec<(config::Guid, String, f32)>,
+}
+
+impl DurationHistory {
+    pub fn load(logs_path: &Path) -> Self {
+        let file_path = logs_path.join(DURATIONS_FILE_NAME);
+        Self {
+            entries: read_entries(&file_path),
+            file_path,
+            unsaved_durations: Vec::new(),
+        }
+    }
+
+    pub fn add_duration(&mut self, script: &config::OriginalScriptDefinition, duration: Duration) {
+        let arguments = get_arguments_signature(script);
+        let duration_sec = duration.as_secs_f32();
+        add_to_entries(&mut self.entries, &script.uid, &arguments, duration_sec);
+        self.unsaved_durations
+            .push((script.uid.clone(), arguments, duration_sec));
+    }
+
+    // the median of the latest successful runs, so a single unusual run doesn't skew it
+    pub fn get_typical_duration(
+        &self,
+        script: &config::OriginalScriptDefinition,
+    ) -> Option<Duration> {
+        let arguments = get_arguments_signature(script);
+        let entry = self
+            .entries
+            .iter()
+            .find(|entry| entry.uid == script.uid && entry.arguments == arguments)?;
+
+        let mut durations_sec = entry.durations_sec.clone();
+        if durations_sec.is_empty() {
+            return None;
+        }
+        durations_sec.sort_by(|a, b| a.total_cmp(b));
+        let middle = durations_sec.len() / 2;
+        let median = if durations_sec.len() % 2 == 0 {
+            (durations_sec[middle - 1] + durations_sec[middle]) / 2.0
+        } else {
+            durations_sec[middle]
+        };
+
+        Some(Duration::from_secs_f32(median.max(0.0)))
+    }
+
+    // called when an execution finishes and on exit, not after every script
+    pub fn save(&mut self) {
+        if self.unsaved_durations.is_empty() {
+            return;
+        }
+
+        let mut entries = read_entries(&self.file_path);
+        for (uid, arguments, duration_sec) in self.unsaved_durations.drain(..) {
+            add_to_entries(&mut entries, &uid, &arguments, duration_sec);
+        }
+        self.entries = entries;
+
+        let content = match serde_json::to_string(&self.entries) {
+            Ok(content) => content,
+            Err(err) => {
+                eprintln!("Failed to serialize the script durations: {}", err);
+                return;
+            }
+        };
+
+        if let Some(parent) = self.file_path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        // the other instances never see a half written file
+        let temp_path = self
+            .file_path
+            .with_extension(format!("json.{}.tmp", std::process::id()));
+        let result = std::fs::write(&temp_path, content)
+            .and_then(|_| std::fs::rename(&temp_path, &self.file_path));
+        if let Err(err) = result {
+            eprintln!("Failed to write the script durations file: {}", err);
+            let _ = std::fs::remove_file(&temp_path);
+        }
+    }
+}
+
+impl Drop for DurationHistory {
+    fn drop(&mut self) {
+        self.save();
+    }
+}
+
+fn read_entries(file_path: &Path) -> Vec<DurationHistoryEntry> {
+    match std::fs::read_to_string(file_path) {
+        Ok(content) => match serde_json::from_str(&content) {
+            Ok(entries) => entries,
+            Err(err) => {
+                eprintln!("Failed to parse the script durations file: {}", err);
+                Vec::new()
+            }
+        },
+        Err(_) => Vec::new(),
+    }
+}
+
+fn add_to_entries(
+    entries: &mut Vec<DurationHistoryEntry>,
+    uid: &config::Guid,
+    arguments: &str,
+    duration_sec: f32,
+) {
+    let entry_idx = entries
+        .iter()
+        .position(|entry| entry.uid == *uid && entry.arguments == arguments);
+    let mut entry = match entry_idx {
+        Some(entry_idx) => entries.remove(entry_idx),
+        None => DurationHistoryEntry {
+            uid: uid.clone(),
+            arguments: arguments.to_string(),
+            durations_sec: Vec::new(),
+        },
+    };
+
+    entry.durations_sec.push(duration_sec);
+    if entry.durations_sec.len() > MAX_DURATIONS_PER_SCRIPT {
+        entry.durations_sec.remove(0);
+    }
+
+    entries.push(entry);
+    if entries.len() > MAX_SCRIPTS {
+        entries.remove(0);
+    }
+}
+
+fn get_arguments_signature(script: &config::OriginalScriptDefinition) -> String {
+    execution_thread::get_script_to_execute_description(script)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestDirectory;
+
+    #[test]
+    fn test_typical_duration_is_median_of_latest_runs_with_same_arguments() {
+        let logs_path = TestDirectory::new("durations");
+
+        let mut script = config::OriginalScriptDefinition {
+            arguments_line: "--fast".to_string(),
+            ..Default::default()
+        };
+
+        let mut duration_history = DurationHistory::load(&logs_path);
+        assert_eq!(duration_history.get_typical_duration(&script), None);
+
+        // the first slow runs get pushed out by the later ones
+        for _ in 0..MAX_DURATIONS_PER_SCRIPT {
+            duration_history.add_duration(&script, Duration::from_secs(100));
+        }
+        for duration_sec in [3, 1, 2, 50, 4, 5, 6] {
+            duration_history.add_duration(&script, Duration::from_secs(duration_sec));
+        }
+        duration_history.save();
+        assert_eq!(
+            DurationHistory::load(&logs_path).get_typical_duration(&script),
+            Some(Duration::from_millis(5500))
+        );
+
+        script.arguments_line = "--slow".to_string();
+        assert_eq!(duration_history.get_typical_duration(&script), None);
+    }
+
+    #[test]
+    fn test_durations_are_saved_in_batches_without_losing_the_ones_of_other_instances() {
+        let logs_path = TestDirectory::new("durations_instances");
+        let first_script = config::OriginalScriptDefinition::default();
+        let second_script = config::OriginalScriptDefinition::default();
+
+        let mut first_instance = DurationHistory::load(&logs_path);
+        let mut second_instance = DurationHistory::load(&logs_path);
+        first_instance.add_duration(&first_script, Duration::from_secs(1));
+        second_instance.add_duration(&second_script, Duration::from_secs(2));
+        let is_written_before_save = logs_path.join(DURATIONS_FILE_NAME).exists();
+        first_instance.save();
+        second_instance.save();
+
+        let duration_history = DurationHistory::load(&logs_path);
+        assert!(!is_written_before_save);
+        assert_eq!(
+            duration_history.get_typical_duration(&first_script),
+            Some(Duration::from_secs(1))
+        );
+        assert_eq!(
+            duration_history.get_typical_duration(&second_script),
+            Some(Duration::from_secs(2))
+        );
+        assert!(std::fs::read_dir(&*logs_path)
+            .unwrap()
+            .all(|entry| entry.unwrap().file_name() == DURATIONS_FILE_NAME));
+    }
+}
@@ -0,0 +1,399 @@
+// Copyright (C) Pavel Grebnev 2025
+// Distributed under the MIT License (license terms are at http://opensource.org/licenses/MIT).
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use crate::config;
+use crate::config_updaters::{update_config_to_the_latest_version, LATEST_CONFIG_FORMAT_VERSION};
+use crate::execution_thread;
+use crate::file_utils;
+use crate::json_file_updater::UpdateResult;
+use crate::parallel_execution_manager;
+
+const HISTORY_FILE_NAME: &str = "execution_history.jsonl";
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+// how many of the latest records we keep in memory to show in the UI
+pub const MAX_LOADED_RECORDS: usize = 200;
+// when the history file grows past this size, only the latest records that fit into half of it are kept
+const MAX_HISTORY_FILE_SIZE_BYTES: u64 = 8 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum ExecutedScriptResult {
+    Success,
+    Warning,
+    Failed,
+    Skipped,
+    NotStarted,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ExecutedScriptRecord {
+    // the exact definition the script was run with (including arguments and placeholder values)
+    pub script: config::OriginalScriptDefinition,
+    pub result: ExecutedScriptResult,
+    pub retry_count: usize,
+    pub start_time: Option<String>,
+    pub finish_time: Option<String>,
+    pub duration_sec: Option<f32>,
+    pub log_paths: Vec<PathBuf>,
+    // indexes of the scripts of this record that the script waited for, None means all the previous ones
+    #[serde(default)]
+    pub dependencies: Option<Vec<usize>>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ExecutionHistoryRecord {
+    // version of the config format the script definitions were written with
+    pub config_version: String,
+    pub start_time: String,
+    pub finish_time: String,
+    pub log_directory: PathBuf,
+    pub has_failed_scripts: bool,
+    pub scripts: Vec<ExecutedScriptRecord>,
+}
+
+pub fn make_record(execution: &parallel_execution_manager::Execution) -> ExecutionHistoryRecord {
+    let execution_scripts: Vec<execution_thread::ExecutionScript> = execution
+        .get_scheduled_scripts_cache()
+        .iter()
+        .map(|record| record.script.clone())
+        .collect();
+    let mut dependency_indexes = execution_thread::get_dependency_indexes(&execution_scripts);
+
+    let scripts = execution
+        .get_scheduled_scripts_cache()
+        .iter()
+        .enumerate()
+        .map(|(script_idx, record)| {
+            let status = &record.status;
+            let result = if !status.has_script_started() {
+                ExecutedScriptResult::NotStarted
+            } else {
+                match status.result {
+                    execution_thread::ScriptResultStatus::Success => ExecutedScriptResult::Success,
+                    execution_thread::ScriptResultStatus::Warning => ExecutedScriptResult::Warning,
+                    execution_thread::ScriptResultStatus::Failed => ExecutedScriptResult::Failed,
+                    execution_thread::ScriptResultStatus::Skipped => ExecutedScriptResult::Skipped,
+                    execution_thread::ScriptResultStatus::Disconnected => {
+                        ExecutedScriptResult::NotStarted
+                    }
+                }
+            };
+
+            // the scripts skipped by their exit code have logs too
+            let log_paths = if result != ExecutedScriptResult::NotStarted {
+                (0..=status.retry_count)
+                    .map(|retry_count| {
+                        file_utils::get_script_output_path(
+                            execution.get_log_folder_path().clone(),
+                            &record.script.original.name,
+                            script_idx as isize,
+                            retry_count,
+                        )
+                    })
+                    .filter(|path| path.exists())
+                    .collect()
+            } else {
+                Vec::new()
+            };
+
+            ExecutedScriptRecord {
+                script: record.script.original.clone(),
+                result,
+                retry_count: status.retry_count,
+                start_time: status.start_time.map(format_instant),
+                finish_time: status.finish_time.map(format_instant),
+                duration_sec: match (status.start_time, status.finish_time) {
+                    (Some(start_time), Some(finish_time))
+                        if result != ExecutedScriptResult::Skipped =>
+                    {
+                        Some((finish_time - start_time).as_secs_f32())
+                    }
+                    _ => None,
+                },
+                log_paths,
+                dependencies: dependency_indexes[script_idx].take(),
+            }
+        })
+        .collect();
+
+    ExecutionHistoryRecord {
+        config_version: LATEST_CONFIG_FORMAT_VERSION.to_string(),
+        start_time: execution.get_start_time().format(TIME_FORMAT).to_string(),
+        finish_time: chrono::Local::now().format(TIME_FORMAT).to_string(),
+        log_directory: execution.get_log_folder_path().clone(),
+        has_failed_scripts: execution.has_failed_scripts(),
+        scripts,
+    }
+}
+
+pub fn append_record(logs_path: &Path, record: &ExecutionHistoryRecord) {
+    let line = match serde_json::to_string(record) {
+        Ok(line) => line,
+        Err(err) => {
+            eprintln!("Failed to serialize execution history record: {}", err);
+            return;
+        }
+    };
+
+    let _ = std::fs::create_dir_all(logs_path);
+    let history_path = logs_path.join(HISTORY_FILE_NAME);
+    let file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&history_path);
+
+    match file {
+        Ok(mut file) => {
+            if let Err(err) = writeln!(file, "{}", line) {
+                eprintln!("Failed to write to the execution history file: {}", err);
+                return;
+            }
+        }
+        Err(err) => {
+            eprintln!("Failed to open the execution history file: {}", err);
+            return;
+        }
+    }
+
+    cap_history_file(&history_path, MAX_HISTORY_FILE_SIZE_BYTES);
+}
+
+fn cap_history_file(history_path: &Path, max_size_bytes: u64) {
+    let file_size = std::fs::metadata(history_path)
+        .map(|metadata| metadata.len())
+        .unwrap_or(0);
+    if file_size <= max_size_bytes {
+        return;
+    }
+
+    let content = match std::fs::read_to_string(history_path) {
+        Ok(content) => content,
+        Err(err) => {
+            eprintln!("Failed to read the execution history file: {}", err);
+            return;
+        }
+    };
+
+    // keep the newest lines, leaving room for the next records so we don't rewrite on every append
+    let mut kept_size = 0;
+    let kept_lines_count = content
+        .lines()
+        .rev()
+        .take_while(|line| {
+            kept_size += line.len() as u64 + 1;
+            kept_size <= max_size_bytes / 2
+        })
+        .count();
+    let all_lines_count = content.lines().count();
+    let mut kept_content = String::with_capacity(kept_size as usize);
+    for line in content.lines().skip(all_lines_count - kept_lines_count) {
+        kept_content.push_str(line);
+        kept_content.push('\n');
+    }
+
+    let temp_path = history_path.with_extension("jsonl.tmp");
+    let result = std::fs::write(&temp_path, kept_content)
+        .and_then(|_| std::fs::rename(&temp_path, history_path));
+    if let Err(err) = result {
+        eprintln!("Failed to trim the execution history file: {}", err);
+        let _ = std::fs::remove_file(&temp_path);
+    }
+}
+
+// returns the latest records, the most recent one is the last
+pub fn read_records(logs_path: &Path) -> Vec<ExecutionHistoryRecord> {
+    let Ok(file) = std::fs::File::open(logs_path.join(HISTORY_FILE_NAME)) else {
+        return Vec::new();
+    };
+
+    // an execution that got more scripts after finishing is recorded again, only the latest is kept
+    // in the position of the latest, so the slots of the earlier ones are emptied
+    let mut records: Vec<Option<ExecutionHistoryRecord>> = Vec::new();
+    let mut record_indexes: HashMap<PathBuf, usize> = HashMap::new();
+    for line in std::io::BufReader::new(file).lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let Some(record) = parse_record(&line) else {
+            continue;
+        };
+
+        if let Some(previous_idx) =
+            record_indexes.insert(record.log_directory.clone(), records.len())
+        {
+            records[previous_idx] = None;
+        }
+        records.push(Some(record));
+    }
+
+    let mut records: Vec<ExecutionHistoryRecord> = records.into_iter().flatten().collect();
+    if records.len() > MAX_LOADED_RECORDS {
+        records.drain(..records.len() - MAX_LOADED_RECORDS);
+    }
+
+    records
+}
+
+pub fn add_record_to_loaded(
+    records: &mut Vec<ExecutionHistoryRecord>,
+    record: ExecutionHistoryRecord,
+) {
+    records.retain(|existing| existing.log_directory != record.log_directory);
+    records.push(record);
+    if records.len() > MAX_LOADED_RECORDS {
+        records.remove(0);
+    }
+}
+
+fn parse_record(line: &str) -> Option<ExecutionHistoryRecord> {
+    let mut record_json: JsonValue = match serde_json::from_str(line) {
+        Ok(json) => json,
+        Err(err) => {
+            eprintln!("Skipping a broken execution history record: {}", err);
+            return None;
+        }
+    };
+
+    // script definitions are stored in the config format, so update them the same way as configs
+    if record_json["config_version"].as_str() != Some(LATEST_CONFIG_FORMAT_VERSION) {
+        let config_version = record_json["config_version"].take();
+        let scripts = record_json["scripts"].as_array_mut()?;
+        let mut config_json = json!({
+            "version": config_version,
+            "script_definitions": scripts
+                .iter_mut()
+                .map(|script| json!({"Original": script["script"].take()}))
+                .collect::<Vec<_>>(),
+        });
+
+        if let UpdateResult::Error(_) = update_config_to_the_latest_version(&mut config_json) {
+            eprintln!("Skipping an execution history record with unsupported config version");
+            return None;
+        }
+
+        let updated_scripts = config_json["script_definitions"].as_array_mut()?;
+        for (script, updated_script) in scripts.iter_mut().zip(updated_scripts.iter_mut()) {
+            script["script"] = updated_script["Original"].take();
+        }
+        record_json["config_version"] = json!(LATEST_CONFIG_FORMAT_VERSION);
+    }
+
+    match serde_json::from_value(record_json) {
+        Ok(record) => Some(record),
+        Err(err) => {
+            eprintln!("Skipping a broken execution history record: {}", err);
+            None
+        }
+    }
+}
+
+fn format_instant(instant: Instant) -> String {
+    let time = chrono::Duration::from_std(Instant::now().saturating_duration_since(instant))
+        .map(|elapsed| chrono::Local::now() - elapsed)
+        .unwrap_or_else(|_| chrono::Local::now());
+    time.format(TIME_FORMAT).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestDirectory;
+
+    fn make_test_record(log_directory: &str, script_name: &str) -> ExecutionHistoryRecord {
+        ExecutionHistoryRecord {
+            config_version: LATEST_CONFIG_FORMAT_VERSION.to_string(),
+            start_time: "2025-01-01 10:00:00".to_string(),
+            finish_time: "2025-01-01 10:01:00".to_string(),
+            log_directory: PathBuf::from(log_directory),
+            has_failed_scripts: false,
+            scripts: vec![ExecutedScriptRecord {
+                script: config::OriginalScriptDefinition {
+                    name: script_name.to_string(),
+                    ..Default::default()
+                },
+                result: ExecutedScriptResult::Success,
+                retry_count: 0,
+                start_time: None,
+                finish_time: None,
+                duration_sec: None,
+                log_paths: Vec::new(),
+                dependencies: None,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_appended_records_can_be_read_back_and_repeated_executions_are_deduplicated() {
+        let logs_path = TestDirectory::new("history_dedup");
+
+        append_record(&logs_path, &make_test_record("dir1", "first"));
+        append_record(&logs_path, &make_test_record("dir2", "second"));
+        append_record(&logs_path, &make_test_record("dir1", "first_continued"));
+
+        let records = read_records(&logs_path);
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].scripts[0].script.name, "second");
+        assert_eq!(records[1].scripts[0].script.name, "first_continued");
+    }
+
+    #[test]
+    fn test_records_with_old_config_version_get_script_definitions_updated() {
+        let logs_path = TestDirectory::new("history_update");
+
+        let mut record_json = serde_json::to_value(make_test_record("dir1", "old")).unwrap();
+        record_json["config_version"] = json!("1.2.4");
+        record_json["scripts"][0]["script"]
+            .as_object_mut()
+            .unwrap()
+            .remove("timeout_sec");
+        std::fs::write(
+            logs_path.join(HISTORY_FILE_NAME),
+            format!("{}\nnot a json\n", record_json),
+        )
+        .unwrap();
+
+        let records = read_records(&logs_path);
+
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].config_version, LATEST_CONFIG_FORMAT_VERSION);
+        assert_eq!(records[0].scripts[0].script.name, "old");
+        assert_eq!(records[0].scripts[0].script.timeout_sec, 0.0);
+    }
+
+    #[test]
+    fn test_history_file_is_trimmed_to_the_latest_records_when_too_large() {
+        let logs_path = TestDirectory::new("history_cap");
+
+        for idx in 0..10 {
+            append_record(
+                &logs_path,
+                &make_test_record(&format!("dir{}", idx), "script"),
+            );
+        }
+        let history_path = logs_path.join(HISTORY_FILE_NAME);
+        let record_size = std::fs::metadata(&history_path).unwrap().len() / 10;
+
+        // fits a bit more than four records in a half
+        cap_history_file(&history_path, record_size * 9);
+
+        let records = read_records(&logs_path);
+        assert_eq!(records.len(), 4);
+        assert_eq!(records[0].log_directory, PathBuf::from("dir6"));
+        assert_eq!(records[3].log_directory, PathBuf::from("dir9"));
+
+        // the file is small enough now, so it is not trimmed again
+        cap_history_file(&history_path, record_size * 9);
+        assert_eq!(read_records(&logs_path).len(), 4);
+    }
+}
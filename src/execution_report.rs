@@ -0,0 +1,279 @@
+// Copyright (C) Pavel Grebnev 2025
+// Distributed under the MIT License (license terms are at http://opensource.org/licenses/MIT).
+
+use serde::Serialize;
+use std::path::PathBuf;
+
+use crate::config;
+use crate::execution_history::{ExecutedScriptResult, ExecutionHistoryRecord};
+use crate::execution_thread;
+
+#[derive(Serialize)]
+struct ExecutionReport<'a> {
+    name: &'a str,
+    start_time: &'a str,
+    finish_time: &'a str,
+    log_directory: &'a PathBuf,
+    has_failed_scripts: bool,
+    scripts: Vec<ScriptReport<'a>>,
+}
+
+#[derive(Serialize)]
+struct ScriptReport<'a> {
+    name: &'a str,
+    uid: &'a config::Guid,
+    command: String,
+    result: ExecutedScriptResult,
+    retry_count: usize,
+    duration_sec: Option<f32>,
+    log_path: Option<&'a PathBuf>,
+}
+
+pub fn write_execution_report(
+    app_config: &config::AppConfig,
+    execution_name: &str,
+    record: &ExecutionHistoryRecord,
+) {
+    let (format, report_folder) = match &app_config.execution_report_override {
+        Some((format, report_folder)) => (*format, Some(report_folder.clone())),
+        None => {
+            let rewritable_config = config::get_main_rewritable_config(app_config);
+            (
+                rewritable_config.execution_report_format,
+                config::get_full_optional_path(
+                    &app_config.paths,
+                    &rewritable_config.execution_report_folder,
+                ),
+            )
+        }
+    };
+
+    let (content, extension) = match format {
+        config::ExecutionReportFormat::None => return,
+        config::ExecutionReportFormat::Json => (make_json_report(execution_name, record), "json"),
+        config::ExecutionReportFormat::JUnitXml => {
+            (make_junit_report(execution_name, record), "xml")
+        }
+    };
+
+    let report_path = match report_folder {
+        // reports from multiple executions can be collected in one folder, so name them uniquely
+        Some(report_folder) => {
+            let execution_folder_name = record
+                .log_directory
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            report_folder.join(format!("{}_report.{}", execution_folder_name, extension))
+        }
+        None => record
+            .log_directory
+            .join(format!("execution_report.{}", extension)),
+    };
+
+    if let Some(parent) = report_path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    if let Err(err) = std::fs::write(&report_path, content) {
+        eprintln!(
+            "Failed to write execution report to '{}': {}",
+            report_path.to_string_lossy(),
+            err
+        );
+    }
+}
+
+fn make_json_report(execution_name: &str, record: &ExecutionHistoryRecord) -> String {
+    let report = ExecutionReport {
+        name: execution_name,
+        start_time: &record.start_time,
+        finish_time: &record.finish_time,
+        log_directory: &record.log_directory,
+        has_failed_scripts: record.has_failed_scripts,
+        scripts: record
+            .scripts
+            .iter()
+            .map(|script| ScriptReport {
+                name: &script.script.name,
+                uid: &script.script.uid,
+                command: execution_thread::get_script_to_execute_description(&script.script),
+                result: script.result,
+                retry_count: script.retry_count,
+                duration_sec: script.duration_sec,
+                log_path: script.log_paths.last(),
+            })
+            .collect(),
+    };
+
+    serde_json::to_string_pretty(&report).unwrap_or_default()
+}
+
+fn make_junit_report(execution_name: &str, record: &ExecutionHistoryRecord) -> String {
+    let count_results = |result: ExecutedScriptResult| {
+        record
+            .scripts
+            .iter()
+            .filter(|script| script.result == result)
+            .count()
+    };
+    let failures = count_results(ExecutedScriptResult::Failed);
+    let skipped = count_results(ExecutedScriptResult::Skipped)
+        + count_results(ExecutedScriptResult::NotStarted);
+    let total_time: f32 = record
+        .scripts
+        .iter()
+        .filter_map(|script| script.duration_sec)
+        .sum();
+
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"scripter\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{:.3}\">\n",
+        record.scripts.len(),
+        failures,
+        skipped,
+        total_time
+    ));
+    xml.push_str(&format!(
+        "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{:.3}\" timestamp=\"{}\">\n",
+        escape_xml(execution_name),
+        record.scripts.len(),
+        failures,
+        skipped,
+        total_time,
+        escape_xml(&record.start_time.replacen(' ', "T", 1))
+    ));
+
+    for script in &record.scripts {
+        xml.push_str(&format!(
+            "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\">\n",
+            escape_xml(&script.script.name),
+            escape_xml(&script.script.uid.to_string()),
+            script.duration_sec.unwrap_or(0.0)
+        ));
+
+        match script.result {
+            ExecutedScriptResult::Failed => {
+                let message = if script.retry_count > 0 {
+                    format!("Failed after {} retries", script.retry_count)
+                } else {
+                    "Failed".to_string()
+                };
+                xml.push_str(&format!("      <failure message=\"{}\"/>\n", message));
+            }
+            ExecutedScriptResult::Skipped => {
+                xml.push_str("      <skipped/>\n");
+            }
+            ExecutedScriptResult::NotStarted => {
+                xml.push_str("      <skipped message=\"Not started\"/>\n");
+            }
+            ExecutedScriptResult::Warning => {
+                xml.push_str("      <system-err>Finished with a warning</system-err>\n");
+            }
+            ExecutedScriptResult::Success => {}
+        }
+
+        let mut system_out = format!(
+            "command: {}",
+            execution_thread::get_script_to_execute_description(&script.script)
+        );
+        if let Some(log_path) = script.log_paths.last() {
+            system_out.push_str(&format!("\nlog: {}", log_path.to_string_lossy()));
+        }
+        xml.push_str(&format!(
+            "      <system-out>{}</system-out>\n",
+            escape_xml(&system_out)
+        ));
+        xml.push_str("    </testcase>\n");
+    }
+
+    xml.push_str("  </testsuite>\n");
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&apos;"),
+            // control characters are not allowed in XML 1.0 even when escaped
+            c if (c as u32) < 0x20 && c != '\n' && c != '\r' && c != '\t' => {}
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution_history::ExecutedScriptRecord;
+
+    fn make_test_script(name: &str, result: ExecutedScriptResult) -> ExecutedScriptRecord {
+        ExecutedScriptRecord {
+            script: config::OriginalScriptDefinition {
+                name: name.to_string(),
+                ..Default::default()
+            },
+            result,
+            retry_count: 0,
+            start_time: None,
+            finish_time: None,
+            duration_sec: Some(1.5),
+            log_paths: vec![PathBuf::from("logs/1_output.log")],
+            dependencies: None,
+        }
+    }
+
+    fn make_test_record() -> ExecutionHistoryRecord {
+        ExecutionHistoryRecord {
+            config_version: String::new(),
+            start_time: "2025-01-01 10:00:00".to_string(),
+            finish_time: "2025-01-01 10:01:00".to_string(),
+            log_directory: PathBuf::from("logs"),
+            has_failed_scripts: true,
+            scripts: vec![
+                make_test_script("build <debug> & test", ExecutedScriptResult::Success),
+                make_test_script("deploy", ExecutedScriptResult::Failed),
+                make_test_script("notify", ExecutedScriptResult::Skipped),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_escape_xml_escapes_special_characters() {
+        assert_eq!(
+            escape_xml("a<b>&\"c\"'\u{1}"),
+            "a&lt;b&gt;&amp;&quot;c&quot;&apos;"
+        );
+    }
+
+    #[test]
+    fn test_junit_report_counts_results_and_escapes_names() {
+        let report = make_junit_report("Execution #1", &make_test_record());
+
+        assert!(report.contains(
+            "<testsuite name=\"Execution #1\" tests=\"3\" failures=\"1\" skipped=\"1\" time=\"4.500\" timestamp=\"2025-01-01T10:00:00\">"
+        ));
+        assert!(report.contains("<testcase name=\"build &lt;debug&gt; &amp; test\""));
+        assert_eq!(report.matches("<failure ").count(), 1);
+        assert_eq!(report.matches("<skipped/>").count(), 1);
+    }
+
+    #[test]
+    fn test_json_report_contains_every_script() {
+        let report = make_json_report("Execution #1", &make_test_record());
+        let report: serde_json::Value = serde_json::from_str(&report).unwrap();
+
+        assert_eq!(report["name"], "Execution #1");
+        assert_eq!(report["scripts"].as_array().unwrap().len(), 3);
+        assert_eq!(report["scripts"][1]["name"], "deploy");
+        assert_eq!(report["scripts"][1]["result"], "Failed");
+        assert_eq!(report["scripts"][1]["log_path"], "logs/1_output.log");
+    }
+}
@@ -1,38 +1,59 @@
 // Copyright (C) Pavel Grebnev 2023-2025
 // Distributed under the MIT License (license terms are at http://opensource.org/licenses/MIT).
 
+#[cfg(unix)]
+use std::os::unix::process::CommandExt as UnixCommandExt;
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
 
 use chrono;
-use crossbeam_channel::{unbounded, Receiver, RecvError, Sender};
+use crossbeam_channel::{unbounded, Receiver, RecvTimeoutError, Sender};
 use std::io::{BufRead, Write};
-use std::sync::atomic::AtomicU8;
+use std::sync::atomic::{AtomicBool, AtomicU8};
 use std::sync::{atomic::Ordering, Arc, Mutex};
 use std::time::{Duration, Instant};
 
+use crate::ansi_escapes;
 use crate::config;
 use crate::file_utils;
+use crate::log_index;
+use crate::log_patterns;
+use crate::resource_usage;
 use crate::ring_buffer::RingBuffer;
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum ScriptResultStatus {
     Success,
+    // finished with an exit code that is configured as a warning
+    Warning,
     Failed,
     Skipped,
     // the script was disconnected from the execution
     Disconnected,
 }
 
+// how an attempt to run a script ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScriptExitStatus {
+    pub exit_code: Option<i32>,
+    // the signal that terminated the process, only on unix
+    pub signal: Option<i32>,
+}
+
 #[derive(Clone)]
 pub struct ScriptExecutionStatus {
     pub start_time: Option<Instant>,
     pub finish_time: Option<Instant>,
     pub result: ScriptResultStatus,
     pub retry_count: usize,
+    // one for each attempt that started a process
+    pub exit_statuses: Vec<ScriptExitStatus>,
+    pub resource_usages: Vec<resource_usage::ResourceUsage>,
+    // the first lines of the current attempt that matched the log pattern rules
+    pub problems: Vec<log_patterns::LogProblem>,
 }
 
-#[derive(Default)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OutputType {
     #[default]
     StdOut,
@@ -46,6 +67,19 @@ pub struct OutputLine {
     pub text: String,
     pub output_type: OutputType,
     pub timestamp: chrono::DateTime<chrono::Local>,
+    // set for the lines of the script output that matched a log pattern rule
+    pub highlight: Option<config::LogPatternSeverity>,
+    // index of the script in the execution, set for the lines of the script output
+    pub script_idx: Option<usize>,
+}
+
+// a line typed by the user for a script that accepts input
+pub struct ScriptInput {
+    pub text: String,
+    // the line is sent as is, but is not shown in the logs (e.g. passwords)
+    pub is_secret: bool,
+    // the input of a headless run was closed, the text is ignored and the script reads end of file
+    pub is_end_of_input: bool,
 }
 
 #[derive(Clone)]
@@ -54,6 +88,11 @@ pub struct ExecutionScript {
     // there may be multiple original scripts with the same uid,
     // but we want to make a way to distinguish them, so we use this uid instead
     pub uid: config::Guid,
+    // uids of the scripts from the same list that need to finish before this one starts
+    // None means that the script waits for all the scripts before it
+    pub dependencies: Option<Vec<config::Guid>>,
+    // --env of a launch that was forwarded to this window, applied on top of the window's --env
+    pub launch_env_vars: Vec<(std::ffi::OsString, std::ffi::OsString)>,
 }
 
 impl ExecutionScript {
@@ -61,18 +100,37 @@ impl ExecutionScript {
         Self {
             original,
             uid: config::Guid::new(),
+            dependencies: None,
+            launch_env_vars: Vec::new(),
         }
     }
 }
 
 pub type LogBuffer = RingBuffer<OutputLine, 30>;
+// values that the scripts set for the scripts that run after them in the same execution
+pub type ScriptOutputs = std::collections::HashMap<String, String>;
+// a line in this format in the output of a script sets an output, e.g. "::set-output branch=feature/x"
+const SET_OUTPUT_PREFIX: &str = "::set-output ";
+// the path to a file where the script can write "NAME=value" lines to set outputs
+const OUTPUT_FILE_ENV_VAR: &str = "SCRIPTER_OUTPUT";
+// tell the scripts how the last of the scripts they waited for ended, e.g. for notifications
+const PREVIOUS_SCRIPT_NAME_ENV_VAR: &str = "SCRIPTER_PREVIOUS_SCRIPT_NAME";
+const PREVIOUS_EXIT_CODE_ENV_VAR: &str = "SCRIPTER_PREVIOUS_EXIT_CODE";
+const PREVIOUS_SIGNAL_ENV_VAR: &str = "SCRIPTER_PREVIOUS_SIGNAL";
 const REQUESTED_ACTION_NONE: u8 = 0;
 const REQUESTED_ACTION_STOP: u8 = 1;
 const REQUESTED_ACTION_DISCONNECT: u8 = 2;
+// how long the processes have to finish after being asked to terminate before they are killed
+const KILL_GRACE_PERIOD: Duration = Duration::from_secs(3);
+// the processes that the script left running can keep its output open,
+// so after the script exits its output is not waited for longer than this
+const OUTPUT_CLOSE_TIMEOUT: Duration = Duration::from_millis(500);
 
 pub struct ScriptExecutionData {
     pub scripts_to_run: Vec<ExecutionScript>,
     pub progress_receiver: Option<Receiver<(usize, ScriptExecutionStatus)>>,
+    // one for each script, so the input goes only to the script it was typed for
+    pub input_senders: Vec<Sender<ScriptInput>>,
     pub requested_action: Arc<AtomicU8>,
     pub thread_join_handle: Option<std::thread::JoinHandle<()>>,
 }
@@ -82,6 +140,7 @@ impl ScriptExecutionData {
         ScriptExecutionData {
             scripts_to_run: Vec::new(),
             progress_receiver: None,
+            input_senders: Vec::new(),
             requested_action: Arc::new(AtomicU8::new(0)),
             thread_join_handle: None,
         }
@@ -114,6 +173,18 @@ impl ScriptExecutionStatus {
         self.has_script_finished() && self.result == ScriptResultStatus::Failed
     }
 
+    pub fn get_exit_statuses_description(self: &ScriptExecutionStatus) -> String {
+        self.exit_statuses
+            .iter()
+            .map(ScriptExitStatus::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    pub fn has_script_finished_with_warning(self: &ScriptExecutionStatus) -> bool {
+        self.has_script_finished() && self.result == ScriptResultStatus::Warning
+    }
+
     pub fn has_script_been_skipped(self: &ScriptExecutionStatus) -> bool {
         self.has_script_finished() && self.result == ScriptResultStatus::Skipped
     }
@@ -123,421 +194,1422 @@ impl ScriptExecutionStatus {
     }
 }
 
+#[derive(Clone, Copy, PartialEq)]
+enum ScheduledScriptState {
+    Waiting,
+    Running,
+    // whether the scripts that depend on this one should treat it as a failure
+    Finished {
+        has_failed: bool,
+        result: ScriptResultStatus,
+        // the script that ran last before this one (or this one) and how it ended
+        last_exit_status: Option<(usize, ScriptExitStatus)>,
+    },
+}
+
+// the values shared between the threads of the scripts running at the same time
+struct ScriptRunContext {
+    scripts_to_run: Vec<ExecutionScript>,
+    log_directory: std::path::PathBuf,
+    first_script_idx: usize,
+    path_caches: config::PathCaches,
+    env_vars: Vec<(std::ffi::OsString, std::ffi::OsString)>,
+    echo_to_terminal: bool,
+    keep_escape_sequences_in_logs: bool,
+    log_file_format: config::LogFileFormat,
+    log_pattern_rules: Vec<config::LogPatternRule>,
+    recent_logs: Arc<Mutex<LogBuffer>>,
+    script_outputs: Arc<Mutex<ScriptOutputs>>,
+    progress_sender: Sender<(usize, ScriptExecutionStatus)>,
+    input_receivers: Vec<Receiver<ScriptInput>>,
+    execution_log_writer: Mutex<Option<std::io::BufWriter<std::fs::File>>>,
+    is_stop_requested: AtomicBool,
+}
+
+// what the thread reading the output of a script needs to know about it
+struct ScriptOutputContext {
+    script_idx: usize,
+    script_outputs: Arc<Mutex<ScriptOutputs>>,
+    problem_collector: Arc<log_patterns::LogProblemCollector>,
+}
+
 pub fn run_scripts(
     execution_data: &mut ScriptExecutionData,
     log_directory: &std::path::PathBuf,
     had_failures_before: bool,
     app_config: &config::AppConfig,
     recent_logs: Arc<Mutex<LogBuffer>>,
+    script_outputs: Arc<Mutex<ScriptOutputs>>,
     first_script_idx: usize,
 ) {
     let (progress_sender, process_receiver) = unbounded();
     execution_data.progress_receiver = Some(process_receiver);
-    let log_directory = log_directory.clone();
+    let (input_senders, input_receivers) = execution_data
+        .scripts_to_run
+        .iter()
+        .map(|_| unbounded::<ScriptInput>())
+        .unzip();
+    execution_data.input_senders = input_senders;
 
-    let scripts_to_run = execution_data.scripts_to_run.clone();
     let requested_action = execution_data.requested_action.clone();
-    let path_caches = app_config.paths.clone();
-    let env_vars = app_config.env_vars.clone();
+    let parallel_scripts_limit =
+        config::get_main_rewritable_config(app_config).parallel_scripts_limit;
+
+    let _ = std::fs::create_dir_all(log_directory);
+
+    let context = Arc::new(ScriptRunContext {
+        scripts_to_run: execution_data.scripts_to_run.clone(),
+        log_directory: log_directory.clone(),
+        first_script_idx,
+        path_caches: app_config.paths.clone(),
+        env_vars: app_config.env_vars.clone(),
+        echo_to_terminal: app_config.is_headless,
+        keep_escape_sequences_in_logs: config::get_main_rewritable_config(app_config)
+            .keep_escape_sequences_in_logs,
+        log_file_format: config::get_main_rewritable_config(app_config).log_file_format,
+        log_pattern_rules: config::get_main_rewritable_config(app_config)
+            .log_pattern_rules
+            .clone(),
+        recent_logs,
+        script_outputs,
+        progress_sender,
+        input_receivers,
+        execution_log_writer: Mutex::new(create_or_append_to_file(
+            log_directory.join("execution_steps.log"),
+        )),
+        is_stop_requested: AtomicBool::new(false),
+    });
 
     execution_data.thread_join_handle = Some(std::thread::spawn(move || {
-        let mut has_previous_script_failed = had_failures_before;
-        let mut kill_requested = false;
+        let dependencies = resolve_dependencies(&context.scripts_to_run);
+        let mut script_states = vec![ScheduledScriptState::Waiting; context.scripts_to_run.len()];
+        // the scripts starting from this index were disconnected from the execution
+        let mut connected_scripts_count = context.scripts_to_run.len();
+        let mut running_scripts_count = 0;
+        let mut script_threads = Vec::new();
+        let (finish_sender, finish_receiver) = unbounded::<(usize, ScheduledScriptState)>();
+
+        loop {
+            let requested_action_raw =
+                requested_action.swap(REQUESTED_ACTION_NONE, Ordering::AcqRel);
+            if requested_action_raw == REQUESTED_ACTION_STOP {
+                context.is_stop_requested.store(true, Ordering::Release);
+                write_to_execution_log(&context.execution_log_writer, "Requested execution stop");
+            } else if requested_action_raw == REQUESTED_ACTION_DISCONNECT {
+                // only the scripts after the last started one are disconnected,
+                // the rest of the waiting scripts still run as a part of this execution
+                let first_disconnected_script_idx = script_states[..connected_scripts_count]
+                    .iter()
+                    .rposition(|state| *state != ScheduledScriptState::Waiting)
+                    .map_or(0, |script_idx| script_idx + 1);
+                send_non_executed_disconnect_statuses(
+                    &context.progress_sender,
+                    first_disconnected_script_idx,
+                    connected_scripts_count,
+                );
+                connected_scripts_count = first_disconnected_script_idx;
+            }
 
-        let _ = std::fs::create_dir_all(&log_directory);
+            // skipping a script can unblock other scripts, so repeat until nothing changes
+            let mut has_state_changed = true;
+            while has_state_changed {
+                has_state_changed = false;
+                for script_idx in 0..connected_scripts_count {
+                    if script_states[script_idx] != ScheduledScriptState::Waiting {
+                        continue;
+                    }
 
-        let mut execution_log_writer =
-            create_or_append_to_file(log_directory.join("execution_steps.log"));
+                    let mut has_previous_script_failed = false;
+                    let mut previous_exit_status = None;
+                    let mut has_connected_dependencies = false;
+                    let mut are_dependencies_finished = true;
+                    for dependency_idx in &dependencies[script_idx] {
+                        if *dependency_idx >= connected_scripts_count {
+                            continue;
+                        }
+                        has_connected_dependencies = true;
+                        match script_states[*dependency_idx] {
+                            ScheduledScriptState::Finished {
+                                has_failed,
+                                last_exit_status,
+                                ..
+                            } => {
+                                has_previous_script_failed |= has_failed;
+                                previous_exit_status = last_exit_status.or(previous_exit_status);
+                            }
+                            _ => are_dependencies_finished = false,
+                        }
+                    }
+                    if !are_dependencies_finished {
+                        continue;
+                    }
+                    if !has_connected_dependencies {
+                        has_previous_script_failed = had_failures_before;
+                    }
 
-        for script_idx in 0..scripts_to_run.len() {
-            let mut disconnect_requested = false;
-            let script = &scripts_to_run[script_idx].original;
-            let mut script_state = get_default_script_execution_status();
-            script_state.start_time = Some(Instant::now());
+                    let script = &context.scripts_to_run[script_idx].original;
+                    if context.is_stop_requested.load(Ordering::Acquire)
+                        || (has_previous_script_failed
+                            && should_skip_script_on_failure(script.reaction_to_previous_failures))
+                        || (!has_previous_script_failed
+                            && should_skip_script_on_success(script.reaction_to_previous_failures))
+                    {
+                        skip_script(&context, script_idx, "skipped");
+                        script_states[script_idx] = ScheduledScriptState::Finished {
+                            has_failed: has_previous_script_failed,
+                            result: ScriptResultStatus::Skipped,
+                            last_exit_status: previous_exit_status,
+                        };
+                        has_state_changed = true;
+                        continue;
+                    }
 
-            if kill_requested
-                || (has_previous_script_failed
-                    && should_skip_script_on_failure(script.reaction_to_previous_failures))
-                || (!has_previous_script_failed
-                    && should_skip_script_on_success(script.reaction_to_previous_failures))
+                    if let Some(unknown_script_name) =
+                        find_unknown_run_condition_script(&context.scripts_to_run, script_idx)
+                    {
+                        fail_script(
+                            &context,
+                            script_idx,
+                            &format!(
+                                "has a run condition for '{}' that is not a part of the execution",
+                                unknown_script_name
+                            ),
+                        );
+                        script_states[script_idx] = ScheduledScriptState::Finished {
+                            has_failed: true,
+                            result: ScriptResultStatus::Failed,
+                            last_exit_status: previous_exit_status,
+                        };
+                        has_state_changed = true;
+                        continue;
+                    }
+
+                    if let Some(unmet_condition) = find_unmet_run_condition(
+                        &context,
+                        script_idx,
+                        &script_states[..connected_scripts_count],
+                    ) {
+                        skip_script(
+                            &context,
+                            script_idx,
+                            &format!("skipped because {}", unmet_condition),
+                        );
+                        script_states[script_idx] = ScheduledScriptState::Finished {
+                            has_failed: has_previous_script_failed,
+                            result: ScriptResultStatus::Skipped,
+                            last_exit_status: previous_exit_status,
+                        };
+                        has_state_changed = true;
+                        continue;
+                    }
+
+                    if parallel_scripts_limit > 0 && running_scripts_count >= parallel_scripts_limit
+                    {
+                        continue;
+                    }
+
+                    script_states[script_idx] = ScheduledScriptState::Running;
+                    running_scripts_count += 1;
+                    let context = context.clone();
+                    let finish_sender = finish_sender.clone();
+                    script_threads.push(std::thread::spawn(move || {
+                        let script = &context.scripts_to_run[script_idx].original;
+                        let script_state = run_script(&context, script_idx, previous_exit_status);
+                        let result = script_state.result;
+                        let has_failed = result == ScriptResultStatus::Failed
+                            || (has_previous_script_failed
+                                && !(matches!(
+                                    result,
+                                    ScriptResultStatus::Success | ScriptResultStatus::Warning
+                                ) && should_turn_failure_to_success(
+                                    script.reaction_to_previous_failures,
+                                )));
+                        let last_exit_status = script_state
+                            .exit_statuses
+                            .last()
+                            .map(|exit_status| (script_idx, *exit_status))
+                            .or(previous_exit_status);
+                        let _ = finish_sender.send((
+                            script_idx,
+                            ScheduledScriptState::Finished {
+                                has_failed,
+                                result,
+                                last_exit_status,
+                            },
+                        ));
+                    }));
+                }
+            }
+
+            if running_scripts_count == 0 {
+                // nothing can start anymore, the waiting scripts (if any) depend on each other in a loop
+                for (script_idx, script_state) in
+                    script_states[..connected_scripts_count].iter().enumerate()
+                {
+                    if *script_state == ScheduledScriptState::Waiting {
+                        skip_script(
+                            &context,
+                            script_idx,
+                            "skipped because of circular dependencies",
+                        );
+                    }
+                }
+                break;
+            }
+
+            if let Ok((script_idx, finished_state)) =
+                finish_receiver.recv_timeout(Duration::from_millis(100))
             {
-                script_state.result = ScriptResultStatus::Skipped;
-                script_state.finish_time = Some(Instant::now());
-                send_script_execution_status(&progress_sender, script_idx, script_state.clone());
+                script_states[script_idx] = finished_state;
+                running_scripts_count -= 1;
+            }
+        }
 
-                write_to_execution_log(
-                    &mut execution_log_writer,
-                    &format!("'{}' skipped", script.name),
+        join_threads(script_threads);
+    }));
+}
+
+// runs one script with all its retries, sends the progress and returns the final status
+fn run_script(
+    context: &ScriptRunContext,
+    script_idx: usize,
+    previous_exit_status: Option<(usize, ScriptExitStatus)>,
+) -> ScriptExecutionStatus {
+    let script = &context.scripts_to_run[script_idx].original;
+    let recent_logs = &context.recent_logs;
+    let echo_to_terminal = context.echo_to_terminal;
+    let input_receiver = &context.input_receivers[script_idx];
+
+    let mut script_state = get_default_script_execution_status();
+    script_state.start_time = Some(Instant::now());
+    send_script_execution_status(&context.progress_sender, script_idx, script_state.clone());
+    write_to_execution_log(
+        &context.execution_log_writer,
+        &format!(
+            "'{}' started as {}",
+            script.name,
+            get_script_to_execute_description(script)
+        ),
+    );
+
+    let (log_pattern_matcher, log_pattern_errors) = log_patterns::LogPatternMatcher::new(
+        script
+            .log_pattern_rules
+            .iter()
+            .chain(&context.log_pattern_rules),
+    );
+    for error in log_pattern_errors {
+        push_log_line(
+            recent_logs,
+            create_own_log_line(error, OutputType::Error),
+            echo_to_terminal,
+        );
+    }
+
+    let result = 'retry_loop: loop {
+        // the outputs are applied to every attempt, they could be set by the scripts running in parallel
+        let script_outputs = context.script_outputs.lock().unwrap().clone(); // it is fine to panic on a poisoned mutex
+        let script = match apply_script_outputs(script, &script_outputs) {
+            Ok(script) => script,
+            Err(error) => {
+                push_log_line(
+                    recent_logs,
+                    create_own_log_line(error, OutputType::Error),
+                    echo_to_terminal,
                 );
+                break 'retry_loop ScriptResultStatus::Failed;
+            }
+        };
+        let script = &script;
 
-                continue;
+        let command_line = get_script_with_arguments(script, &context.path_caches);
+
+        let script_output_path = file_utils::get_script_output_path(
+            context.log_directory.clone(),
+            &script.name,
+            (context.first_script_idx + script_idx) as isize,
+            script_state.retry_count,
+        );
+        let outputs_file_path = script_output_path.with_extension("outputs");
+
+        // shared with the output reading threads, we also write our own messages there
+        let output_writer = log_index::ScriptOutputWriter::create(
+            &script_output_path,
+            context.keep_escape_sequences_in_logs,
+            context.log_file_format,
+            script_state.retry_count,
+        )
+        .map(|output_writer| Arc::new(Mutex::new(output_writer)));
+
+        let (stdout_type, stderr_type) = if output_writer.is_ok() && !script.ignore_output {
+            (std::process::Stdio::piped(), std::process::Stdio::piped())
+        } else {
+            (std::process::Stdio::null(), std::process::Stdio::null())
+        };
+
+        // in headless mode the runner forwards the lines typed in the terminal, the script can't
+        // read the terminal itself since it is not in the foreground process group
+        let stdin_type = if script.accept_input {
+            std::process::Stdio::piped()
+        } else {
+            std::process::Stdio::null()
+        };
+
+        let executor_arguments = {
+            let mut executor_arguments = script.executor_arguments.clone();
+            for argument in &mut executor_arguments {
+                replace_placeholders(argument, &script.argument_placeholders);
             }
-            send_script_execution_status(&progress_sender, script_idx, script_state.clone());
-            write_to_execution_log(
-                &mut execution_log_writer,
-                &format!(
-                    "'{}' started as {}",
-                    script.name,
-                    get_script_to_execute_description(&script)
+            executor_arguments
+        };
+
+        let executor = script
+            .custom_executor
+            .clone()
+            .unwrap_or(config::get_default_executor());
+
+        let env_vars = match get_script_env_vars(
+            script,
+            &context.env_vars,
+            &context.scripts_to_run[script_idx].launch_env_vars,
+            &context.path_caches,
+        ) {
+            Ok(env_vars) => env_vars,
+            Err(error) => {
+                push_log_line(
+                    recent_logs,
+                    create_own_log_line(error, OutputType::Error),
+                    echo_to_terminal,
+                );
+                break 'retry_loop ScriptResultStatus::Failed;
+            }
+        };
+
+        if executor.is_empty() {
+            push_log_line(
+                recent_logs,
+                create_own_log_line(
+                    "Empty executor is not supported".to_string(),
+                    OutputType::Error,
                 ),
+                echo_to_terminal,
             );
+            break 'retry_loop ScriptResultStatus::Failed;
+        }
 
-            'retry_loop: loop {
-                if kill_requested {
-                    break;
+        let line = create_own_log_line(
+            format!(
+                "Running \"{}\"{}\n[{}][{}]{}{}",
+                script.name,
+                if script_state.retry_count > 0 {
+                    format!(" retry #{}", script_state.retry_count)
+                } else {
+                    "".to_string()
+                },
+                executor.join("]["),
+                command_line,
+                if !executor_arguments.is_empty() {
+                    format!("[{}]", executor_arguments.join("]["))
+                } else {
+                    "".to_string()
+                },
+                if env_vars.is_empty() {
+                    "".to_string()
+                } else {
+                    format!(
+                        " env: {}",
+                        env_vars
+                            .iter()
+                            .map(|(k, v)| format!(
+                                "{}={}",
+                                k.to_string_lossy(),
+                                v.to_string_lossy()
+                            ))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    )
                 }
+            ),
+            OutputType::Event,
+        );
+        push_log_line(recent_logs, line, echo_to_terminal);
 
-                let command_line = get_script_with_arguments(&script, &path_caches);
+        let mut command = std::process::Command::new(&executor[0]);
 
-                let output_file = std::fs::File::create(file_utils::get_script_output_path(
-                    log_directory.clone(),
-                    &script.name,
-                    (first_script_idx + script_idx) as isize,
-                    script_state.retry_count,
-                ));
+        command.args(&executor[1..]);
 
-                let (stdout_type, stderr_type) = if output_file.is_ok() && !script.ignore_output {
-                    (std::process::Stdio::piped(), std::process::Stdio::piped())
-                } else {
-                    (std::process::Stdio::null(), std::process::Stdio::null())
-                };
+        #[cfg(target_os = "windows")]
+        command.creation_flags(0x08000000); // CREATE_NO_WINDOW
 
-                let executor_arguments = {
-                    let mut executor_arguments = script.executor_arguments.clone();
-                    for argument in &mut executor_arguments {
-                        replace_placeholders(argument, &script.argument_placeholders);
-                    }
-                    executor_arguments
-                };
+        run_in_own_process_group(&mut command);
 
-                let executor = script
-                    .custom_executor
-                    .clone()
-                    .unwrap_or(config::get_default_executor());
+        command
+            .arg(command_line)
+            .envs(env_vars.iter().cloned())
+            .env(OUTPUT_FILE_ENV_VAR, &outputs_file_path)
+            .stdin(stdin_type)
+            .stdout(stdout_type)
+            .stderr(stderr_type);
 
-                {
-                    let mut recent_logs = recent_logs.lock().unwrap(); // it is fine to panic on a poisoned mutex
-
-                    if executor.is_empty() {
-                        recent_logs.push(OutputLine {
-                            text: "Empty executor is not supported".to_string(),
-                            output_type: OutputType::Error,
-                            timestamp: chrono::Local::now(),
-                        });
-                        script_state.result = ScriptResultStatus::Failed;
-                        script_state.finish_time = Some(Instant::now());
-                        send_script_execution_status(
-                            &progress_sender,
-                            script_idx,
-                            script_state.clone(),
-                        );
-                        has_previous_script_failed = true;
-                        break 'retry_loop;
-                    }
+        if let Some((previous_script_idx, previous_exit_status)) = previous_exit_status {
+            command.env(
+                PREVIOUS_SCRIPT_NAME_ENV_VAR,
+                &context.scripts_to_run[previous_script_idx].original.name,
+            );
+            if let Some(exit_code) = previous_exit_status.exit_code {
+                command.env(PREVIOUS_EXIT_CODE_ENV_VAR, exit_code.to_string());
+            }
+            if let Some(signal) = previous_exit_status.signal {
+                command.env(PREVIOUS_SIGNAL_ENV_VAR, signal.to_string());
+            }
+        }
 
-                    recent_logs.push(OutputLine {
-                        text: format!(
-                            "Running \"{}\"{}\n[{}][{}]{}{}",
-                            script.name,
-                            if script_state.retry_count > 0 {
-                                format!(" retry #{}", script_state.retry_count)
-                            } else {
-                                "".to_string()
-                            },
-                            executor.join("]["),
-                            command_line,
-                            if !executor_arguments.is_empty() {
-                                format!("[{}]", executor_arguments.join("]["))
-                            } else {
-                                "".to_string()
-                            },
-                            if env_vars.is_empty() {
-                                "".to_string()
-                            } else {
-                                format!(
-                                    " env: {}",
-                                    env_vars
-                                        .iter()
-                                        .map(|(k, v)| format!(
-                                            "{}={}",
-                                            k.to_string_lossy(),
-                                            v.to_string_lossy()
-                                        ))
-                                        .collect::<Vec<_>>()
-                                        .join(", ")
-                                )
-                            }
-                        ),
-                        output_type: OutputType::Event,
-                        timestamp: chrono::Local::now(),
-                    });
-                }
+        if !executor_arguments.is_empty() {
+            for argument in &executor_arguments {
+                command.arg(argument);
+            }
+        }
 
-                let mut command = std::process::Command::new(&executor[0]);
+        match script.working_directory.path_type {
+            config::PathType::ScripterExecutableRelative => {
+                command.current_dir(
+                    context
+                        .path_caches
+                        .exe_folder_path
+                        .join(&script.working_directory.path),
+                );
+            }
+            config::PathType::WorkingDirRelative => {
+                command.current_dir(&script.working_directory.path);
+            }
+        }
 
-                for i in 1..executor.len() {
-                    command.arg(&executor[i]);
+        // the script appends to the file, so it should exist and be empty
+        let _ = std::fs::write(&outputs_file_path, "");
+
+        let child = command.spawn();
+
+        // avoid potential deadlocks (cargo culted from os_pipe readme)
+        drop(command);
+
+        let mut child = match child {
+            Ok(child) => child,
+            Err(err) => {
+                if let Ok(output_writer) = &output_writer {
+                    let error_text = format!("Failed to start the process: {}", err);
+                    send_log_line(
+                        output_writer,
+                        recent_logs,
+                        create_own_log_line(error_text, OutputType::Error),
+                        echo_to_terminal,
+                    );
                 }
+                write_to_execution_log(
+                    &context.execution_log_writer,
+                    &format!("'{}' failed to start the process: {}", script.name, err,),
+                );
+                // it doesn't make sense to retry if something is broken on this level
+                break 'retry_loop ScriptResultStatus::Failed;
+            }
+        };
 
-                #[cfg(target_os = "windows")]
-                command.creation_flags(0x08000000); // CREATE_NO_WINDOW
+        script_state.problems.clear();
+        let problem_collector = Arc::new(log_patterns::LogProblemCollector::new(
+            log_pattern_matcher.clone(),
+        ));
 
-                command
-                    .arg(command_line)
-                    .envs(env_vars.clone())
-                    .stdin(std::process::Stdio::null())
-                    .stdout(stdout_type)
-                    .stderr(stderr_type);
+        let mut resource_usage_sampler = resource_usage::ResourceUsageSampler::new(&child);
 
-                if !executor_arguments.is_empty() {
-                    for argument in &executor_arguments {
-                        command.arg(argument);
-                    }
+        let mut child_stdin = child.stdin.take();
+        // whatever was typed before the script started was not meant for it,
+        // unless it was typed ahead in the terminal
+        if child_stdin.is_some() && !echo_to_terminal {
+            while input_receiver.try_recv().is_ok() {}
+        }
+
+        let mut output_reading_threads = None;
+
+        if !script.ignore_output {
+            match (child.stdout.take(), child.stderr.take(), &output_writer) {
+                (Some(stdout), Some(stderr), Ok(output_writer)) => {
+                    output_reading_threads = Some(join_and_split_output(
+                        stdout,
+                        stderr,
+                        recent_logs.clone(),
+                        output_writer.clone(),
+                        ScriptOutputContext {
+                            script_idx: context.first_script_idx + script_idx,
+                            script_outputs: context.script_outputs.clone(),
+                            problem_collector: problem_collector.clone(),
+                        },
+                        echo_to_terminal,
+                    ));
+                }
+                _ => {
+                    println!("Failed to redirect stdout/stderr. No diagnostic is provided for now");
                 }
+            }
+        }
 
-                match script.working_directory.path_type {
-                    config::PathType::ScripterExecutableRelative => {
-                        command.current_dir(
-                            &path_caches
-                                .exe_folder_path
-                                .join(&script.working_directory.path),
-                        );
-                    }
-                    config::PathType::WorkingDirRelative => {
-                        command.current_dir(&script.working_directory.path);
+        let attempt_start_time = Instant::now();
+        // set when the script was stopped or timed out
+        let mut process_stopper: Option<ProcessStopper> = None;
+        let mut exit_status = None;
+
+        let mut exit_code_result = loop {
+            if let Some(stdin) = &mut child_stdin {
+                while let Ok(input) = input_receiver.try_recv() {
+                    if input.is_end_of_input {
+                        // closing the pipe lets the script read end of file
+                        child_stdin = None;
+                        break;
                     }
+                    let write_result =
+                        writeln!(stdin, "{}", input.text).and_then(|_| stdin.flush());
+                    let (text, output_type) = match write_result {
+                        Ok(_) if input.is_secret => ("> ********".to_string(), OutputType::Event),
+                        Ok(_) => (format!("> {}", input.text), OutputType::Event),
+                        Err(err) => (
+                            format!("Failed to send input to the script: {}", err),
+                            OutputType::Error,
+                        ),
+                    };
+                    // the terminal has already shown what was typed in it
+                    let should_echo = echo_to_terminal && output_type == OutputType::Error;
+                    let line = create_own_log_line(text, output_type);
+                    send_own_log_line(&output_writer, recent_logs, line, should_echo);
                 }
+            }
 
-                let child = command.spawn();
-
-                // avoid potential deadlocks (cargo culted from os_pipe readme)
-                drop(command);
-
-                let mut child = match child {
-                    Ok(child) => child,
-                    Err(err) => {
-                        if let Ok(output_file) = output_file {
-                            let error_text = format!("Failed to start the process: {}", err);
-                            let mut output_writer = std::io::BufWriter::new(output_file);
-                            send_log_line(
-                                &mut output_writer,
-                                &recent_logs,
-                                OutputLine {
-                                    text: error_text,
-                                    output_type: OutputType::Error,
-                                    timestamp: chrono::Local::now(),
-                                },
-                            );
-                        }
-                        write_to_execution_log(
-                            &mut execution_log_writer,
-                            &format!("'{}' failed to start the process: {}", script.name, err,),
-                        );
-                        // it doesn't make sense to retry if something is broken on this level
-                        script_state.result = ScriptResultStatus::Failed;
-                        script_state.finish_time = Some(Instant::now());
-                        send_script_execution_status(
-                            &progress_sender,
-                            script_idx,
-                            script_state.clone(),
-                        );
-                        has_previous_script_failed = true;
-                        break 'retry_loop;
-                    }
-                };
+            resource_usage_sampler.sample();
 
-                let mut threads_to_join = Vec::new();
-
-                if !script.ignore_output {
-                    match (child.stdout.take(), child.stderr.take(), output_file) {
-                        (Some(stdout), Some(stderr), Ok(output_file)) => {
-                            threads_to_join = join_and_split_output(
-                                stdout,
-                                stderr,
-                                recent_logs.clone(),
-                                output_file,
-                            );
-                        }
-                        _ => {
-                            println!(
-                                "Failed to redirect stdout/stderr. No diagnostic is provided for now"
-                            );
-                        }
-                    }
+            // show the problems while the script is still running
+            if problem_collector.get_problems_count() != script_state.problems.len() {
+                script_state.problems = problem_collector.get_problems();
+                send_script_execution_status(
+                    &context.progress_sender,
+                    script_idx,
+                    script_state.clone(),
+                );
+            }
+
+            if let Some(process_stopper) = &process_stopper {
+                // a stopped script can't be successful, even if it returned an exit code that is
+                if process_stopper.update(&mut child) {
+                    break config::ExitCodeResult::Failure;
                 }
+            } else if let Ok(Some(status)) = child.try_wait() {
+                exit_status = Some(ScriptExitStatus::from_exit_status(status));
+                break get_exit_code_result(script, status.code());
+            }
 
-                'script_wait_loop: loop {
-                    if let Ok(Some(status)) = child.try_wait() {
-                        if status.success() {
-                            // successfully finished the script, jump to the next script
-                            script_state.finish_time = Some(Instant::now());
-                            script_state.result = ScriptResultStatus::Success;
-                            send_script_execution_status(
-                                &progress_sender,
-                                script_idx,
-                                script_state.clone(),
-                            );
-                            if has_previous_script_failed
-                                && should_turn_failure_to_success(
-                                    script.reaction_to_previous_failures,
-                                )
-                            {
-                                has_previous_script_failed = false;
-                            }
-                            join_threads(threads_to_join);
-                            write_to_execution_log(
-                                &mut execution_log_writer,
-                                &format!("'{}' finished successfully", script.name),
-                            );
-                            break 'retry_loop;
-                        } else {
-                            if script_state.retry_count < script.autorerun_count && !kill_requested
-                            {
-                                // script failed, but we can retry
-                                script_state.retry_count += 1;
-                                send_script_execution_status(
-                                    &progress_sender,
-                                    script_idx,
-                                    script_state.clone(),
-                                );
-                                write_to_execution_log(
-                                    &mut execution_log_writer,
-                                    &format!(
-                                        "'{}' failed, retrying (retry {} out of {}{})",
-                                        script.name,
-                                        script_state.retry_count,
-                                        script.autorerun_count,
-                                        if script.autorerun_delay_sec > 0.0 {
-                                            format!(", with {}s delay", script.autorerun_delay_sec)
-                                        } else {
-                                            String::new()
-                                        }
-                                    ),
-                                );
-
-                                if script.autorerun_delay_sec > 0.0 {
-                                    let end_time = Instant::now()
-                                        + Duration::from_secs_f32(script.autorerun_delay_sec);
-                                    let mut time_left = end_time - Instant::now();
-
-                                    // sleep in periods of one second, to be able to listen to the stop signal
-                                    while time_left > Duration::from_secs(1) {
-                                        std::thread::sleep(Duration::from_secs(1));
-                                        time_left = end_time - Instant::now();
-                                        let requested_action_raw =
-                                            requested_action.load(Ordering::Acquire);
-                                        if requested_action_raw > 0 {
-                                            break 'script_wait_loop;
-                                        }
-                                    }
-
-                                    // sleep the reminder of time
-                                    if time_left > Duration::from_secs(0) {
-                                        std::thread::sleep(time_left);
-                                    }
-                                }
-                                break;
-                            } else {
-                                // script failed and we can't retry
-                                script_state.finish_time = Some(Instant::now());
-                                script_state.result = ScriptResultStatus::Failed;
-                                send_script_execution_status(
-                                    &progress_sender,
-                                    script_idx,
-                                    script_state.clone(),
-                                );
-                                has_previous_script_failed = true;
-                                join_threads(threads_to_join);
-                                write_to_execution_log(
-                                    &mut execution_log_writer,
-                                    &format!("'{}' failed", script.name),
-                                );
-                                break 'retry_loop;
-                            }
-                        }
-                    }
+            if process_stopper.is_none()
+                && script.timeout_sec > 0.0
+                && attempt_start_time.elapsed().as_secs_f32() >= script.timeout_sec
+            {
+                // the attempt is treated as a failure, so the retry logic below applies
+                process_stopper = Some(ProcessStopper::start(
+                    &mut child,
+                    script.keep_child_processes,
+                ));
+                let line = create_own_log_line(
+                    format!(
+                        "\"{}\" timed out after {}s",
+                        script.name, script.timeout_sec
+                    ),
+                    OutputType::Error,
+                );
+                send_own_log_line(&output_writer, recent_logs, line, echo_to_terminal);
+                write_to_execution_log(
+                    &context.execution_log_writer,
+                    &format!("'{}' timed out after {}s", script.name, script.timeout_sec),
+                );
+            }
+
+            if process_stopper.is_none() && context.is_stop_requested.load(Ordering::Acquire) {
+                process_stopper = Some(ProcessStopper::start(
+                    &mut child,
+                    script.keep_child_processes,
+                ));
+            }
+
+            std::thread::sleep(Duration::from_millis(100));
+        };
+
+        let resource_usage = resource_usage_sampler.get_usage();
+        script_state.resource_usages.push(resource_usage);
+
+        // the process could have been killed after a timeout, it is already waited for by then
+        if exit_status.is_none() {
+            if let Ok(Some(status)) = child.try_wait() {
+                exit_status = Some(ScriptExitStatus::from_exit_status(status));
+            }
+        }
+
+        if let Some(output_reading_threads) = output_reading_threads {
+            finish_reading_output(output_reading_threads);
+        }
+        script_state.problems = problem_collector.get_problems();
+
+        if let Some(exit_status) = exit_status {
+            script_state.exit_statuses.push(exit_status);
+            write_to_execution_log(
+                &context.execution_log_writer,
+                &format!("'{}' exited with {}", script.name, exit_status),
+            );
+        }
+        write_to_execution_log(
+            &context.execution_log_writer,
+            &format!("'{}' used {}", script.name, resource_usage),
+        );
+
+        read_outputs_file(
+            &outputs_file_path,
+            &context.script_outputs,
+            &output_writer,
+            recent_logs,
+            echo_to_terminal,
+        );
+
+        if let Some(exit_code) = exit_status.and_then(|exit_status| exit_status.exit_code) {
+            if exit_code_result != get_exit_code_result_by_default(exit_code) {
+                let line = create_own_log_line(
+                    format!(
+                        "Exit code {} is treated as {}",
+                        exit_code,
+                        get_exit_code_result_description(exit_code_result)
+                    ),
+                    OutputType::Event,
+                );
+                send_own_log_line(&output_writer, recent_logs, line, echo_to_terminal);
+            }
+        }
+
+        if let Some(failing_pattern) = problem_collector.get_failing_pattern() {
+            if exit_code_result == config::ExitCodeResult::Success
+                || exit_code_result == config::ExitCodeResult::Warning
+            {
+                exit_code_result = config::ExitCodeResult::Failure;
+                let line = create_own_log_line(
+                    format!(
+                        "The output matched \"{}\", the script is treated as failed",
+                        failing_pattern
+                    ),
+                    OutputType::Error,
+                );
+                send_own_log_line(&output_writer, recent_logs, line, echo_to_terminal);
+                write_to_execution_log(
+                    &context.execution_log_writer,
+                    &format!(
+                        "'{}' output matched \"{}\" that fails the script",
+                        script.name, failing_pattern
+                    ),
+                );
+            }
+        }
+
+        match exit_code_result {
+            config::ExitCodeResult::Success => {
+                write_to_execution_log(
+                    &context.execution_log_writer,
+                    &format!("'{}' finished successfully", script.name),
+                );
+                break 'retry_loop ScriptResultStatus::Success;
+            }
+            config::ExitCodeResult::Warning => {
+                write_to_execution_log(
+                    &context.execution_log_writer,
+                    &format!("'{}' finished with a warning", script.name),
+                );
+                break 'retry_loop ScriptResultStatus::Warning;
+            }
+            config::ExitCodeResult::Skipped => {
+                write_to_execution_log(
+                    &context.execution_log_writer,
+                    &format!("'{}' finished with an exit code that skips it", script.name),
+                );
+                break 'retry_loop ScriptResultStatus::Skipped;
+            }
+            config::ExitCodeResult::Failure => {}
+        }
+
+        if script_state.retry_count >= script.autorerun_count
+            || context.is_stop_requested.load(Ordering::Acquire)
+        {
+            // script failed and we can't retry
+            write_to_execution_log(
+                &context.execution_log_writer,
+                &format!("'{}' failed", script.name),
+            );
+            break 'retry_loop ScriptResultStatus::Failed;
+        }
+
+        // script failed, but we can retry
+        script_state.retry_count += 1;
+        send_script_execution_status(&context.progress_sender, script_idx, script_state.clone());
+        write_to_execution_log(
+            &context.execution_log_writer,
+            &format!(
+                "'{}' failed, retrying (retry {} out of {}{})",
+                script.name,
+                script_state.retry_count,
+                script.autorerun_count,
+                if script.autorerun_delay_sec > 0.0 {
+                    format!(", with {}s delay", script.autorerun_delay_sec)
+                } else {
+                    String::new()
+                }
+            ),
+        );
+
+        if script.autorerun_delay_sec > 0.0 {
+            let end_time = Instant::now() + Duration::from_secs_f32(script.autorerun_delay_sec);
+
+            // sleep in short periods, to be able to listen to the stop signal
+            while Instant::now() < end_time {
+                if context.is_stop_requested.load(Ordering::Acquire) {
+                    break 'retry_loop ScriptResultStatus::Failed;
+                }
+                std::thread::sleep((end_time - Instant::now()).min(Duration::from_millis(100)));
+            }
+        }
+    };
+
+    script_state.result = result;
+    script_state.finish_time = Some(Instant::now());
+    send_script_execution_status(&context.progress_sender, script_idx, script_state.clone());
+    script_state
+}
+
+fn skip_script(context: &ScriptRunContext, script_idx: usize, reason: &str) {
+    let mut script_state = get_default_script_execution_status();
+    script_state.start_time = Some(Instant::now());
+    script_state.finish_time = Some(Instant::now());
+    send_script_execution_status(&context.progress_sender, script_idx, script_state);
+
+    write_to_execution_log(
+        &context.execution_log_writer,
+        &format!(
+            "'{}' {}",
+            context.scripts_to_run[script_idx].original.name, reason
+        ),
+    );
+}
+
+fn fail_script(context: &ScriptRunContext, script_idx: usize, reason: &str) {
+    let script_name = &context.scripts_to_run[script_idx].original.name;
+    push_log_line(
+        &context.recent_logs,
+        create_own_log_line(format!("\"{}\" {}", script_name, reason), OutputType::Error),
+        context.echo_to_terminal,
+    );
+
+    let mut script_state = get_default_script_execution_status();
+    script_state.start_time = Some(Instant::now());
+    script_state.finish_time = Some(Instant::now());
+    script_state.result = ScriptResultStatus::Failed;
+    send_script_execution_status(&context.progress_sender, script_idx, script_state);
+
+    write_to_execution_log(
+        &context.execution_log_writer,
+        &format!("'{}' {}", script_name, reason),
+    );
+}
+
+// the scripts named in the run conditions of the script, their results are needed to check the conditions
+fn get_run_condition_script_indexes(scripts: &[ExecutionScript], script_idx: usize) -> Vec<usize> {
+    let mut result = Vec::new();
+    for run_condition in &scripts[script_idx].original.run_conditions {
+        if run_condition.condition_type == config::RunConditionType::Expression {
+            continue;
+        }
+        for (other_script_idx, other_script) in scripts.iter().enumerate() {
+            if other_script_idx != script_idx
+                && other_script.original.name == run_condition.value.trim()
+                && !result.contains(&other_script_idx)
+            {
+                result.push(other_script_idx);
+            }
+        }
+    }
+    result
+}
+
+// returns the name from a run condition of the script that doesn't match any script of the execution
+fn find_unknown_run_condition_script(
+    scripts: &[ExecutionScript],
+    script_idx: usize,
+) -> Option<String> {
+    scripts[script_idx]
+        .original
+        .run_conditions
+        .iter()
+        .filter(|run_condition| {
+            run_condition.condition_type != config::RunConditionType::Expression
+        })
+        .map(|run_condition| run_condition.value.trim())
+        .find(|script_name| {
+            !scripts
+                .iter()
+                .enumerate()
+                .any(|(other_script_idx, other_script)| {
+                    other_script_idx != script_idx && other_script.original.name == *script_name
+                })
+        })
+        .map(str::to_string)
+}
+
+// for each script returns indexes of the scripts it waits for
+// (the scripts named in the run conditions are waited for as well)
+pub fn resolve_dependencies(scripts: &[ExecutionScript]) -> Vec<Vec<usize>> {
+    // the scripts nothing waits for yet, a script without explicit dependencies waits for them
+    let mut last_scripts: Vec<usize> = Vec::new();
+
+    let mut result = Vec::with_capacity(scripts.len());
+    for (script_idx, script) in scripts.iter().enumerate() {
+        let explicit_dependencies = script.dependencies.as_ref().map(|dependency_uids| {
+            dependency_uids
+                .iter()
+                .filter_map(|uid| scripts.iter().position(|script| script.uid == *uid))
+                .filter(|dependency_idx| *dependency_idx != script_idx)
+                .collect::<Vec<usize>>()
+        });
+
+        let mut dependencies = match explicit_dependencies {
+            // if the scripts it waited for were removed, fall back to waiting for the previous scripts
+            Some(dependencies)
+                if !dependencies.is_empty()
+                    || script.dependencies.as_ref().is_some_and(Vec::is_empty) =>
+            {
+                dependencies
+            }
+            _ => last_scripts.clone(),
+        };
+        for condition_script_idx in get_run_condition_script_indexes(scripts, script_idx) {
+            if !dependencies.contains(&condition_script_idx) {
+                dependencies.push(condition_script_idx);
+            }
+        }
+
+        last_scripts.retain(|idx| !dependencies.contains(idx));
+        last_scripts.push(script_idx);
+        result.push(dependencies);
+    }
+
+    result
+}
+
+// adds the scripts to the end of the list, the scripts that don't wait for anything
+// (e.g. the first scripts of a preset) will wait for the scripts that were already there
+pub fn append_scripts_to_list(list: &mut Vec<ExecutionScript>, scripts: Vec<ExecutionScript>) {
+    let previous_script_uids = get_last_script_uids(list);
+
+    for mut script in scripts {
+        if script.dependencies.as_ref().is_some_and(Vec::is_empty) {
+            script.dependencies = Some(previous_script_uids.clone());
+        }
+        list.push(script);
+    }
+}
+
+// the scripts that no other script in the list waits for
+pub fn get_last_script_uids(scripts: &[ExecutionScript]) -> Vec<config::Guid> {
+    let mut last_scripts = vec![true; scripts.len()];
+    for script_dependencies in &resolve_dependencies(scripts) {
+        for dependency_idx in script_dependencies {
+            last_scripts[*dependency_idx] = false;
+        }
+    }
+    scripts
+        .iter()
+        .zip(last_scripts)
+        .filter(|(_, is_last)| *is_last)
+        .map(|(script, _)| script.uid.clone())
+        .collect()
+}
+
+// replaces explicit dependencies with None where waiting for all the previous scripts
+// gives the same result, to keep simple sequential lists looking as such
+pub fn simplify_dependencies(scripts: &mut [ExecutionScript]) {
+    let resolved_dependencies = resolve_dependencies(scripts);
+    let mut last_scripts: Vec<usize> = Vec::new();
+    for (script_idx, dependencies) in resolved_dependencies.into_iter().enumerate() {
+        if last_scripts.len() == dependencies.len()
+            && last_scripts.iter().all(|idx| dependencies.contains(idx))
+        {
+            scripts[script_idx].dependencies = None;
+        }
+        last_scripts.retain(|idx| !dependencies.contains(idx));
+        last_scripts.push(script_idx);
+    }
+}
+
+// converts dependencies to indexes in the list, to be able to store them outside of the execution
+pub fn get_dependency_indexes(scripts: &[ExecutionScript]) -> Vec<Option<Vec<usize>>> {
+    scripts
+        .iter()
+        .map(|script| {
+            script.dependencies.as_ref().map(|dependency_uids| {
+                dependency_uids
+                    .iter()
+                    .filter_map(|uid| scripts.iter().position(|script| script.uid == *uid))
+                    .collect()
+            })
+        })
+        .collect()
+}
+
+pub fn set_dependencies_from_indexes(
+    scripts: &mut [ExecutionScript],
+    dependency_indexes: &[Option<Vec<usize>>],
+) {
+    let uids: Vec<config::Guid> = scripts.iter().map(|script| script.uid.clone()).collect();
+    for (script, dependency_indexes) in scripts.iter_mut().zip(dependency_indexes) {
+        script.dependencies = dependency_indexes.as_ref().map(|dependency_indexes| {
+            dependency_indexes
+                .iter()
+                .filter_map(|idx| uids.get(*idx).cloned())
+                .collect()
+        });
+    }
+}
+
+pub fn request_stop_execution(execution_data: &mut ScriptExecutionData) {
+    execution_data
+        .requested_action
+        .store(REQUESTED_ACTION_STOP, Ordering::Relaxed);
+}
+
+pub fn request_disconnect_non_executed_scripts(execution_data: &mut ScriptExecutionData) {
+    execution_data
+        .requested_action
+        .store(REQUESTED_ACTION_DISCONNECT, Ordering::Relaxed);
+}
+
+fn send_script_execution_status(
+    tx: &Sender<(usize, ScriptExecutionStatus)>,
+    script_idx: usize,
+    script_state: ScriptExecutionStatus,
+) {
+    let _result = tx.send((script_idx, script_state));
+}
+
+fn get_script_with_arguments(
+    script: &config::OriginalScriptDefinition,
+    path_caches: &config::PathCaches,
+) -> String {
+    let path = match script.command.path_type {
+        config::PathType::ScripterExecutableRelative => path_caches
+            .exe_folder_path
+            .join(&script.command.path)
+            .to_str()
+            .unwrap_or_default()
+            .to_string(),
+        config::PathType::WorkingDirRelative => script.command.path.clone(),
+    };
+
+    let escaped_path = escape_path(path);
+
+    if script.arguments_line.is_empty() {
+        escaped_path
+    } else {
+        let mut arguments = script.arguments_line.clone();
+        replace_placeholders(&mut arguments, &script.argument_placeholders);
+        format!("{} {}", escaped_path, arguments,)
+    }
+}
+
+// the variables from --env, overridden by the ones from --env of a forwarded launch, then by the ones
+// from the env file, then by the ones of the script
+fn get_script_env_vars(
+    script: &config::OriginalScriptDefinition,
+    global_env_vars: &[(std::ffi::OsString, std::ffi::OsString)],
+    launch_env_vars: &[(std::ffi::OsString, std::ffi::OsString)],
+    path_caches: &config::PathCaches,
+) -> Result<Vec<(std::ffi::OsString, std::ffi::OsString)>, String> {
+    let mut env_vars = global_env_vars.to_vec();
+    let mut set_env_var = |name: std::ffi::OsString, value: std::ffi::OsString| match env_vars
+        .iter_mut()
+        .find(|(existing_name, _)| *existing_name == name)
+    {
+        Some(env_var) => env_var.1 = value,
+        None => env_vars.push((name, value)),
+    };
+
+    for (name, value) in launch_env_vars {
+        set_env_var(name.clone(), value.clone());
+    }
+
+    if !script.env_file.path.is_empty() {
+        let env_file_path = config::get_full_path(path_caches, &script.env_file);
+        let env_file_content = std::fs::read_to_string(&env_file_path).map_err(|error| {
+            format!(
+                "Can't read env file '{}': {}",
+                env_file_path.to_string_lossy(),
+                error
+            )
+        })?;
+        let file_env_vars = parse_env_file(&env_file_content).map_err(|error| {
+            format!(
+                "Can't read env file '{}': {}",
+                env_file_path.to_string_lossy(),
+                error
+            )
+        })?;
+        for (name, value) in file_env_vars {
+            set_env_var(name.into(), value.into());
+        }
+    }
+
+    for env_var in &script.env_vars {
+        if env_var.name.is_empty() {
+            continue;
+        }
+        let mut value = env_var.value.clone();
+        replace_placeholders(&mut value, &script.argument_placeholders);
+        set_env_var(env_var.name.clone().into(), value.into());
+    }
+
+    Ok(env_vars)
+}
+
+// supports "NAME=value" lines with optional "export " and quotes around the value, "#" starts a comment line
+fn parse_env_file(content: &str) -> Result<Vec<(String, String)>, String> {
+    let mut env_vars = Vec::new();
+    for (line_idx, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((name, value)) = line.split_once('=') else {
+            return Err(format!(
+                "line {} is not in the NAME=value format",
+                line_idx + 1
+            ));
+        };
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(format!("line {} has an empty variable name", line_idx + 1));
+        }
+        let value = value.trim();
+        let value = [('"', '"'), ('\'', '\'')]
+            .iter()
+            .find_map(|(start, end)| {
+                value
+                    .strip_prefix(*start)
+                    .and_then(|value| value.strip_suffix(*end))
+            })
+            .unwrap_or(value);
+        env_vars.push((name.to_string(), value.to_string()));
+    }
+    Ok(env_vars)
+}
+
+fn parse_set_output_line(line: &str) -> Option<(String, String)> {
+    let (name, value) = line
+        .trim_end_matches(['\r', '\n'])
+        .strip_prefix(SET_OUTPUT_PREFIX)?
+        .split_once('=')?;
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+    Some((name.to_string(), value.to_string()))
+}
+
+fn read_outputs_file(
+    outputs_file_path: &std::path::Path,
+    script_outputs: &Mutex<ScriptOutputs>,
+    output_writer: &Result<Arc<Mutex<log_index::ScriptOutputWriter>>, std::io::Error>,
+    recent_logs: &Arc<Mutex<LogBuffer>>,
+    echo_to_terminal: bool,
+) {
+    let Ok(content) = std::fs::read_to_string(outputs_file_path) else {
+        return;
+    };
+
+    let (text, output_type) = match parse_env_file(&content) {
+        Ok(new_outputs) if new_outputs.is_empty() => return,
+        Ok(new_outputs) => {
+            let text = new_outputs
+                .iter()
+                .map(|(name, value)| format!("{}={}", name, value))
+                .collect::<Vec<_>>()
+                .join(", ");
+            script_outputs.lock().unwrap().extend(new_outputs); // it is fine to panic on a poisoned mutex
+            (format!("Set outputs: {}", text), OutputType::Event)
+        }
+        Err(error) => (
+            format!(
+                "Can't read outputs from ${}: {}",
+                OUTPUT_FILE_ENV_VAR, error
+            ),
+            OutputType::Error,
+        ),
+    };
+
+    let line = create_own_log_line(text, output_type);
+    send_own_log_line(output_writer, recent_logs, line, echo_to_terminal);
+}
+
+impl ScriptExitStatus {
+    fn from_exit_status(status: std::process::ExitStatus) -> Self {
+        #[cfg(unix)]
+        let signal = std::os::unix::process::ExitStatusExt::signal(&status);
+        #[cfg(not(unix))]
+        let signal = None;
+
+        Self {
+            exit_code: status.code(),
+            signal,
+        }
+    }
+}
+
+impl std::fmt::Display for ScriptExitStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.exit_code, self.signal) {
+            (Some(exit_code), _) => write!(f, "exit code {}", exit_code),
+            (None, Some(signal)) => match get_signal_name(signal) {
+                Some(signal_name) => write!(f, "signal {}", signal_name),
+                None => write!(f, "signal {}", signal),
+            },
+            (None, None) => write!(f, "unknown exit status"),
+        }
+    }
+}
+
+// the signals that are the same on all unix systems, to tell crashes from kills
+fn get_signal_name(signal: i32) -> Option<&'static str> {
+    match signal {
+        1 => Some("SIGHUP"),
+        2 => Some("SIGINT"),
+        3 => Some("SIGQUIT"),
+        4 => Some("SIGILL"),
+        6 => Some("SIGABRT"),
+        8 => Some("SIGFPE"),
+        9 => Some("SIGKILL"),
+        11 => Some("SIGSEGV"),
+        13 => Some("SIGPIPE"),
+        14 => Some("SIGALRM"),
+        15 => Some("SIGTERM"),
+        _ => None,
+    }
+}
+
+fn get_exit_code_result_by_default(exit_code: i32) -> config::ExitCodeResult {
+    if exit_code == 0 {
+        config::ExitCodeResult::Success
+    } else {
+        config::ExitCodeResult::Failure
+    }
+}
+
+// None exit code means that the process was terminated by a signal
+fn get_exit_code_result(
+    script: &config::OriginalScriptDefinition,
+    exit_code: Option<i32>,
+) -> config::ExitCodeResult {
+    let Some(exit_code) = exit_code else {
+        return config::ExitCodeResult::Failure;
+    };
+
+    script
+        .exit_codes
+        .iter()
+        .find(|reaction| reaction.exit_code == exit_code)
+        .map_or_else(
+            || get_exit_code_result_by_default(exit_code),
+            |reaction| reaction.result,
+        )
+}
+
+fn get_exit_code_result_description(exit_code_result: config::ExitCodeResult) -> &'static str {
+    match exit_code_result {
+        config::ExitCodeResult::Success => "success",
+        config::ExitCodeResult::Warning => "a warning",
+        config::ExitCodeResult::Skipped => "skipped",
+        config::ExitCodeResult::Failure => "a failure",
+    }
+}
+
+// returns the description of the first run condition of the script that is not met
+fn find_unmet_run_condition(
+    context: &ScriptRunContext,
+    script_idx: usize,
+    script_states: &[ScheduledScriptState],
+) -> Option<String> {
+    let script = &context.scripts_to_run[script_idx].original;
+    if script.run_conditions.is_empty() {
+        return None;
+    }
 
-                    let requested_action_raw = requested_action.load(Ordering::Acquire);
-                    if requested_action_raw > 0 {
-                        if requested_action_raw == REQUESTED_ACTION_STOP {
-                            kill_process(&mut child);
-                            kill_requested = true;
-                            write_to_execution_log(
-                                &mut execution_log_writer,
-                                "Requested execution stop",
-                            );
-                        } else if requested_action_raw == REQUESTED_ACTION_DISCONNECT {
-                            let first_disconnected_script_idx = script_idx + 1;
-                            let size_of_script_list = if disconnect_requested {
-                                first_disconnected_script_idx
-                            } else {
-                                scripts_to_run.len()
-                            };
-                            send_non_executed_disconnect_statuses(
-                                &progress_sender,
-                                first_disconnected_script_idx,
-                                size_of_script_list,
-                            );
-                            disconnect_requested = true;
-                        }
-                        requested_action.store(REQUESTED_ACTION_NONE, Ordering::Release);
+    let has_script_finished_with =
+        |script_name: &str, expected_results: &[ScriptResultStatus]| {
+            context.scripts_to_run.iter().zip(script_states).any(
+                |(other_script, state)| match state {
+                    ScheduledScriptState::Finished { result, .. } => {
+                        other_script.original.name == script_name.trim()
+                            && expected_results.contains(result)
                     }
-
-                    std::thread::sleep(Duration::from_millis(100));
+                    _ => false,
+                },
+            )
+        };
+
+    for run_condition in &script.run_conditions {
+        match run_condition.condition_type {
+            config::RunConditionType::ScriptSucceeded => {
+                if !has_script_finished_with(
+                    &run_condition.value,
+                    &[ScriptResultStatus::Success, ScriptResultStatus::Warning],
+                ) {
+                    return Some(format!("'{}' did not succeed", run_condition.value.trim()));
                 }
-                join_threads(threads_to_join);
             }
-
-            if disconnect_requested {
-                break;
+            config::RunConditionType::ScriptFailed => {
+                if !has_script_finished_with(&run_condition.value, &[ScriptResultStatus::Failed]) {
+                    return Some(format!("'{}' did not fail", run_condition.value.trim()));
+                }
+            }
+            config::RunConditionType::Expression => {
+                let script_outputs = context.script_outputs.lock().unwrap().clone(); // it is fine to panic on a poisoned mutex
+                let env_vars = get_script_env_vars(
+                    script,
+                    &context.env_vars,
+                    &context.scripts_to_run[script_idx].launch_env_vars,
+                    &context.path_caches,
+                )
+                .unwrap_or_else(|_| context.env_vars.clone());
+                let get_env_var = |name: &str| {
+                    env_vars
+                        .iter()
+                        .find(|(env_var_name, _)| env_var_name == name)
+                        .map(|(_, value)| value.to_string_lossy().to_string())
+                        .or_else(|| std::env::var(name).ok())
+                };
+                match evaluate_condition_expression(
+                    &run_condition.value,
+                    &script_outputs,
+                    get_env_var,
+                ) {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        return Some(format!("'{}' is false", run_condition.value.trim()));
+                    }
+                    Err(error) => {
+                        return Some(format!(
+                            "'{}' can't be evaluated: {}",
+                            run_condition.value.trim(),
+                            error
+                        ));
+                    }
+                }
             }
         }
-    }));
+    }
+    None
 }
 
-pub fn request_stop_execution(execution_data: &mut ScriptExecutionData) {
-    execution_data
-        .requested_action
-        .store(REQUESTED_ACTION_STOP, Ordering::Relaxed);
-}
+// supports "A == B", "A != B" and "A" (true if not empty, "0" or "false"),
+// where the values can reference "{{outputs.NAME}}" and "{{env.NAME}}"
+fn evaluate_condition_expression(
+    expression: &str,
+    script_outputs: &ScriptOutputs,
+    get_env_var: impl Fn(&str) -> Option<String>,
+) -> Result<bool, String> {
+    // the values that are not set are empty, so it is possible to check whether they are set
+    let expression = replace_references(expression, |name| {
+        if let Some(output_name) = name.strip_prefix("outputs.") {
+            Ok(Some(
+                script_outputs.get(output_name).cloned().unwrap_or_default(),
+            ))
+        } else if let Some(env_var_name) = name.strip_prefix("env.") {
+            Ok(Some(get_env_var(env_var_name).unwrap_or_default()))
+        } else {
+            Err(format!("unknown reference '{{{{{}}}}}'", name))
+        }
+    })?;
+
+    let get_value = |text: &str| {
+        let text = text.trim();
+        text.strip_prefix('"')
+            .and_then(|text| text.strip_suffix('"'))
+            .unwrap_or(text)
+            .to_string()
+    };
 
-pub fn request_disconnect_non_executed_scripts(execution_data: &mut ScriptExecutionData) {
-    execution_data
-        .requested_action
-        .store(REQUESTED_ACTION_DISCONNECT, Ordering::Relaxed);
+    if let Some((left, right)) = expression.split_once("==") {
+        Ok(get_value(left) == get_value(right))
+    } else if let Some((left, right)) = expression.split_once("!=") {
+        Ok(get_value(left) != get_value(right))
+    } else {
+        let value = get_value(&expression);
+        Ok(!value.is_empty() && value != "0" && !value.eq_ignore_ascii_case("false"))
+    }
 }
 
-fn send_script_execution_status(
-    tx: &Sender<(usize, ScriptExecutionStatus)>,
-    script_idx: usize,
-    script_state: ScriptExecutionStatus,
-) {
-    let _result = tx.send((script_idx, script_state));
+// replaces "{{NAME}}" with the values returned by get_value, None keeps the reference as is
+fn replace_references(
+    text: &str,
+    get_value: impl Fn(&str) -> Result<Option<String>, String>,
+) -> Result<String, String> {
+    const REFERENCE_START: &str = "{{";
+    const REFERENCE_END: &str = "}}";
+
+    let mut result = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find(REFERENCE_START) {
+        let name_start = start + REFERENCE_START.len();
+        let Some(name_len) = rest[name_start..].find(REFERENCE_END) else {
+            break;
+        };
+        let reference_end = name_start + name_len + REFERENCE_END.len();
+        result.push_str(&rest[..start]);
+        match get_value(rest[name_start..name_start + name_len].trim())? {
+            Some(value) => result.push_str(&value),
+            None => result.push_str(&rest[start..reference_end]),
+        }
+        rest = &rest[reference_end..];
+    }
+    result.push_str(rest);
+    Ok(result)
 }
 
-fn get_script_with_arguments(
+// replaces "{{outputs.NAME}}" with the outputs set by the previous scripts of the execution
+fn apply_script_outputs(
     script: &config::OriginalScriptDefinition,
-    path_caches: &config::PathCaches,
-) -> String {
-    let path = match script.command.path_type {
-        config::PathType::ScripterExecutableRelative => path_caches
-            .exe_folder_path
-            .join(&script.command.path)
-            .to_str()
-            .unwrap_or_default()
-            .to_string(),
-        config::PathType::WorkingDirRelative => script.command.path.clone(),
-    };
-
-    let escaped_path = escape_path(path);
-
-    if script.arguments_line.is_empty() {
-        escaped_path
-    } else {
-        let mut arguments = script.arguments_line.clone();
-        replace_placeholders(&mut arguments, &script.argument_placeholders);
-        format!("{} {}", escaped_path, arguments,)
+    script_outputs: &ScriptOutputs,
+) -> Result<config::OriginalScriptDefinition, String> {
+    let mut script = script.clone();
+    replace_script_outputs(&mut script.arguments_line, script_outputs)?;
+    for placeholder in &mut script.argument_placeholders {
+        replace_script_outputs(&mut placeholder.value, script_outputs)?;
+    }
+    for argument in &mut script.executor_arguments {
+        replace_script_outputs(argument, script_outputs)?;
     }
+    for env_var in &mut script.env_vars {
+        replace_script_outputs(&mut env_var.value, script_outputs)?;
+    }
+    Ok(script)
+}
+
+fn replace_script_outputs(text: &mut String, script_outputs: &ScriptOutputs) -> Result<(), String> {
+    *text = replace_references(text, |name| {
+        let Some(output_name) = name.strip_prefix("outputs.") else {
+            return Ok(None);
+        };
+        match script_outputs.get(output_name) {
+            Some(value) => Ok(Some(value.clone())),
+            None => Err(format!(
+                "Output '{}' was not set by any of the previous scripts",
+                output_name
+            )),
+        }
+    })?;
+    Ok(())
 }
 
 struct PlaceholderOccurrence {
@@ -604,6 +1676,9 @@ fn get_default_script_execution_status() -> ScriptExecutionStatus {
         finish_time: None,
         result: ScriptResultStatus::Skipped,
         retry_count: 0,
+        exit_statuses: Vec::new(),
+        resource_usages: Vec::new(),
+        problems: Vec::new(),
     }
 }
 
@@ -639,10 +1714,126 @@ fn escape_path(path: String) -> String {
     }
 }
 
-fn kill_process(process: &mut std::process::Child) {
-    let kill_result = process.kill();
-    if let Err(result) = kill_result {
-        println!("failed to kill child process: {}", result);
+// make the process the leader of a new process group, so we can stop it together with its children
+fn run_in_own_process_group(command: &mut std::process::Command) {
+    #[cfg(unix)]
+    command.process_group(0);
+
+    // on Windows the process tree is tracked by parent process ids, nothing to set up
+    #[cfg(not(unix))]
+    let _ = command;
+}
+
+// stops the processes of a script without blocking, so the script thread can keep polling them
+struct ProcessStopper {
+    // after this the processes that are still running are killed
+    kill_time: Instant,
+    // the other processes that the script started are kept running
+    #[cfg_attr(not(unix), allow(dead_code))]
+    is_script_process_only: bool,
+}
+
+impl ProcessStopper {
+    // asks the processes to terminate
+    #[cfg(unix)]
+    fn start(process: &mut std::process::Child, is_script_process_only: bool) -> Self {
+        if unsafe { kill(get_kill_target(process, is_script_process_only), SIGTERM) } != 0 {
+            // the processes are already gone, kill the direct child just in case
+            let kill_result = process.kill();
+            if let Err(result) = kill_result {
+                println!("failed to kill child process: {}", result);
+            }
+        }
+
+        Self {
+            kill_time: Instant::now() + KILL_GRACE_PERIOD,
+            is_script_process_only,
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    fn start(process: &mut std::process::Child, is_script_process_only: bool) -> Self {
+        // console processes don't react to the graceful variant of taskkill, so force it right away
+        let taskkill_result = if is_script_process_only {
+            None
+        } else {
+            std::process::Command::new("taskkill")
+                .args(["/F", "/T", "/PID", &process.id().to_string()])
+                .creation_flags(0x08000000) // CREATE_NO_WINDOW
+                .stdin(std::process::Stdio::null())
+                .stdout(std::process::Stdio::null())
+                .stderr(std::process::Stdio::null())
+                .status()
+                .ok()
+        };
+
+        if !matches!(taskkill_result, Some(status) if status.success()) {
+            let kill_result = process.kill();
+            if let Err(result) = kill_result {
+                println!("failed to kill child process: {}", result);
+            }
+        }
+
+        Self {
+            kill_time: Instant::now() + KILL_GRACE_PERIOD,
+            is_script_process_only,
+        }
+    }
+
+    // returns true when the processes are stopped, kills them if they didn't stop in time
+    fn update(&self, process: &mut std::process::Child) -> bool {
+        // reap the direct child, otherwise it would keep the process group alive as a zombie
+        let has_script_process_exited = matches!(process.try_wait(), Ok(Some(_)));
+        #[cfg(unix)]
+        let is_running = if self.is_script_process_only {
+            !has_script_process_exited
+        } else {
+            // signal 0 only checks whether any process of the group is still alive
+            let check_result = unsafe { kill(get_kill_target(process, false), 0) };
+            check_result == 0
+        };
+        // taskkill has already killed the whole tree
+        #[cfg(not(unix))]
+        let is_running = !has_script_process_exited;
+
+        if !is_running {
+            return true;
+        }
+        if Instant::now() < self.kill_time {
+            return false;
+        }
+
+        #[cfg(unix)]
+        unsafe {
+            kill(
+                get_kill_target(process, self.is_script_process_only),
+                SIGKILL,
+            );
+        }
+        #[cfg(not(unix))]
+        let _ = process.kill();
+        let _ = process.wait();
+        true
+    }
+}
+
+#[cfg(unix)]
+const SIGKILL: i32 = 9;
+#[cfg(unix)]
+const SIGTERM: i32 = 15;
+
+#[cfg(unix)]
+extern "C" {
+    fn kill(pid: i32, sig: i32) -> i32;
+}
+
+// negative pid addresses the whole process group, the group id is the pid of its leader
+#[cfg(unix)]
+fn get_kill_target(process: &std::process::Child, is_script_process_only: bool) -> i32 {
+    if is_script_process_only {
+        process.id() as i32
+    } else {
+        -(process.id() as i32)
     }
 }
 
@@ -660,6 +1851,9 @@ fn send_non_executed_disconnect_statuses(
                 finish_time: None,
                 result: ScriptResultStatus::Disconnected,
                 retry_count: 0,
+                exit_statuses: Vec::new(),
+                resource_usages: Vec::new(),
+                problems: Vec::new(),
             },
         );
     }
@@ -673,99 +1867,196 @@ fn send_non_executed_disconnect_statuses(
             finish_time: None,
             result: ScriptResultStatus::Disconnected,
             retry_count: 0,
+            exit_statuses: Vec::new(),
+            resource_usages: Vec::new(),
+            problems: Vec::new(),
         },
     );
 }
 
+struct OutputReadingThreads {
+    read_stdio_threads: Vec<std::thread::JoinHandle<()>>,
+    join_and_split_thread: std::thread::JoinHandle<()>,
+    // set when the script exited, but the processes that it left running keep the output open,
+    // their lines are still read so they don't block on writing, but are not logged
+    is_abandoned: Arc<AtomicBool>,
+}
+
 fn join_and_split_output(
     stdout: std::process::ChildStdout,
     stderr: std::process::ChildStderr,
     recent_logs: Arc<Mutex<LogBuffer>>,
-    output_file: std::fs::File,
-) -> Vec<std::thread::JoinHandle<()>> {
-    let (sender_out, receiver_out) = unbounded();
-    let (sender_err, receiver_err) = unbounded();
-
-    let read_stdio_thread = std::thread::spawn(move || {
-        read_one_stdio(stdout, sender_out);
+    output_writer: Arc<Mutex<log_index::ScriptOutputWriter>>,
+    output_context: ScriptOutputContext,
+    echo_to_terminal: bool,
+) -> OutputReadingThreads {
+    let (sender_out, receiver) = unbounded();
+    let sender_err = sender_out.clone();
+    let is_abandoned = Arc::new(AtomicBool::new(false));
+
+    let read_stdio_thread = std::thread::spawn({
+        let is_abandoned = is_abandoned.clone();
+        move || read_one_stdio(stdout, OutputType::StdOut, sender_out, &is_abandoned)
     });
 
-    let read_stderr_thread = std::thread::spawn(move || {
-        read_one_stdio(stderr, sender_err);
+    let read_stderr_thread = std::thread::spawn({
+        let is_abandoned = is_abandoned.clone();
+        move || read_one_stdio(stderr, OutputType::StdErr, sender_err, &is_abandoned)
     });
 
-    let join_and_split_thread = std::thread::spawn(move || {
-        let mut output_writer = std::io::BufWriter::new(output_file);
-        loop {
-            crossbeam_channel::select! {
-                recv(receiver_out) -> log => {
-                    if try_split_log(&mut output_writer, &recent_logs, OutputType::StdOut, log).is_err() {
-                        break;
+    let join_and_split_thread = std::thread::spawn({
+        let is_abandoned = is_abandoned.clone();
+        move || {
+            // keep reading until both streams are closed, otherwise the last lines of one of them can be lost
+            let mut open_streams_count = 2;
+            while open_streams_count > 0 {
+                match receiver.recv_timeout(Duration::from_millis(100)) {
+                    Ok((_, _, true)) | Err(RecvTimeoutError::Disconnected) => {
+                        open_streams_count -= 1
                     }
-                },
-                recv(receiver_err) -> log => {
-                    if try_split_log(&mut output_writer, &recent_logs, OutputType::StdErr, log).is_err() {
-                        break;
+                    // the lines read before the output was abandoned are all in the channel by now
+                    Err(RecvTimeoutError::Timeout) => {
+                        if is_abandoned.load(Ordering::Acquire) {
+                            break;
+                        }
+                    }
+                    Ok((output_type, text, false)) => {
+                        if output_type == OutputType::StdOut {
+                            if let Some((name, value)) = parse_set_output_line(&text) {
+                                output_context
+                                    .script_outputs
+                                    .lock()
+                                    .unwrap()
+                                    .insert(name, value); // it is fine to panic on a poisoned mutex
+                            }
+                        }
+                        let text = ansi_escapes::collapse_carriage_returns(&text).into_owned();
+                        let highlight = output_context.problem_collector.classify_line(&text);
+                        send_log_line(
+                            &output_writer,
+                            &recent_logs,
+                            OutputLine {
+                                text,
+                                output_type,
+                                timestamp: chrono::Local::now(),
+                                highlight,
+                                script_idx: Some(output_context.script_idx),
+                            },
+                            echo_to_terminal,
+                        );
                     }
                 }
             }
         }
     });
 
-    vec![read_stdio_thread, read_stderr_thread, join_and_split_thread]
+    OutputReadingThreads {
+        read_stdio_threads: vec![read_stdio_thread, read_stderr_thread],
+        join_and_split_thread,
+        is_abandoned,
+    }
 }
 
-fn read_one_stdio<R: std::io::Read>(stdio: R, out_channel: Sender<(String, bool)>) {
+fn read_one_stdio<R: std::io::Read>(
+    stdio: R,
+    output_type: OutputType,
+    out_channel: Sender<(OutputType, String, bool)>,
+    is_abandoned: &AtomicBool,
+) {
     let mut stdout_reader = std::io::BufReader::new(stdio);
     loop {
         let mut line = String::new();
         let read_result = stdout_reader.read_line(&mut line);
         let should_stop = read_result.unwrap_or(0) == 0;
 
-        let _ = out_channel.try_send((line, should_stop));
+        if !is_abandoned.load(Ordering::Acquire) {
+            let _ = out_channel.try_send((output_type, line, should_stop));
+        }
         if should_stop {
             break;
         }
     }
 }
 
-fn try_split_log(
-    output_writer: &mut std::io::BufWriter<std::fs::File>,
-    recent_logs: &Arc<Mutex<LogBuffer>>,
-    output_type: OutputType,
-    log: Result<(String, bool), RecvError>,
-) -> Result<(), ()> {
-    if let Ok((text, should_exit)) = log {
-        if should_exit {
-            return Err(());
-        } else {
-            send_log_line(
-                output_writer,
-                recent_logs,
-                OutputLine {
-                    text,
-                    output_type,
-                    timestamp: chrono::Local::now(),
-                },
-            );
+// called after the script process exited, the threads reading the output that is still open
+// are left running
+fn finish_reading_output(threads: OutputReadingThreads) {
+    let end_time = Instant::now() + OUTPUT_CLOSE_TIMEOUT;
+    while threads
+        .read_stdio_threads
+        .iter()
+        .any(|thread| !thread.is_finished())
+    {
+        if Instant::now() >= end_time {
+            threads.is_abandoned.store(true, Ordering::Release);
+            break;
         }
-    } else {
-        return Err(());
+        std::thread::sleep(Duration::from_millis(10));
     }
-    Ok(())
+    let _ = threads.join_and_split_thread.join();
 }
 
 fn send_log_line(
-    output_writer: &mut std::io::BufWriter<std::fs::File>,
+    output_writer: &Mutex<log_index::ScriptOutputWriter>,
+    recent_logs: &Arc<Mutex<LogBuffer>>,
+    line: OutputLine,
+    echo_to_terminal: bool,
+) {
+    output_writer.lock().unwrap().write_line(&line); // it is fine to panic on a poisoned mutex
+    push_log_line(recent_logs, line, echo_to_terminal);
+}
+
+// for lines that are not coming from the script itself
+fn send_own_log_line(
+    output_writer: &std::io::Result<Arc<Mutex<log_index::ScriptOutputWriter>>>,
     recent_logs: &Arc<Mutex<LogBuffer>>,
     line: OutputLine,
+    echo_to_terminal: bool,
 ) {
-    let _ = write!(output_writer, "{}", line.text);
-    let _ = output_writer.flush();
+    match output_writer {
+        Ok(output_writer) => send_log_line(output_writer, recent_logs, line, echo_to_terminal),
+        Err(_) => push_log_line(recent_logs, line, echo_to_terminal),
+    }
+}
 
+// for lines that are shown but not written to the output file, e.g. when there is no file yet
+fn push_log_line(recent_logs: &Mutex<LogBuffer>, line: OutputLine, echo_to_terminal: bool) {
+    if echo_to_terminal {
+        print_log_line_to_terminal(&line);
+    }
     recent_logs.lock().unwrap().push(line); // it is fine to panic on a poisoned mutex
 }
 
+fn create_own_log_line(text: String, output_type: OutputType) -> OutputLine {
+    OutputLine {
+        text,
+        output_type,
+        timestamp: chrono::Local::now(),
+        highlight: None,
+        script_idx: None,
+    }
+}
+
+fn print_log_line_to_terminal(line: &OutputLine) {
+    let text = get_terminal_text(line);
+    match line.output_type {
+        OutputType::StdOut | OutputType::Event => print!("{}", text),
+        OutputType::StdErr | OutputType::Error => eprint!("{}", text),
+    }
+}
+
+// script output already contains line endings, our own messages don't
+fn get_terminal_text(line: &OutputLine) -> std::borrow::Cow<'_, str> {
+    match line.output_type {
+        OutputType::StdOut | OutputType::StdErr => std::borrow::Cow::Borrowed(&line.text),
+        OutputType::Error | OutputType::Event => std::borrow::Cow::Owned(format!(
+            "[{}] {}\n",
+            line.timestamp.format("%H:%M:%S"),
+            line.text
+        )),
+    }
+}
+
 pub fn create_or_append_to_file(
     file_path: std::path::PathBuf,
 ) -> Option<std::io::BufWriter<std::fs::File>> {
@@ -791,10 +2082,10 @@ pub fn create_or_append_to_file(
 }
 
 fn write_to_execution_log(
-    output_writer: &mut Option<std::io::BufWriter<std::fs::File>>,
+    output_writer: &Mutex<Option<std::io::BufWriter<std::fs::File>>>,
     line: &str,
 ) {
-    if let Some(writer) = output_writer {
+    if let Some(writer) = &mut *output_writer.lock().unwrap() {
         let _ = write!(
             writer,
             "{}: {}\n",
@@ -870,3 +2161,582 @@ pub fn get_script_to_execute_description(script: &config::OriginalScriptDefiniti
 
     description
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestDirectory;
+    use std::io::Read;
+
+    fn spawn_in_own_process_group(script: &str) -> std::process::Child {
+        let executor = config::get_default_executor();
+        let mut command = std::process::Command::new(&executor[0]);
+        command.args(&executor[1..]).arg(script);
+        run_in_own_process_group(&mut command);
+        command
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::null())
+            .spawn()
+            .unwrap()
+    }
+
+    fn kill_process_tree(process: &mut std::process::Child) {
+        let process_stopper = ProcessStopper::start(process, false);
+        while !process_stopper.update(process) {
+            std::thread::sleep(Duration::from_millis(50));
+        }
+    }
+
+    #[cfg(unix)]
+    fn is_process_alive(pid: i32) -> bool {
+        extern "C" {
+            fn kill(pid: i32, sig: i32) -> i32;
+        }
+        if unsafe { kill(pid, 0) } != 0 {
+            return false;
+        }
+        // orphaned zombies may not be reaped right away (e.g. in containers), they are not running
+        match std::fs::read_to_string(format!("/proc/{}/stat", pid)) {
+            Ok(stat) => stat
+                .rsplit_once(')')
+                .map(|(_, rest)| !rest.trim_start().starts_with('Z'))
+                .unwrap_or(true),
+            Err(_) => true,
+        }
+    }
+
+    #[test]
+    fn test_outputs_of_previous_scripts_replace_their_references() {
+        let mut script_outputs = ScriptOutputs::new();
+        let (name, value) = parse_set_output_line("::set-output branch=feature/login\n").unwrap();
+        script_outputs.insert(name, value);
+        assert!(parse_set_output_line("set-output branch=main\n").is_none());
+
+        let mut arguments =
+            "push origin {{outputs.branch}} --title \"{{outputs.branch}}\"".to_string();
+        replace_script_outputs(&mut arguments, &script_outputs).unwrap();
+        assert_eq!(
+            arguments,
+            "push origin feature/login --title \"feature/login\""
+        );
+
+        let mut arguments = "{{outputs.pr_number}}".to_string();
+        assert!(replace_script_outputs(&mut arguments, &script_outputs).is_err());
+    }
+
+    #[test]
+    fn test_exit_codes_are_mapped_to_results() {
+        let mut script = config::OriginalScriptDefinition::default();
+        script.exit_codes.push(config::ExitCodeReaction {
+            exit_code: 1,
+            result: config::ExitCodeResult::Warning,
+        });
+
+        assert_eq!(
+            get_exit_code_result(&script, Some(0)),
+            config::ExitCodeResult::Success
+        );
+        assert_eq!(
+            get_exit_code_result(&script, Some(1)),
+            config::ExitCodeResult::Warning
+        );
+        assert_eq!(
+            get_exit_code_result(&script, Some(2)),
+            config::ExitCodeResult::Failure
+        );
+        assert_eq!(
+            get_exit_code_result(&script, None),
+            config::ExitCodeResult::Failure
+        );
+    }
+
+    #[test]
+    fn test_exit_statuses_are_described_by_exit_code_or_signal() {
+        let exit_status = |exit_code, signal| ScriptExitStatus { exit_code, signal };
+        assert_eq!(exit_status(Some(2), None).to_string(), "exit code 2");
+        assert_eq!(exit_status(None, Some(9)).to_string(), "signal SIGKILL");
+        assert_eq!(exit_status(None, Some(40)).to_string(), "signal 40");
+    }
+
+    #[test]
+    fn test_condition_expressions_are_evaluated_with_outputs_and_env_vars() {
+        let mut script_outputs = ScriptOutputs::new();
+        script_outputs.insert("branch".to_string(), "feature/x".to_string());
+        let get_env_var = |name: &str| (name == "CI").then(|| "true".to_string());
+
+        let evaluate = |expression: &str| {
+            evaluate_condition_expression(expression, &script_outputs, get_env_var)
+        };
+        assert_eq!(evaluate("{{outputs.branch}} != main"), Ok(true));
+        assert_eq!(evaluate("{{outputs.branch}} == \"feature/x\""), Ok(true));
+        assert_eq!(evaluate("{{env.CI}} == false"), Ok(false));
+        assert_eq!(evaluate("{{env.CI}}"), Ok(true));
+        assert_eq!(evaluate("{{outputs.pr_number}}"), Ok(false));
+        assert!(evaluate("{{branch}} == main").is_err());
+    }
+
+    #[test]
+    fn test_env_file_lines_are_parsed_into_variables() {
+        let env_vars = parse_env_file(
+            "# build settings\nBUILD_TYPE=release\n\nexport TARGET = \"x86_64 linux\"\nEMPTY=\nQUOTED='a=b'\n",
+        )
+        .unwrap();
+        assert_eq!(
+            env_vars,
+            vec![
+                ("BUILD_TYPE".to_string(), "release".to_string()),
+                ("TARGET".to_string(), "x86_64 linux".to_string()),
+                ("EMPTY".to_string(), "".to_string()),
+                ("QUOTED".to_string(), "a=b".to_string()),
+            ]
+        );
+
+        assert!(parse_env_file("BUILD_TYPE=release\nnot a variable\n").is_err());
+    }
+
+    #[test]
+    fn test_launch_env_vars_override_global_ones_and_are_overridden_by_script_ones() {
+        let env_var = |name: &str, value: &str| {
+            (
+                std::ffi::OsString::from(name),
+                std::ffi::OsString::from(value),
+            )
+        };
+        let script = config::OriginalScriptDefinition {
+            env_vars: vec![config::EnvironmentVariable {
+                name: "TARGET".to_string(),
+                value: "script".to_string(),
+            }],
+            ..Default::default()
+        };
+        let path_caches = config::PathCaches {
+            logs_path: std::path::PathBuf::new(),
+            work_path: std::path::PathBuf::new(),
+            exe_folder_path: std::path::PathBuf::new(),
+            config_path: std::path::PathBuf::new(),
+        };
+
+        let env_vars = get_script_env_vars(
+            &script,
+            &[env_var("BUILD_TYPE", "debug"), env_var("CI", "true")],
+            &[
+                env_var("BUILD_TYPE", "release"),
+                env_var("TARGET", "launch"),
+            ],
+            &path_caches,
+        )
+        .unwrap();
+        assert_eq!(
+            env_vars,
+            vec![
+                env_var("BUILD_TYPE", "release"),
+                env_var("CI", "true"),
+                env_var("TARGET", "script"),
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_kill_process_tree_kills_nested_shells() {
+        let mut child = spawn_in_own_process_group(
+            "sleep 60 & echo $!; sh -c 'sleep 60 & echo $!; sleep 60' & echo $!; wait",
+        );
+
+        let mut reader = std::io::BufReader::new(child.stdout.take().unwrap());
+        let mut pids = vec![child.id() as i32];
+        while pids.len() < 4 {
+            let mut line = String::new();
+            assert!(reader.read_line(&mut line).unwrap() > 0);
+            pids.push(line.trim().parse().unwrap());
+        }
+        assert!(pids.iter().all(|pid| is_process_alive(*pid)));
+
+        kill_process_tree(&mut child);
+
+        let end_time = Instant::now() + Duration::from_secs(5);
+        while pids.iter().any(|pid| is_process_alive(*pid)) && Instant::now() < end_time {
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        assert!(pids.iter().all(|pid| !is_process_alive(*pid)));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_kill_process_tree_kills_processes_that_ignore_sigterm() {
+        let mut child = spawn_in_own_process_group(
+            "trap '' TERM; sh -c \"trap '' TERM; sleep 60\" & echo $!; wait",
+        );
+
+        let mut reader = std::io::BufReader::new(child.stdout.take().unwrap());
+        let mut line = String::new();
+        assert!(reader.read_line(&mut line).unwrap() > 0);
+        let pids = [child.id() as i32, line.trim().parse().unwrap()];
+
+        let start_time = Instant::now();
+        kill_process_tree(&mut child);
+        assert!(start_time.elapsed() >= KILL_GRACE_PERIOD);
+
+        let end_time = Instant::now() + Duration::from_secs(5);
+        while pids.iter().any(|pid| is_process_alive(*pid)) && Instant::now() < end_time {
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        assert!(pids.iter().all(|pid| !is_process_alive(*pid)));
+    }
+
+    // runs the scripts in the folder and returns the execution without waiting for it to finish
+    fn start_test_scripts(
+        directory: &std::path::Path,
+        scripts: Vec<config::OriginalScriptDefinition>,
+    ) -> ScriptExecutionData {
+        start_test_scripts_with_config(directory, scripts, &config::get_test_config(directory))
+    }
+
+    fn start_test_scripts_with_config(
+        directory: &std::path::Path,
+        scripts: Vec<config::OriginalScriptDefinition>,
+        app_config: &config::AppConfig,
+    ) -> ScriptExecutionData {
+        let mut execution_data = ScriptExecutionData::new();
+        execution_data.scripts_to_run = scripts
+            .into_iter()
+            .map(|mut script| {
+                script.working_directory = config::PathConfig {
+                    path: directory.to_string_lossy().to_string(),
+                    path_type: config::PathType::WorkingDirRelative,
+                };
+                ExecutionScript::from_original(script)
+            })
+            .collect();
+        run_scripts(
+            &mut execution_data,
+            &directory.join("logs"),
+            false,
+            app_config,
+            Arc::new(Mutex::new(RingBuffer::new(Default::default()))),
+            Arc::new(Mutex::new(ScriptOutputs::new())),
+            0,
+        );
+        execution_data
+    }
+
+    // waits for the execution to finish and returns the final statuses of its scripts
+    fn wait_test_scripts(mut execution_data: ScriptExecutionData) -> Vec<ScriptExecutionStatus> {
+        execution_data
+            .thread_join_handle
+            .take()
+            .unwrap()
+            .join()
+            .unwrap();
+        let mut statuses: Vec<Option<ScriptExecutionStatus>> =
+            vec![None; execution_data.scripts_to_run.len()];
+        for (script_idx, status) in execution_data.progress_receiver.unwrap().try_iter() {
+            statuses[script_idx] = Some(status);
+        }
+        statuses.into_iter().map(Option::unwrap).collect()
+    }
+
+    // the pids of the script process and its child, from every attempt
+    #[cfg(unix)]
+    fn read_test_pids(directory: &std::path::Path) -> Vec<i32> {
+        std::fs::read_to_string(directory.join("pids"))
+            .unwrap()
+            .lines()
+            .map(|line| line.trim().parse().unwrap())
+            .collect()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_script_that_times_out_is_killed_and_retried() {
+        let directory = TestDirectory::new("script_timeout");
+        let script = config::OriginalScriptDefinition {
+            name: "sleeping".to_string(),
+            command: config::PathConfig {
+                path: "echo $$ >> pids; sleep 60 & echo $! >> pids; wait".to_string(),
+                path_type: config::PathType::WorkingDirRelative,
+            },
+            timeout_sec: 1.0,
+            autorerun_count: 1,
+            ..Default::default()
+        };
+
+        let start_time = Instant::now();
+        let statuses = wait_test_scripts(start_test_scripts(&directory, vec![script]));
+        assert!(start_time.elapsed() < Duration::from_secs(30));
+
+        let status = &statuses[0];
+        assert!(status.result == ScriptResultStatus::Failed);
+        assert_eq!(status.retry_count, 1);
+        assert_eq!(status.exit_statuses.len(), 2);
+        // the processes of both attempts are gone, including the ones started by the script
+        let pids = read_test_pids(&directory);
+        assert_eq!(pids.len(), 4);
+        assert!(pids.iter().all(|pid| !is_process_alive(*pid)));
+
+        let execution_log =
+            std::fs::read_to_string(directory.join("logs").join("execution_steps.log")).unwrap();
+        assert_eq!(
+            execution_log
+                .matches("'sleeping' timed out after 1s")
+                .count(),
+            2
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_headless_script_gets_the_forwarded_input_and_end_of_file() {
+        let directory = TestDirectory::new("headless_input");
+        let script = config::OriginalScriptDefinition {
+            command: config::PathConfig {
+                path:
+                    "touch started; read answer; echo \"answer: $answer\" > answers; cat >> answers"
+                        .to_string(),
+                path_type: config::PathType::WorkingDirRelative,
+            },
+            accept_input: true,
+            ..Default::default()
+        };
+        let mut app_config = config::get_test_config(&directory);
+        app_config.is_headless = true;
+
+        let execution_data = start_test_scripts_with_config(&directory, vec![script], &app_config);
+        let end_time = Instant::now() + Duration::from_secs(10);
+        while !directory.join("started").exists() {
+            assert!(Instant::now() < end_time);
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        let send_input = |text: &str, is_end_of_input| {
+            execution_data.input_senders[0]
+                .send(ScriptInput {
+                    text: text.to_string(),
+                    is_secret: false,
+                    is_end_of_input,
+                })
+                .unwrap();
+        };
+        send_input("yes", false);
+        send_input("the rest", false);
+        send_input("", true);
+
+        let statuses = wait_test_scripts(execution_data);
+        assert!(statuses[0].result == ScriptResultStatus::Success);
+        assert_eq!(
+            std::fs::read_to_string(directory.join("answers")).unwrap(),
+            "answer: yes\nthe rest\n"
+        );
+    }
+
+    // starts a script that runs a process in the background, stops the execution and returns the
+    // final status with the pids of the script process and the background process
+    #[cfg(unix)]
+    fn stop_test_script_with_background_process(
+        directory: &std::path::Path,
+        keep_child_processes: bool,
+    ) -> (ScriptExecutionStatus, Vec<i32>) {
+        let script = config::OriginalScriptDefinition {
+            command: config::PathConfig {
+                path: "echo started; echo $$ >> pids; sleep 60 & echo $! >> pids; wait".to_string(),
+                path_type: config::PathType::WorkingDirRelative,
+            },
+            keep_child_processes,
+            ..Default::default()
+        };
+
+        let mut execution_data = start_test_scripts(directory, vec![script]);
+        let end_time = Instant::now() + Duration::from_secs(10);
+        while std::fs::read_to_string(directory.join("pids"))
+            .map_or(true, |pids| pids.lines().count() < 2)
+        {
+            assert!(Instant::now() < end_time);
+            std::thread::sleep(Duration::from_millis(50));
+        }
+
+        let stop_time = Instant::now();
+        request_stop_execution(&mut execution_data);
+        let mut statuses = wait_test_scripts(execution_data);
+        // a background process that is kept running still has the output of the script open
+        assert!(stop_time.elapsed() < Duration::from_secs(30));
+
+        let output_log = std::fs::read_dir(directory.join("logs"))
+            .unwrap()
+            .flatten()
+            .find(|entry| entry.file_name().to_string_lossy().ends_with("_output.log"))
+            .unwrap();
+        assert!(std::fs::read_to_string(output_log.path())
+            .unwrap()
+            .contains("started"));
+
+        (statuses.remove(0), read_test_pids(directory))
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_stopped_script_is_killed_with_its_background_processes() {
+        let directory = TestDirectory::new("script_stop");
+
+        let (status, pids) = stop_test_script_with_background_process(&directory, false);
+
+        assert!(status.result == ScriptResultStatus::Failed);
+        assert_eq!(status.retry_count, 0);
+        let end_time = Instant::now() + Duration::from_secs(5);
+        while pids.iter().any(|pid| is_process_alive(*pid)) && Instant::now() < end_time {
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        assert!(pids.iter().all(|pid| !is_process_alive(*pid)));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_stopped_script_can_keep_its_background_processes() {
+        let directory = TestDirectory::new("script_stop_keep_children");
+
+        let (status, pids) = stop_test_script_with_background_process(&directory, true);
+        let is_background_process_alive = is_process_alive(pids[1]);
+        unsafe {
+            kill(pids[1], SIGKILL);
+        }
+
+        assert!(status.result == ScriptResultStatus::Failed);
+        assert!(!is_process_alive(pids[0]));
+        assert!(is_background_process_alive);
+    }
+
+    fn make_test_scripts(count: usize) -> Vec<ExecutionScript> {
+        (0..count)
+            .map(|_| ExecutionScript::from_original(config::OriginalScriptDefinition::default()))
+            .collect()
+    }
+
+    #[test]
+    fn test_resolve_dependencies_of_sequential_and_parallel_scripts() {
+        let mut scripts = make_test_scripts(5);
+        // 0 -> (1, 2 in parallel) -> 3 waits for both -> 4 sequential
+        set_dependencies_from_indexes(
+            &mut scripts,
+            &[None, Some(vec![0]), Some(vec![0]), Some(vec![1, 2]), None],
+        );
+
+        assert_eq!(
+            resolve_dependencies(&scripts),
+            vec![vec![], vec![0], vec![0], vec![1, 2], vec![3]]
+        );
+
+        // a sequential script after parallel scripts waits for all of them
+        scripts[3].dependencies = Some(vec![scripts[1].uid.clone()]);
+        assert_eq!(resolve_dependencies(&scripts)[4], vec![2, 3]);
+
+        // if the scripts it waited for are gone, it waits for the previous scripts
+        scripts[3].dependencies = Some(vec![config::Guid::new()]);
+        assert_eq!(resolve_dependencies(&scripts)[3], vec![1, 2]);
+    }
+
+    #[test]
+    fn test_scripts_wait_for_the_scripts_of_their_run_conditions() {
+        let mut scripts = make_test_scripts(4);
+        for (script, name) in scripts
+            .iter_mut()
+            .zip(["build", "lint", "notify", "deploy"])
+        {
+            script.original.name = name.to_string();
+        }
+        // build and lint run in parallel, notify waits only for build
+        set_dependencies_from_indexes(
+            &mut scripts,
+            &[Some(vec![]), Some(vec![]), Some(vec![0]), Some(vec![0])],
+        );
+        scripts[2]
+            .original
+            .run_conditions
+            .push(config::RunCondition {
+                condition_type: config::RunConditionType::ScriptFailed,
+                value: " lint ".to_string(),
+            });
+        scripts[3]
+            .original
+            .run_conditions
+            .push(config::RunCondition {
+                condition_type: config::RunConditionType::ScriptSucceeded,
+                value: "tests".to_string(),
+            });
+
+        let dependencies = resolve_dependencies(&scripts);
+        assert_eq!(dependencies[2], vec![0, 1]);
+        assert_eq!(dependencies[3], vec![0]);
+        assert_eq!(find_unknown_run_condition_script(&scripts, 2), None);
+        assert_eq!(
+            find_unknown_run_condition_script(&scripts, 3),
+            Some("tests".to_string())
+        );
+    }
+
+    #[test]
+    fn test_appended_graph_waits_for_existing_scripts() {
+        let mut list = make_test_scripts(2);
+        let mut preset_scripts = make_test_scripts(3);
+        set_dependencies_from_indexes(
+            &mut preset_scripts,
+            &[Some(vec![]), Some(vec![]), Some(vec![0, 1])],
+        );
+
+        append_scripts_to_list(&mut list, preset_scripts);
+
+        assert_eq!(
+            resolve_dependencies(&list),
+            vec![vec![], vec![0], vec![1], vec![1], vec![2, 3]]
+        );
+        assert_eq!(
+            get_dependency_indexes(&list),
+            vec![None, None, Some(vec![1]), Some(vec![1]), Some(vec![2, 3])]
+        );
+    }
+
+    #[test]
+    fn test_kill_process_tree_closes_output_of_nested_shells() {
+        #[cfg(unix)]
+        let script = "sh -c 'sh -c \"sleep 60\" & sleep 60' & echo started; wait";
+        #[cfg(target_os = "windows")]
+        let script = "start /B cmd /C \"ping -n 60 127.0.0.1 >nul\" & echo started & ping -n 60 127.0.0.1 >nul";
+
+        let mut child = spawn_in_own_process_group(script);
+        let mut stdout = child.stdout.take().unwrap();
+
+        let mut started = [0u8; 7];
+        stdout.read_exact(&mut started).unwrap();
+        assert_eq!(&started, b"started");
+
+        kill_process_tree(&mut child);
+
+        // the pipe is closed only when every process that inherited it has exited
+        let (sender, receiver) = unbounded();
+        std::thread::spawn(move || {
+            let mut rest = Vec::new();
+            let _ = stdout.read_to_end(&mut rest);
+            let _ = sender.send(());
+        });
+        assert!(receiver.recv_timeout(Duration::from_secs(10)).is_ok());
+    }
+
+    #[test]
+    fn test_terminal_echo_keeps_script_output_and_adds_time_to_own_messages() {
+        let script_line = OutputLine {
+            text: "compiling\n".to_string(),
+            output_type: OutputType::StdErr,
+            timestamp: chrono::Local::now(),
+            highlight: None,
+            script_idx: Some(0),
+        };
+        let own_line = create_own_log_line("\"build\" failed".to_string(), OutputType::Error);
+
+        assert_eq!(get_terminal_text(&script_line), "compiling\n");
+        assert_eq!(
+            get_terminal_text(&own_line),
+            format!(
+                "[{}] \"build\" failed\n",
+                own_line.timestamp.format("%H:%M:%S")
+            )
+        );
+    }
+}
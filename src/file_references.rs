@@ -0,0 +1,258 @@
+// Copyright (C) Pavel Grebnev 2025
+// Distributed under the MIT License (license terms are at http://opensource.org/licenses/MIT).
+
+use std::ops::Range;
+use std::path::Path;
+
+// a position in a file printed by a compiler or a test runner, e.g. "src/main.rs:42:7"
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileReference {
+    // the part of the text that contains the whole reference
+    pub range: Range<usize>,
+    pub path: String,
+    pub line: u32,
+    pub column: Option<u32>,
+}
+
+pub fn find_file_references(text: &str) -> Vec<FileReference> {
+    let bytes = text.as_bytes();
+    let mut references = Vec::new();
+    let mut search_start = 0;
+    let mut colon_idx = 0;
+    while colon_idx < bytes.len() {
+        if bytes[colon_idx] != b':' {
+            colon_idx += 1;
+            continue;
+        }
+
+        let Some((line, line_end)) = parse_number(bytes, colon_idx + 1) else {
+            colon_idx += 1;
+            continue;
+        };
+        let path_start = find_path_start(bytes, search_start, colon_idx);
+        let path = &text[path_start.unwrap_or(colon_idx)..colon_idx];
+        let Some(path_start) = path_start.filter(|_| looks_like_file_path(path)) else {
+            colon_idx += 1;
+            continue;
+        };
+
+        let (column, end) = match bytes.get(line_end) {
+            Some(b':') => match parse_number(bytes, line_end + 1) {
+                Some((column, column_end)) => (Some(column), column_end),
+                None => (None, line_end),
+            },
+            _ => (None, line_end),
+        };
+
+        references.push(FileReference {
+            range: path_start..end,
+            path: path.to_string(),
+            line,
+            column,
+        });
+        search_start = end;
+        colon_idx = end;
+    }
+    references
+}
+
+// the command is split by spaces (parts with spaces can be put in double quotes),
+// "{file}", "{line}" and "{column}" are replaced in every part
+pub fn get_editor_command(
+    command_template: &str,
+    file_path: &Path,
+    line: u32,
+    column: Option<u32>,
+) -> Vec<String> {
+    let file_path = file_path.to_string_lossy();
+    let line = line.to_string();
+    let column = column.unwrap_or(1).to_string();
+    split_command_line(command_template)
+        .into_iter()
+        .map(|part| {
+            part.replace("{file}", &file_path)
+                .replace("{line}", &line)
+                .replace("{column}", &column)
+        })
+        .collect()
+}
+
+fn parse_number(bytes: &[u8], start: usize) -> Option<(u32, usize)> {
+    let digits_count = bytes[start.min(bytes.len())..]
+        .iter()
+        .take_while(|byte| byte.is_ascii_digit())
+        .count();
+    // a number followed by letters is not a line number (e.g. "file.rs:12px")
+    let end = start + digits_count;
+    if digits_count == 0
+        || bytes
+            .get(end)
+            .is_some_and(|byte| byte.is_ascii_alphabetic())
+    {
+        return None;
+    }
+    let number = std::str::from_utf8(&bytes[start..end]).ok()?.parse().ok()?;
+    Some((number, end))
+}
+
+// None if the text before the colon is a part of a URL
+fn find_path_start(bytes: &[u8], search_start: usize, colon_idx: usize) -> Option<usize> {
+    let mut start = colon_idx;
+    while start > search_start {
+        let byte = bytes[start - 1];
+        if byte == b':' {
+            let is_followed_by_slash = matches!(bytes.get(start), Some(b'\\' | b'/'));
+            // a drive letter of a Windows path, e.g. "C:\project\main.cpp"
+            let is_drive = is_followed_by_slash
+                && start >= 2
+                && bytes[start - 2].is_ascii_alphabetic()
+                && (start == 2 || is_path_delimiter(bytes[start - 3]));
+            if is_drive {
+                return Some(start - 2);
+            }
+            if is_followed_by_slash {
+                return None;
+            }
+            break;
+        }
+        if is_path_delimiter(byte) {
+            break;
+        }
+        start -= 1;
+    }
+    Some(start)
+}
+
+fn is_path_delimiter(byte: u8) -> bool {
+    byte.is_ascii_whitespace()
+        || matches!(
+            byte,
+            b'"' | b'\''
+                | b'`'
+                | b'('
+                | b')'
+                | b'['
+                | b']'
+                | b'<'
+                | b'>'
+                | b'{'
+                | b'}'
+                | b','
+                | b';'
+                | b'|'
+                | b'='
+                | b'@'
+        )
+}
+
+// either has a folder in it or a file name with an extension, so times like "10:30" and
+// words like "error:" are not taken for paths, and URLs are skipped
+fn looks_like_file_path(path: &str) -> bool {
+    if path.is_empty() || path.contains("://") {
+        return false;
+    }
+
+    let file_name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+    let has_extension = file_name.rsplit_once('.').is_some_and(|(name, extension)| {
+        !name.is_empty()
+            && !extension.is_empty()
+            && extension.chars().all(|c| c.is_ascii_alphanumeric())
+            && extension.chars().any(|c| c.is_ascii_alphabetic())
+    });
+    let has_folder = path.contains(['/', '\\']) && !file_name.is_empty();
+    has_extension || has_folder
+}
+
+fn split_command_line(command_line: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current_part = String::new();
+    let mut is_in_quotes = false;
+    let mut has_part = false;
+    for c in command_line.chars() {
+        match c {
+            '"' => {
+                is_in_quotes = !is_in_quotes;
+                has_part = true;
+            }
+            c if c.is_whitespace() && !is_in_quotes => {
+                if has_part {
+                    parts.push(std::mem::take(&mut current_part));
+                    has_part = false;
+                }
+            }
+            c => {
+                current_part.push(c);
+                has_part = true;
+            }
+        }
+    }
+    if has_part {
+        parts.push(current_part);
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find_paths(text: &str) -> Vec<(String, u32, Option<u32>)> {
+        find_file_references(text)
+            .into_iter()
+            .map(|reference| (reference.path, reference.line, reference.column))
+            .collect()
+    }
+
+    #[test]
+    fn test_file_references_are_found_in_tool_output() {
+        assert_eq!(
+            find_file_references("  --> src/main.rs:42:7"),
+            vec![FileReference {
+                range: 6..22,
+                path: "src/main.rs".to_string(),
+                line: 42,
+                column: Some(7),
+            }]
+        );
+        assert_eq!(
+            find_paths("main.cpp:10: error: 'x' was not declared (see \"include/x.h:3\")"),
+            vec![
+                ("main.cpp".to_string(), 10, None),
+                ("include/x.h".to_string(), 3, None)
+            ]
+        );
+        assert_eq!(
+            find_paths(r"C:\project\main.cpp:5:1: warning"),
+            vec![(r"C:\project\main.cpp".to_string(), 5, Some(1))]
+        );
+        assert_eq!(
+            find_paths("at Object.<anonymous> (/home/user/test.js:12:34)"),
+            vec![("/home/user/test.js".to_string(), 12, Some(34))]
+        );
+        assert_eq!(find_paths("started at 10:30:15, error: 2 failed"), vec![]);
+        assert_eq!(find_paths("see https://example.com/page.html:8080"), vec![]);
+        assert_eq!(find_paths("margin: style.css:12px"), vec![]);
+    }
+
+    #[test]
+    fn test_editor_command_gets_file_position() {
+        assert_eq!(
+            get_editor_command(
+                "code -g {file}:{line}:{column}",
+                Path::new("/tmp/main.rs"),
+                42,
+                None
+            ),
+            vec!["code", "-g", "/tmp/main.rs:42:1"]
+        );
+        assert_eq!(
+            get_editor_command(
+                "\"C:\\Program Files\\editor.exe\" \"{file}\" -n{line}",
+                Path::new("main file.rs"),
+                7,
+                Some(3)
+            ),
+            vec!["C:\\Program Files\\editor.exe", "main file.rs", "-n7"]
+        );
+    }
+}
@@ -0,0 +1,551 @@
+// Copyright (C) Pavel Grebnev 2025
+// Distributed under the MIT License (license terms are at http://opensource.org/licenses/MIT).
+
+use crate::config;
+use crate::parallel_execution_manager::ExecutionId;
+use notify::{self, Watcher};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+// the watchers can report a change some time after it was made, e.g. FSEvents batches the events
+const MIN_OWN_CHANGES_DELAY: Duration = Duration::from_millis(500);
+
+pub struct FileWatchTriggers {
+    states: Vec<FileWatchState>,
+    work_path: PathBuf,
+}
+
+#[derive(Default)]
+pub struct TriggeredRuns {
+    // scripts to start together with the index of their file watch
+    pub runs_to_start: Vec<(usize, config::Guid)>,
+    pub executions_to_stop: Vec<ExecutionId>,
+}
+
+struct FileWatchState {
+    file_watch: config::ScriptFileWatch,
+    // kept alive to keep receiving the events
+    _watcher: Option<notify::RecommendedWatcher>,
+    has_changes: Arc<AtomicBool>,
+    // the run starts when nothing has changed for the debounce time after this
+    last_change_time: Option<Instant>,
+    // a run that waits for the previous run of this file watch to finish or to be stopped
+    has_queued_run: bool,
+    last_execution_id: Option<ExecutionId>,
+    was_previous_run_active: bool,
+    previous_run_finish_time: Option<Instant>,
+}
+
+// the part of the path without wildcards is watched, the rest is matched against changed paths
+struct WatchPattern {
+    root: PathBuf,
+    glob_segments: Vec<String>,
+}
+
+impl FileWatchTriggers {
+    pub fn new() -> Self {
+        Self {
+            states: Vec::new(),
+            work_path: PathBuf::new(),
+        }
+    }
+
+    // recreates the watchers only for the file watches that changed
+    pub fn update_file_watches(
+        &mut self,
+        file_watches: &[config::ScriptFileWatch],
+        work_path: &Path,
+    ) {
+        if self.work_path == work_path
+            && self.states.len() == file_watches.len()
+            && self
+                .states
+                .iter()
+                .zip(file_watches)
+                .all(|(state, file_watch)| state.file_watch == *file_watch)
+        {
+            return;
+        }
+
+        if self.work_path != work_path {
+            self.states.clear();
+            self.work_path = work_path.to_path_buf();
+        }
+
+        let mut old_states = std::mem::take(&mut self.states);
+        for file_watch in file_watches {
+            let old_state_idx = old_states
+                .iter()
+                .position(|state| state.file_watch == *file_watch);
+            let state = match old_state_idx {
+                Some(idx) => old_states.swap_remove(idx),
+                None => create_file_watch_state(file_watch, work_path),
+            };
+            self.states.push(state);
+        }
+    }
+
+    pub fn take_triggered_runs(
+        &mut self,
+        now: Instant,
+        is_execution_running: impl Fn(ExecutionId) -> bool,
+    ) -> TriggeredRuns {
+        let mut result = TriggeredRuns::default();
+        for (idx, state) in self.states.iter_mut().enumerate() {
+            let is_previous_run_active = state.last_execution_id.is_some_and(&is_execution_running);
+            if state.was_previous_run_active && !is_previous_run_active {
+                state.previous_run_finish_time = Some(now);
+            }
+            state.was_previous_run_active = is_previous_run_active;
+
+            let policy = state.file_watch.running_script_policy;
+            let debounce = Duration::from_millis(state.file_watch.debounce_ms);
+            let is_own_change = policy == config::FileWatchRunningScriptPolicy::Ignore
+                && (is_previous_run_active
+                    || state.previous_run_finish_time.is_some_and(|finish_time| {
+                        now.saturating_duration_since(finish_time)
+                            < debounce.max(MIN_OWN_CHANGES_DELAY)
+                    }));
+            if state.has_changes.swap(false, Ordering::Relaxed) && !is_own_change {
+                state.last_change_time = Some(now);
+            }
+
+            if let Some(last_change_time) = state.last_change_time {
+                // the queued run waits for the last writes of the running one to settle
+                let debounce_start_time = match state.previous_run_finish_time {
+                    Some(finish_time) if policy == config::FileWatchRunningScriptPolicy::Queue => {
+                        last_change_time.max(finish_time)
+                    }
+                    _ => last_change_time,
+                };
+                let is_waiting_for_previous_run =
+                    is_previous_run_active && policy == config::FileWatchRunningScriptPolicy::Queue;
+                if !is_waiting_for_previous_run
+                    && now.saturating_duration_since(debounce_start_time) >= debounce
+                {
+                    state.last_change_time = None;
+                    state.has_queued_run = true;
+
+                    if is_previous_run_active
+                        && state.file_watch.running_script_policy
+                            == config::FileWatchRunningScriptPolicy::Restart
+                    {
+                        if let Some(execution_id) = state.last_execution_id {
+                            result.executions_to_stop.push(execution_id);
+                        }
+                    }
+                }
+            }
+
+            if state.has_queued_run && !is_previous_run_active {
+                state.has_queued_run = false;
+                result
+                    .runs_to_start
+                    .push((idx, state.file_watch.script_uid.clone()));
+            }
+        }
+        result
+    }
+
+    pub fn on_run_started(&mut self, file_watch_idx: usize, execution_id: ExecutionId) {
+        if let Some(state) = self.states.get_mut(file_watch_idx) {
+            state.last_execution_id = Some(execution_id);
+        }
+    }
+}
+
+fn create_file_watch_state(
+    file_watch: &config::ScriptFileWatch,
+    work_path: &Path,
+) -> FileWatchState {
+    let has_changes = Arc::new(AtomicBool::new(false));
+
+    let patterns = parse_watch_patterns(&file_watch.paths, work_path);
+    let ignored_patterns = parse_watch_patterns(&file_watch.ignored_paths, work_path);
+
+    let roots: Vec<PathBuf> = patterns
+        .iter()
+        .map(|pattern| pattern.root.clone())
+        .collect();
+    let watcher = {
+        let has_changes = has_changes.clone();
+        notify::recommended_watcher(move |res: Result<notify::Event, _>| {
+            let Ok(event) = res else {
+                return;
+            };
+            if event.kind.is_access() {
+                return;
+            }
+            if event
+                .paths
+                .iter()
+                .any(|path| is_triggering_path(&patterns, &ignored_patterns, path))
+            {
+                has_changes.store(true, Ordering::Relaxed);
+            }
+        })
+    };
+
+    let watcher = match watcher {
+        Ok(mut watcher) => {
+            for root in roots {
+                let recursive_mode = if root.is_dir() {
+                    notify::RecursiveMode::Recursive
+                } else {
+                    notify::RecursiveMode::NonRecursive
+                };
+                if let Err(error) = watcher.watch(&root, recursive_mode) {
+                    eprintln!(
+                        "Can't watch '{}' for changes: {}",
+                        root.to_string_lossy(),
+                        error
+                    );
+                }
+            }
+            Some(watcher)
+        }
+        Err(error) => {
+            eprintln!("Can't create a file watcher: {}", error);
+            None
+        }
+    };
+
+    FileWatchState {
+        file_watch: file_watch.clone(),
+        _watcher: watcher,
+        has_changes,
+        last_change_time: None,
+        has_queued_run: false,
+        last_execution_id: None,
+        was_previous_run_active: false,
+        previous_run_finish_time: None,
+    }
+}
+
+fn parse_watch_patterns(paths: &[String], work_path: &Path) -> Vec<WatchPattern> {
+    paths
+        .iter()
+        .filter(|path| !path.trim().is_empty())
+        .map(|path| parse_watch_pattern(path.trim(), work_path))
+        .collect()
+}
+
+fn is_triggering_path(
+    patterns: &[WatchPattern],
+    ignored_patterns: &[WatchPattern],
+    path: &Path,
+) -> bool {
+    let does_match = |pattern: &WatchPattern| does_pattern_match_path(pattern, path);
+    patterns.iter().any(does_match) && !ignored_patterns.iter().any(does_match)
+}
+
+fn parse_watch_pattern(path: &str, work_path: &Path) -> WatchPattern {
+    // Windows understands both separators, elsewhere the paths from Windows users are converted
+    #[cfg(not(target_os = "windows"))]
+    let path = path.replace('\\', "/");
+    let path = Path::new(&path);
+
+    // an absolute path replaces the work path
+    let mut root = if path.is_absolute() {
+        PathBuf::new()
+    } else {
+        work_path.to_path_buf()
+    };
+    let mut glob_segments = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(segment) => {
+                let segment = segment.to_string_lossy();
+                if !glob_segments.is_empty() || segment.contains(['*', '?']) {
+                    glob_segments.push(segment.to_string());
+                } else {
+                    root.push(segment.as_ref());
+                }
+            }
+            Component::ParentDir if !glob_segments.is_empty() => {
+                glob_segments.push("..".to_string());
+            }
+            // the drive or the root folder of an absolute path, and ".." before the wildcards
+            Component::Prefix(_) | Component::RootDir | Component::ParentDir => {
+                root.push(component);
+            }
+            Component::CurDir => {}
+        }
+    }
+
+    WatchPattern {
+        root: canonicalize_existing_part(&root),
+        glob_segments,
+    }
+}
+
+// the watchers report real absolute paths (e.g. FSEvents resolves symlinks), so the root should be
+// the same to match them, even if the path doesn't exist yet
+fn canonicalize_existing_part(path: &Path) -> PathBuf {
+    for existing_path in path.ancestors() {
+        if let Ok(canonical_path) = std::fs::canonicalize(existing_path) {
+            return match path.strip_prefix(existing_path) {
+                Ok(rest) if !rest.as_os_str().is_empty() => canonical_path.join(rest),
+                _ => canonical_path,
+            };
+        }
+    }
+    path.to_path_buf()
+}
+
+fn does_pattern_match_path(pattern: &WatchPattern, path: &Path) -> bool {
+    let Ok(relative_path) = path.strip_prefix(&pattern.root) else {
+        return false;
+    };
+
+    // a path without wildcards matches everything inside it
+    if pattern.glob_segments.is_empty() {
+        return true;
+    }
+
+    let path_segments: Vec<String> = relative_path
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy().to_string())
+        .collect();
+    does_glob_match_segments(&pattern.glob_segments, &path_segments)
+}
+
+fn does_glob_match_segments(glob_segments: &[String], path_segments: &[String]) -> bool {
+    match glob_segments.first() {
+        None => path_segments.is_empty(),
+        // "**" matches any number of directories, including none
+        Some(glob_segment) if glob_segment == "**" => (0..=path_segments.len()).any(|skipped| {
+            does_glob_match_segments(&glob_segments[1..], &path_segments[skipped..])
+        }),
+        Some(glob_segment) => {
+            !path_segments.is_empty()
+                && does_wildcard_match(glob_segment.as_bytes(), path_segments[0].as_bytes())
+                && does_glob_match_segments(&glob_segments[1..], &path_segments[1..])
+        }
+    }
+}
+
+// "*" matches any number of characters, "?" matches one character
+fn does_wildcard_match(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some(b'*') => {
+            (0..=text.len()).any(|skipped| does_wildcard_match(&pattern[1..], &text[skipped..]))
+        }
+        Some(b'?') => !text.is_empty() && does_wildcard_match(&pattern[1..], &text[1..]),
+        Some(character) => {
+            text.first() == Some(character) && does_wildcard_match(&pattern[1..], &text[1..])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestDirectory;
+
+    #[test]
+    fn test_watch_pattern_is_split_into_watched_root_and_glob() {
+        let pattern = parse_watch_pattern("src/**/*.rs", Path::new("/project"));
+        assert_eq!(pattern.root, PathBuf::from("/project/src"));
+        assert_eq!(pattern.glob_segments, vec!["**", "*.rs"]);
+
+        let pattern = parse_watch_pattern("build.rs", Path::new("/project"));
+        assert_eq!(pattern.root, PathBuf::from("/project/build.rs"));
+        assert!(pattern.glob_segments.is_empty());
+    }
+
+    #[test]
+    fn test_glob_patterns_match_changed_paths() {
+        let pattern = parse_watch_pattern("src/**/*.rs", Path::new("/project"));
+        assert!(does_pattern_match_path(
+            &pattern,
+            Path::new("/project/src/main.rs")
+        ));
+        assert!(does_pattern_match_path(
+            &pattern,
+            Path::new("/project/src/a/b/lib.rs")
+        ));
+        assert!(!does_pattern_match_path(
+            &pattern,
+            Path::new("/project/src/a/b/lib.rs.bak")
+        ));
+        assert!(!does_pattern_match_path(
+            &pattern,
+            Path::new("/project/target/main.rs")
+        ));
+
+        let pattern = parse_watch_pattern("proto/v?/*.proto", Path::new("/project"));
+        assert!(does_pattern_match_path(
+            &pattern,
+            Path::new("/project/proto/v1/api.proto")
+        ));
+        assert!(!does_pattern_match_path(
+            &pattern,
+            Path::new("/project/proto/v10/api.proto")
+        ));
+
+        let pattern = parse_watch_pattern("assets", Path::new("/project"));
+        assert!(does_pattern_match_path(
+            &pattern,
+            Path::new("/project/assets/icons/a.png")
+        ));
+        assert!(!does_pattern_match_path(
+            &pattern,
+            Path::new("/project/assets2/a.png")
+        ));
+    }
+
+    #[test]
+    fn test_changes_of_ignored_paths_do_not_trigger_runs() {
+        let work_path = Path::new("/project");
+        let patterns = parse_watch_patterns(&["src".to_string()], work_path);
+        let ignored_patterns = parse_watch_patterns(
+            &["src/generated".to_string(), "**/*.log".to_string()],
+            work_path,
+        );
+
+        assert!(is_triggering_path(
+            &patterns,
+            &ignored_patterns,
+            Path::new("/project/src/main.rs")
+        ));
+        assert!(!is_triggering_path(
+            &patterns,
+            &ignored_patterns,
+            Path::new("/project/src/generated/api.rs")
+        ));
+        assert!(!is_triggering_path(
+            &patterns,
+            &ignored_patterns,
+            Path::new("/project/src/tests/output.log")
+        ));
+    }
+
+    fn make_test_triggers(
+        running_script_policy: config::FileWatchRunningScriptPolicy,
+    ) -> FileWatchTriggers {
+        let file_watch = config::ScriptFileWatch {
+            script_uid: config::Guid::new(),
+            paths: Vec::new(),
+            ignored_paths: Vec::new(),
+            debounce_ms: 1000,
+            running_script_policy,
+        };
+        let mut file_watch_triggers = FileWatchTriggers::new();
+        file_watch_triggers.update_file_watches(&[file_watch], Path::new("/project"));
+        file_watch_triggers
+    }
+
+    fn report_change(file_watch_triggers: &FileWatchTriggers) {
+        file_watch_triggers.states[0]
+            .has_changes
+            .store(true, Ordering::Relaxed);
+    }
+
+    #[test]
+    fn test_changes_made_while_the_triggered_run_is_active_are_ignored() {
+        let mut executions = sparse_set_container::SparseSet::new();
+        let execution_id = executions.push(0);
+        let mut file_watch_triggers =
+            make_test_triggers(config::FileWatchRunningScriptPolicy::Ignore);
+        let start_time = Instant::now();
+        let at = |ms| start_time + Duration::from_millis(ms);
+
+        report_change(&file_watch_triggers);
+        file_watch_triggers.take_triggered_runs(at(0), |_| false);
+        let first_runs = file_watch_triggers.take_triggered_runs(at(1000), |_| false);
+        file_watch_triggers.on_run_started(0, execution_id);
+
+        // the script writes to the watched files while it runs and its last writes come late
+        report_change(&file_watch_triggers);
+        file_watch_triggers.take_triggered_runs(at(1100), |_| true);
+        file_watch_triggers.take_triggered_runs(at(5000), |_| false);
+        report_change(&file_watch_triggers);
+        file_watch_triggers.take_triggered_runs(at(5500), |_| false);
+        let runs_after_own_changes = file_watch_triggers.take_triggered_runs(at(9000), |_| false);
+
+        report_change(&file_watch_triggers);
+        file_watch_triggers.take_triggered_runs(at(10000), |_| false);
+        let runs_after_user_change = file_watch_triggers.take_triggered_runs(at(11000), |_| false);
+
+        assert_eq!(first_runs.runs_to_start.len(), 1);
+        assert!(runs_after_own_changes.runs_to_start.is_empty());
+        assert_eq!(runs_after_user_change.runs_to_start.len(), 1);
+    }
+
+    #[test]
+    fn test_queued_run_starts_after_the_running_one_has_settled() {
+        let mut executions = sparse_set_container::SparseSet::new();
+        let execution_id = executions.push(0);
+        let mut file_watch_triggers =
+            make_test_triggers(config::FileWatchRunningScriptPolicy::Queue);
+        let start_time = Instant::now();
+        let at = |ms| start_time + Duration::from_millis(ms);
+        file_watch_triggers.on_run_started(0, execution_id);
+
+        report_change(&file_watch_triggers);
+        file_watch_triggers.take_triggered_runs(at(0), |_| true);
+        let runs_while_running = file_watch_triggers.take_triggered_runs(at(3000), |_| true);
+        let runs_right_after_finish = file_watch_triggers.take_triggered_runs(at(3100), |_| false);
+        let runs_after_debounce = file_watch_triggers.take_triggered_runs(at(4100), |_| false);
+
+        assert!(runs_while_running.runs_to_start.is_empty());
+        assert!(runs_while_running.executions_to_stop.is_empty());
+        assert!(runs_right_after_finish.runs_to_start.is_empty());
+        assert_eq!(runs_after_debounce.runs_to_start.len(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_relative_and_symlinked_work_paths_match_real_changed_paths() {
+        let directory = TestDirectory::new("file_watch_symlink");
+        let real_work_path = directory.join("real");
+        std::fs::create_dir_all(real_work_path.join("src")).unwrap();
+        let linked_work_path = directory.join("link");
+        std::os::unix::fs::symlink(&real_work_path, &linked_work_path).unwrap();
+        let real_work_path = std::fs::canonicalize(real_work_path).unwrap();
+
+        let pattern = parse_watch_pattern("src/**/*.rs", &linked_work_path);
+        assert_eq!(pattern.root, real_work_path.join("src"));
+        assert!(does_pattern_match_path(
+            &pattern,
+            &real_work_path.join("src/a/lib.rs")
+        ));
+
+        // the folders that don't exist yet are kept as they are
+        let pattern = parse_watch_pattern("generated/*.rs", &linked_work_path);
+        assert_eq!(pattern.root, real_work_path.join("generated"));
+
+        let current_dir = std::fs::canonicalize(std::env::current_dir().unwrap()).unwrap();
+        let pattern = parse_watch_pattern("./src/*.rs", Path::new("."));
+        assert_eq!(pattern.root, current_dir.join("src"));
+        assert!(does_pattern_match_path(
+            &pattern,
+            &current_dir.join("src/main.rs")
+        ));
+    }
+
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn test_windows_absolute_paths_replace_the_work_path() {
+        let pattern = parse_watch_pattern("C:\\src\\*.rs", Path::new("D:\\project"));
+        assert!(pattern.root.ends_with("src"));
+        assert!(pattern
+            .root
+            .to_string_lossy()
+            .to_uppercase()
+            .contains("C:\\SRC"));
+        assert_eq!(pattern.glob_segments, vec!["*.rs"]);
+        assert!(does_pattern_match_path(
+            &pattern,
+            &pattern.root.join("main.rs")
+        ));
+
+        let pattern = parse_watch_pattern("C:/src/**/*.rs", Path::new("D:\\project"));
+        assert_eq!(pattern.glob_segments, vec!["**", "*.rs"]);
+        assert!(pattern.root.ends_with("src"));
+    }
+}
@@ -0,0 +1,420 @@
+// Copyright (C) Pavel Grebnev 2025
+// Distributed under the MIT License (license terms are at http://opensource.org/licenses/MIT).
+
+use crate::config;
+use crate::execution_history;
+use crate::execution_report;
+use crate::execution_thread;
+use crate::log_retention;
+use crate::main_window_utils::{
+    get_scripts_from_scenario_execution, is_original_script_missing_arguments,
+};
+use crate::parallel_execution_manager;
+use crate::scenario;
+use crossbeam_channel::{unbounded, Receiver, TryRecvError};
+use std::time::Duration;
+
+const EXIT_CODE_SUCCESS: i32 = 0;
+const EXIT_CODE_SCRIPTS_FAILED: i32 = 1;
+const EXIT_CODE_SETUP_ERROR: i32 = 2;
+
+// runs the scenario (or a single script from --run) without creating a window
+// returns the exit code for the process
+pub fn run_headless() -> i32 {
+    attach_to_parent_console();
+
+    let app_config = config::get_app_config_copy();
+
+    if let Some(error) = &app_config.config_read_error {
+        eprintln!("{}", get_config_error_description(error));
+        return EXIT_CODE_SETUP_ERROR;
+    }
+
+    let scenario = match scenario::get_scenario_copy() {
+        None => {
+            eprintln!("'--headless' requires either '--scenario' or '--run' argument");
+            return EXIT_CODE_SETUP_ERROR;
+        }
+        Some(Err(err)) => {
+            eprintln!("{}", err);
+            return EXIT_CODE_SETUP_ERROR;
+        }
+        Some(Ok(scenario)) => scenario,
+    };
+
+    run_scenario(&app_config, &scenario)
+}
+
+fn run_scenario(app_config: &config::AppConfig, scenario: &scenario::Scenario) -> i32 {
+    let mut executions_to_start = Vec::new();
+    for execution in &scenario.parallel_executions {
+        let (scripts, missing_scripts) = get_scripts_from_scenario_execution(app_config, execution);
+
+        if !missing_scripts.is_empty() {
+            let formatted_script_uids = missing_scripts
+                .iter()
+                .map(|uid| format!("'{}'", uid.to_string()))
+                .collect::<Vec<_>>()
+                .join(", ");
+            eprintln!(
+                "Some scripts specified in the scenario are missing: {}",
+                formatted_script_uids
+            );
+            return EXIT_CODE_SETUP_ERROR;
+        }
+
+        let scripts_missing_arguments = scripts
+            .iter()
+            .filter(|script| is_original_script_missing_arguments(&script.original))
+            .map(|script| format!("'{}'", script.original.name))
+            .collect::<Vec<_>>();
+        if !scripts_missing_arguments.is_empty() {
+            eprintln!(
+                "Some scripts are missing required arguments, they can't be run headless: {}",
+                scripts_missing_arguments.join(", ")
+            );
+            return EXIT_CODE_SETUP_ERROR;
+        }
+
+        if execution.only_schedule == Some(true) {
+            println!("Scenario execution is marked as 'only_schedule', running it anyway since there is no window to schedule it in");
+        }
+
+        if scripts.is_empty() {
+            continue;
+        }
+
+        executions_to_start.push(scripts);
+    }
+
+    // the terminal is read only when needed, reading it from a background job would stop the runner
+    let terminal_input = executions_to_start
+        .iter()
+        .flatten()
+        .any(|script| script.original.accept_input)
+        .then(start_reading_terminal_input);
+    let mut scripts_with_closed_input = Vec::new();
+
+    let mut execution_manager =
+        parallel_execution_manager::ParallelExecutionManager::new(&app_config.paths.logs_path);
+    for scripts in executions_to_start {
+        execution_manager.start_new_execution(app_config, scripts);
+    }
+
+    if !execution_manager.has_any_execution_started() {
+        println!("Nothing to run");
+        return EXIT_CODE_SUCCESS;
+    }
+
+    while !execution_manager.has_all_executions_finished()
+        || execution_manager.is_waiting_on_any_execution_to_finish()
+    {
+        if let Some(terminal_input) = &terminal_input {
+            forward_terminal_input(
+                &execution_manager,
+                terminal_input,
+                &mut scripts_with_closed_input,
+            );
+        }
+        let tick_result = execution_manager.tick(app_config);
+        for execution_id in tick_result.just_finished_executions {
+            if let Some(execution) = execution_manager.get_started_executions().get(execution_id) {
+                let record = execution_history::make_record(execution);
+                execution_history::append_record(&app_config.paths.logs_path, &record);
+                execution_report::write_execution_report(app_config, execution.get_name(), &record);
+            }
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+
+    print_summary(&execution_manager);
+
+    let log_cleanup_request = log_retention::LogCleanupRequest::new(
+        app_config,
+        execution_manager
+            .get_started_executions()
+            .values()
+            .map(|execution| execution.get_log_folder_path().clone())
+            .collect(),
+        Vec::new(),
+        &execution_history::read_records(&app_config.paths.logs_path),
+    );
+    if log_cleanup_request.is_needed() {
+        log_cleanup_request.run();
+    }
+
+    if execution_manager.has_any_execution_failed() {
+        EXIT_CODE_SCRIPTS_FAILED
+    } else {
+        EXIT_CODE_SUCCESS
+    }
+}
+
+// the sender is dropped when the input is closed
+fn start_reading_terminal_input() -> Receiver<String> {
+    let (sender, receiver) = unbounded();
+    std::thread::spawn(move || {
+        for line in std::io::stdin().lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+// sends the lines typed in the terminal to the running script that accepts input, once the input
+// is closed every such script (and every retry of it) gets the end of file
+fn forward_terminal_input(
+    execution_manager: &parallel_execution_manager::ParallelExecutionManager,
+    terminal_input: &Receiver<String>,
+    scripts_with_closed_input: &mut Vec<(config::Guid, usize)>,
+) {
+    let Some((execution, script)) = execution_manager
+        .get_started_executions()
+        .values()
+        .find_map(|execution| {
+            execution
+                .get_running_script_accepting_input()
+                .map(|script| (execution, script))
+        })
+    else {
+        // the lines typed ahead wait for the next script that accepts input
+        return;
+    };
+    let script_attempt = (script.script.uid.clone(), script.status.retry_count);
+
+    loop {
+        match terminal_input.try_recv() {
+            Ok(text) => execution.send_input_to_running_script(execution_thread::ScriptInput {
+                text,
+                is_secret: false,
+                is_end_of_input: false,
+            }),
+            Err(TryRecvError::Empty) => break,
+            Err(TryRecvError::Disconnected) => {
+                if !scripts_with_closed_input.contains(&script_attempt) {
+                    execution.send_input_to_running_script(execution_thread::ScriptInput {
+                        text: String::new(),
+                        is_secret: false,
+                        is_end_of_input: true,
+                    });
+                    scripts_with_closed_input.push(script_attempt);
+                }
+                break;
+            }
+        }
+    }
+}
+
+fn print_summary(execution_manager: &parallel_execution_manager::ParallelExecutionManager) {
+    println!();
+    for execution in execution_manager.get_started_executions().values() {
+        println!(
+            "{} (logs: {})",
+            execution.get_name(),
+            execution.get_log_folder_path().to_string_lossy()
+        );
+        for record in execution.get_scheduled_scripts_cache() {
+            let status = match record.status.result {
+                execution_thread::ScriptResultStatus::Success => "succeeded",
+                execution_thread::ScriptResultStatus::Warning => "succeeded with a warning",
+                execution_thread::ScriptResultStatus::Failed => "failed",
+                execution_thread::ScriptResultStatus::Skipped => "skipped",
+                execution_thread::ScriptResultStatus::Disconnected => "disconnected",
+            };
+            let duration = match (record.status.start_time, record.status.finish_time) {
+                (Some(start_time), Some(finish_time)) => {
+                    format!(" in {:.1}s", (finish_time - start_time).as_secs_f32())
+                }
+                _ => String::new(),
+            };
+            let retries = if record.status.retry_count > 0 {
+                format!(" after {} retries", record.status.retry_count)
+            } else {
+                String::new()
+            };
+            // a success is expected to have exit code 0, for the rest it tells what happened
+            let exit_status = match record.status.exit_statuses.last() {
+                Some(exit_status)
+                    if record.status.result != execution_thread::ScriptResultStatus::Success =>
+                {
+                    format!(" ({})", exit_status)
+                }
+                _ => String::new(),
+            };
+            println!(
+                "  '{}' {}{}{}{}",
+                record.script.original.name, status, retries, duration, exit_status
+            );
+            for problem in &record.status.problems {
+                println!("    {}: {}", problem.severity, problem.text);
+            }
+        }
+    }
+}
+
+fn get_config_error_description(error: &config::ConfigReadError) -> String {
+    match error {
+        config::ConfigReadError::FileReadError { file_path, error } => format!(
+            "Failed to read file '{}': {}",
+            file_path.to_string_lossy(),
+            error
+        ),
+        config::ConfigReadError::DataParseJsonError { file_path, error } => format!(
+            "Failed to parse JSON data from file '{}': {}",
+            file_path.to_string_lossy(),
+            error
+        ),
+        config::ConfigReadError::UpdaterUnknownVersion {
+            file_path,
+            version,
+            latest_version,
+        } => format!(
+            "Unknown version of the config file '{}'. The version of the file is '{}', latest known version is '{}'",
+            file_path.to_string_lossy(),
+            version,
+            latest_version
+        ),
+        config::ConfigReadError::UpdaterValidatorError {
+            file_path,
+            error,
+            version,
+        } => format!(
+            "Version check of file '{}' failed on version '{}' with error: {}",
+            file_path.to_string_lossy(),
+            version,
+            error
+        ),
+        config::ConfigReadError::ConfigDeserializeError { file_path, error } => format!(
+            "Failed to deserialize config file '{}': {}",
+            file_path.to_string_lossy(),
+            error
+        ),
+        config::ConfigReadError::ConfigSerializeError { error } => {
+            format!("Failed to serialize a config file: {}", error)
+        }
+        config::ConfigReadError::FileWriteError { file_path, error } => format!(
+            "Failed to write to file '{}': {}",
+            file_path.to_string_lossy(),
+            error
+        ),
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn attach_to_parent_console() {
+    // the app is built for the windows subsystem, so it doesn't get a console by default
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn attach_to_parent_console() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestDirectory;
+
+    // every inner list is a parallel execution of the scripts with the given commands
+    fn run_test_scenario(directory: &std::path::Path, executions: &[&[&str]]) -> i32 {
+        let mut app_config = config::get_test_config(directory);
+        app_config.is_headless = true;
+
+        let mut scenario = scenario::Scenario {
+            format_version: "1".to_string(),
+            start_focused: None,
+            parallel_executions: Vec::new(),
+        };
+        for commands in executions {
+            let mut scripts = Vec::new();
+            for command in *commands {
+                let script = config::OriginalScriptDefinition {
+                    name: command.to_string(),
+                    command: config::PathConfig {
+                        path: command.to_string(),
+                        path_type: config::PathType::WorkingDirRelative,
+                    },
+                    working_directory: config::PathConfig {
+                        path: directory.to_string_lossy().to_string(),
+                        path_type: config::PathType::WorkingDirRelative,
+                    },
+                    ..Default::default()
+                };
+                scripts.push(scenario::Script {
+                    uid: script.uid.clone(),
+                    name: None,
+                    arguments: None,
+                    placeholders: None,
+                    timeout_sec: None,
+                });
+                app_config
+                    .script_definitions
+                    .push(config::ScriptDefinition::Original(script));
+            }
+            scenario.parallel_executions.push(scenario::Execution {
+                scripts,
+                only_schedule: None,
+            });
+        }
+
+        run_scenario(&app_config, &scenario)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_successful_run_returns_success_exit_code() {
+        let directory = TestDirectory::new("headless_success");
+
+        let exit_code = run_test_scenario(&directory, &[&["echo first", "touch second"]]);
+
+        assert_eq!(exit_code, EXIT_CODE_SUCCESS);
+        assert!(directory.join("second").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_failed_script_skips_the_next_ones_and_fails_the_run() {
+        let directory = TestDirectory::new("headless_failure");
+
+        let exit_code = run_test_scenario(
+            &directory,
+            &[&["exit 3", "touch skipped"], &["touch parallel"]],
+        );
+
+        assert_eq!(exit_code, EXIT_CODE_SCRIPTS_FAILED);
+        assert!(!directory.join("skipped").exists());
+        assert!(directory.join("parallel").exists());
+    }
+
+    #[test]
+    fn test_missing_script_is_a_setup_error() {
+        let directory = TestDirectory::new("headless_missing_script");
+        let app_config = config::get_test_config(&directory);
+        let scenario = scenario::Scenario {
+            format_version: "1".to_string(),
+            start_focused: None,
+            parallel_executions: vec![scenario::Execution {
+                scripts: vec![scenario::Script {
+                    uid: config::Guid::new(),
+                    name: None,
+                    arguments: None,
+                    placeholders: None,
+                    timeout_sec: None,
+                }],
+                only_schedule: None,
+            }],
+        };
+
+        assert_eq!(run_scenario(&app_config, &scenario), EXIT_CODE_SETUP_ERROR);
+    }
+}
@@ -0,0 +1,430 @@
+// Copyright (C) Pavel Grebnev 2025
+// Distributed under the MIT License (license terms are at http://opensource.org/licenses/MIT).
+
+// Every script output file (and its index) of a finished execution's log folder is compressed
+// separately to "<name>.gz", so the folder structure stays the same and the files can be found by
+// their original paths. The other files of the folder are read by other tools and are kept as is.
+// To read them, the files are decompressed on demand to a temporary folder of this app instance,
+// where every log folder gets its own subfolder named after the hash of its full path.
+
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use std::fs::File;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io::{BufReader, BufWriter, Write};
+#[cfg(unix)]
+use std::os::unix::fs::DirBuilderExt;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const COMPRESSED_EXTENSION: &str = "gz";
+const EXTRACTED_LOGS_FOLDER_PREFIX: &str = "scripter_extracted_logs";
+
+// created on the first extraction, removed when the app exits
+static EXTRACTED_LOGS_DIRECTORY: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+pub fn get_compressed_path(path: &Path) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".");
+    file_name.push(COMPRESSED_EXTENSION);
+    path.with_file_name(file_name)
+}
+
+// true if the log file exists either as is or compressed
+pub fn log_exists(path: &Path) -> bool {
+    path.exists() || get_compressed_path(path).exists()
+}
+
+pub fn is_directory_compressed(directory: &Path) -> bool {
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return false;
+    };
+    entries
+        .flatten()
+        .any(|entry| is_compressed_file(&entry.path()))
+}
+
+// compresses the script outputs of the folder that are not compressed yet, returns true if anything was compressed
+pub fn compress_directory(directory: &Path) -> std::io::Result<bool> {
+    let mut has_compressed_files = false;
+    for entry in std::fs::read_dir(directory)?.flatten() {
+        let path = entry.path();
+        if !entry.file_type()?.is_file() || !is_script_output_file(&path) {
+            continue;
+        }
+
+        compress_file(&path)?;
+        has_compressed_files = true;
+    }
+    Ok(has_compressed_files)
+}
+
+// returns a path that can be read or opened by other apps, decompressing the log if needed
+// works with both the log files and the log folders
+pub fn get_readable_path(path: &Path) -> PathBuf {
+    let result = if path.is_dir() {
+        get_readable_directory(path)
+    } else {
+        get_readable_file(path)
+    };
+
+    match result {
+        Ok(readable_path) => readable_path,
+        Err(err) => {
+            eprintln!(
+                "Failed to decompress logs at '{}': {}",
+                path.to_string_lossy(),
+                err
+            );
+            path.to_path_buf()
+        }
+    }
+}
+
+fn get_readable_file(path: &Path) -> std::io::Result<PathBuf> {
+    let compressed_path = get_compressed_path(path);
+    if !compressed_path.exists() {
+        return Ok(path.to_path_buf());
+    }
+
+    let extracted_path = get_extracted_path(&get_extracted_logs_directory()?, path);
+    extract_file(path, &extracted_path)?;
+    Ok(extracted_path)
+}
+
+fn get_readable_directory(directory: &Path) -> std::io::Result<PathBuf> {
+    if !is_directory_compressed(directory) {
+        return Ok(directory.to_path_buf());
+    }
+
+    let extracted_logs_directory = get_extracted_logs_directory()?;
+    let mut extracted_directory = PathBuf::new();
+    for entry in std::fs::read_dir(directory)?.flatten() {
+        if !entry.file_type()?.is_file() {
+            continue;
+        }
+        let path = entry.path();
+        // the compressed file and the one written after the compression are extracted together
+        let original_path = if is_compressed_file(&path) {
+            path.with_extension("")
+        } else {
+            path
+        };
+
+        let extracted_path = get_extracted_path(&extracted_logs_directory, &original_path);
+        extract_file(&original_path, &extracted_path)?;
+        if let Some(parent) = extracted_path.parent() {
+            extracted_directory = parent.to_path_buf();
+        }
+    }
+
+    Ok(extracted_directory)
+}
+
+// removes everything that this app instance decompressed for reading,
+// the other instances can still be showing their files
+pub fn remove_extracted_logs() {
+    // it is fine to panic on a poisoned mutex
+    if let Some(extracted_logs_directory) = EXTRACTED_LOGS_DIRECTORY.lock().unwrap().take() {
+        let _ = std::fs::remove_dir_all(extracted_logs_directory);
+    }
+}
+
+// removes the decompressed files of a log folder, if there were any
+pub fn remove_extracted_directory(directory: &Path) {
+    // it is fine to panic on a poisoned mutex
+    if let Some(extracted_logs_directory) = &*EXTRACTED_LOGS_DIRECTORY.lock().unwrap() {
+        let _ = std::fs::remove_dir_all(get_extraction_directory(
+            extracted_logs_directory,
+            directory,
+        ));
+    }
+}
+
+// the folder is created under a new random name that only the current user can access,
+// so other users can't plant files or links there that we would write through
+fn get_extracted_logs_directory() -> std::io::Result<PathBuf> {
+    let mut extracted_logs_directory = EXTRACTED_LOGS_DIRECTORY.lock().unwrap(); // it is fine to panic on a poisoned mutex
+    if let Some(extracted_logs_directory) = &*extracted_logs_directory {
+        return Ok(extracted_logs_directory.clone());
+    }
+
+    let directory = loop {
+        let directory = std::env::temp_dir().join(format!(
+            "{}_{}_{:016x}",
+            EXTRACTED_LOGS_FOLDER_PREFIX,
+            std::process::id(),
+            rand::random::<u64>()
+        ));
+        let mut directory_builder = std::fs::DirBuilder::new();
+        #[cfg(unix)]
+        directory_builder.mode(0o700);
+        match directory_builder.create(&directory) {
+            Ok(()) => break directory,
+            Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(err) => return Err(err),
+        }
+    };
+    *extracted_logs_directory = Some(directory.clone());
+    Ok(directory)
+}
+
+// e.g. "1_build_output.log" or "1_build_output_retry2.idx", see get_script_output_path
+fn is_script_output_file(path: &Path) -> bool {
+    let Some(file_name) = path.file_name().and_then(|file_name| file_name.to_str()) else {
+        return false;
+    };
+    let Some((stem, extension)) = file_name.rsplit_once('.') else {
+        return false;
+    };
+    (extension == "log" || extension == "idx")
+        && (stem.ends_with("_output") || stem.contains("_output_retry"))
+}
+
+fn is_compressed_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension == COMPRESSED_EXTENSION)
+}
+
+// the archive is written to a temporary file that replaces the old archive only when complete,
+// so a crash or a full disk can't leave a broken archive or lose the log
+fn compress_file(path: &Path) -> std::io::Result<()> {
+    let compressed_path = get_compressed_path(path);
+    let mut temporary_file_name = compressed_path
+        .file_name()
+        .unwrap_or_default()
+        .to_os_string();
+    temporary_file_name.push(".partial");
+    let temporary_path = compressed_path.with_file_name(temporary_file_name);
+
+    let result = write_compressed_file(path, &compressed_path, &temporary_path)
+        .and_then(|_| std::fs::rename(&temporary_path, &compressed_path));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temporary_path);
+        return result;
+    }
+
+    std::fs::remove_file(path)
+}
+
+fn write_compressed_file(
+    path: &Path,
+    compressed_path: &Path,
+    temporary_path: &Path,
+) -> std::io::Result<()> {
+    let mut temporary_file = BufWriter::new(File::create(temporary_path)?);
+    // if the execution wrote to the file again after it was compressed, the new part goes
+    // to the end of the archive as another gzip member
+    match File::open(compressed_path) {
+        Ok(compressed_file) => {
+            std::io::copy(&mut BufReader::new(compressed_file), &mut temporary_file)?;
+        }
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+        Err(err) => return Err(err),
+    }
+
+    let mut encoder = GzEncoder::new(temporary_file, flate2::Compression::default());
+    std::io::copy(&mut BufReader::new(File::open(path)?), &mut encoder)?;
+    let temporary_file = encoder
+        .finish()?
+        .into_inner()
+        .map_err(|err| err.into_error())?;
+    temporary_file.sync_all()
+}
+
+fn get_extracted_path(extracted_logs_directory: &Path, path: &Path) -> PathBuf {
+    get_extraction_directory(
+        extracted_logs_directory,
+        path.parent().unwrap_or(Path::new("")),
+    )
+    .join(path.file_name().unwrap_or_default())
+}
+
+// folders with the same name from different logs paths don't share the extracted files
+fn get_extraction_directory(extracted_logs_directory: &Path, directory: &Path) -> PathBuf {
+    let mut hasher = DefaultHasher::new();
+    directory.hash(&mut hasher);
+    let mut folder_name = std::ffi::OsString::from(format!("{:016x}-", hasher.finish()));
+    folder_name.push(directory.file_name().unwrap_or_default());
+    extracted_logs_directory.join(folder_name)
+}
+
+// writes the content of the compressed file followed by the not yet compressed part, if any
+fn extract_file(path: &Path, extracted_path: &Path) -> std::io::Result<()> {
+    let compressed_path = get_compressed_path(path);
+    let source_paths = [compressed_path.as_path(), path];
+    let source_modified_time = source_paths
+        .iter()
+        .filter_map(|source_path| std::fs::metadata(source_path).ok()?.modified().ok())
+        .max();
+
+    // the files are extracted again only if the logs changed since the last time
+    let extracted_modified_time = std::fs::metadata(extracted_path)
+        .and_then(|metadata| metadata.modified())
+        .ok();
+    if extracted_modified_time.is_some() && extracted_modified_time >= source_modified_time {
+        return Ok(());
+    }
+
+    if let Some(parent) = extracted_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    // an outdated extracted file is replaced, never written through
+    match std::fs::remove_file(extracted_path) {
+        Ok(()) => {}
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+        Err(err) => return Err(err),
+    }
+    let mut extracted_file = BufWriter::new(
+        std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(extracted_path)?,
+    );
+    if let Ok(compressed_file) = File::open(&compressed_path) {
+        std::io::copy(
+            &mut MultiGzDecoder::new(BufReader::new(compressed_file)),
+            &mut extracted_file,
+        )?;
+    }
+    if let Ok(file) = File::open(path) {
+        std::io::copy(&mut BufReader::new(file), &mut extracted_file)?;
+    }
+    extracted_file.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestDirectory;
+
+    #[test]
+    fn test_compressed_logs_are_readable_by_original_paths() {
+        let directory = TestDirectory::new("log_archive");
+        let log_path = directory.join("1_build_output.log");
+
+        std::fs::write(&log_path, "first line\n").unwrap();
+        assert!(compress_directory(&directory).unwrap());
+        assert!(!log_path.exists());
+        assert!(log_exists(&log_path));
+
+        // the execution got more scripts after it finished and was compressed again
+        std::fs::write(&log_path, "second line\n").unwrap();
+        assert!(compress_directory(&directory).unwrap());
+        assert!(!compress_directory(&directory).unwrap());
+
+        let readable_path = get_readable_path(&log_path);
+        assert_ne!(readable_path, log_path);
+        assert_eq!(
+            std::fs::read_to_string(&readable_path).unwrap(),
+            "first line\nsecond line\n"
+        );
+
+        let readable_directory = get_readable_path(&directory);
+        assert_eq!(readable_path.parent(), Some(readable_directory.as_path()));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let extracted_logs_directory = readable_directory.parent().unwrap();
+            let mode = std::fs::metadata(extracted_logs_directory)
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o077, 0);
+        }
+
+        // the file is extracted again after the log changed
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        std::fs::write(&log_path, "third line\n").unwrap();
+        assert_eq!(get_readable_path(&log_path), readable_path);
+        assert_eq!(
+            std::fs::read_to_string(&readable_path).unwrap(),
+            "first line\nsecond line\nthird line\n"
+        );
+
+        remove_extracted_directory(&directory);
+        assert!(!readable_directory.exists());
+    }
+
+    #[test]
+    fn test_interrupted_compression_does_not_break_the_archive() {
+        let directory = TestDirectory::new("log_archive_interrupted");
+        let log_path = directory.join("1_build_output.log");
+        std::fs::write(&log_path, "first line\n").unwrap();
+        assert!(compress_directory(&directory).unwrap());
+
+        // left by a compression that didn't finish
+        let partial_path = directory.join("1_build_output.log.gz.partial");
+        std::fs::write(&partial_path, "not a gzip").unwrap();
+        std::fs::write(&log_path, "second line\n").unwrap();
+        assert!(compress_directory(&directory).unwrap());
+
+        assert!(!partial_path.exists());
+        assert!(!log_path.exists());
+        assert_eq!(
+            std::fs::read_to_string(get_readable_path(&log_path)).unwrap(),
+            "first line\nsecond line\n"
+        );
+    }
+
+    #[test]
+    fn test_only_script_outputs_are_compressed() {
+        let directory = TestDirectory::new("log_archive_only_outputs");
+        let compressed_files = [
+            "1_build_output.log",
+            "1_build_output.idx",
+            "2_test_output_retry1.log",
+            "2_test_output_retry1.idx",
+        ];
+        let kept_files = [
+            "execution_steps.log",
+            "execution_report.json",
+            "execution_report.xml",
+            "1_build_output.outputs",
+        ];
+        for file_name in compressed_files.iter().chain(&kept_files) {
+            std::fs::write(directory.join(file_name), file_name).unwrap();
+        }
+
+        assert!(compress_directory(&directory).unwrap());
+
+        for file_name in compressed_files {
+            assert!(!directory.join(file_name).exists());
+            assert!(get_compressed_path(&directory.join(file_name)).exists());
+        }
+        for file_name in kept_files {
+            assert_eq!(
+                std::fs::read_to_string(directory.join(file_name)).unwrap(),
+                file_name
+            );
+        }
+    }
+
+    #[test]
+    fn test_folders_with_same_name_from_different_logs_paths_are_extracted_separately() {
+        let directory = TestDirectory::new("log_archive_same_names");
+        let mut readable_paths = Vec::new();
+        for logs_folder_name in ["first_logs", "second_logs"] {
+            let run_directory = directory.join(logs_folder_name).join("20250101-100000-1");
+            std::fs::create_dir_all(&run_directory).unwrap();
+            let log_path = run_directory.join("1_build_output.log");
+            std::fs::write(&log_path, logs_folder_name).unwrap();
+            assert!(compress_directory(&run_directory).unwrap());
+            readable_paths.push((run_directory, get_readable_path(&log_path)));
+        }
+
+        assert_ne!(readable_paths[0].1, readable_paths[1].1);
+        assert_eq!(
+            std::fs::read_to_string(&readable_paths[0].1).unwrap(),
+            "first_logs"
+        );
+        assert_eq!(
+            std::fs::read_to_string(&readable_paths[1].1).unwrap(),
+            "second_logs"
+        );
+
+        for (run_directory, _) in &readable_paths {
+            remove_extracted_directory(run_directory);
+        }
+    }
+}
@@ -0,0 +1,526 @@
+// Copyright (C) Pavel Grebnev 2025
+// Distributed under the MIT License (license terms are at http://opensource.org/licenses/MIT).
+
+// Every line written to a script output file gets a fixed-size record in a sidecar index file.
+// This lets us read any line of a huge log without scanning the whole file,
+// and keeps the type of the output that the plain log file can't store.
+// The records point to the text of the line, so the timestamps that the timestamped format
+// adds to the file are skipped, and the lines of the JSON-lines format are decoded when read.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use crate::ansi_escapes;
+use crate::config;
+use crate::execution_thread::{OutputLine, OutputType};
+use crate::log_archive;
+
+// offset: u64, length: u32, output type: u8, timestamp in milliseconds: i64
+const RECORD_SIZE: usize = 21;
+// set in the output type byte when the line is a JSON object and not the text itself
+const JSON_LINE_FLAG: u8 = 0x80;
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.3f";
+// RFC 3339, so the tools that process the logs don't need to guess the time zone
+const JSON_TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.3f%:z";
+// reading more than this at once is done line by line to not allocate too much memory
+const MAX_CHUNK_BYTES: u64 = 8 * 1024 * 1024;
+// lines longer than this are cut when read, nobody is going to read them in the UI anyway
+pub const MAX_LINE_BYTES: usize = 64 * 1024;
+
+pub struct IndexedLine {
+    pub text: String,
+    pub output_type: OutputType,
+    pub timestamp: Option<chrono::DateTime<chrono::Local>>,
+}
+
+struct IndexRecord {
+    offset: u64,
+    length: u32,
+    output_type: OutputType,
+    timestamp_ms: i64,
+    is_json_line: bool,
+}
+
+pub struct ScriptOutputWriter {
+    output_writer: std::io::BufWriter<File>,
+    index_writer: Option<std::io::BufWriter<File>>,
+    written_bytes: u64,
+    keep_escape_sequences: bool,
+    format: config::LogFileFormat,
+    retry_count: usize,
+}
+
+pub struct ScriptOutputReader {
+    output_file: File,
+    index_file: Option<File>,
+    line_count: usize,
+    // only used for logs that were written without an index
+    scanned_line_starts: Vec<u64>,
+    scanned_bytes: u64,
+}
+
+pub fn get_index_path(output_path: &Path) -> PathBuf {
+    output_path.with_extension("idx")
+}
+
+impl ScriptOutputWriter {
+    pub fn create(
+        output_path: &Path,
+        keep_escape_sequences: bool,
+        format: config::LogFileFormat,
+        retry_count: usize,
+    ) -> std::io::Result<Self> {
+        let output_file = File::create(output_path)?;
+        // the log is still useful without the index, so don't fail if we can't create it
+        let index_writer = match File::create(get_index_path(output_path)) {
+            Ok(index_file) => Some(std::io::BufWriter::new(index_file)),
+            Err(err) => {
+                eprintln!(
+                    "Could not create log index for '{}': {}",
+                    output_path.to_string_lossy(),
+                    err
+                );
+                None
+            }
+        };
+
+        Ok(Self {
+            output_writer: std::io::BufWriter::new(output_file),
+            index_writer,
+            written_bytes: 0,
+            keep_escape_sequences,
+            format,
+            retry_count,
+        })
+    }
+
+    pub fn write_line(&mut self, line: &OutputLine) {
+        // colors are useful in the UI, but make the files hard to read in text editors
+        let text = if self.keep_escape_sequences {
+            std::borrow::Cow::Borrowed(line.text.as_str())
+        } else {
+            ansi_escapes::strip_escape_sequences(&line.text)
+        };
+
+        let mut prefix = String::new();
+        let mut body = text;
+        match self.format {
+            config::LogFileFormat::Plain => {}
+            config::LogFileFormat::Timestamped => {
+                prefix = format!(
+                    "[{}] [{}] ",
+                    line.timestamp.format(TIMESTAMP_FORMAT),
+                    get_stream_name(line.output_type)
+                );
+            }
+            config::LogFileFormat::JsonLines => {
+                let json_line = serde_json::json!({
+                    "timestamp": line.timestamp.format(JSON_TIMESTAMP_FORMAT).to_string(),
+                    "stream": get_stream_name(line.output_type),
+                    "retry": self.retry_count,
+                    "text": body.trim_end_matches(['\n', '\r']),
+                });
+                body = std::borrow::Cow::Owned(json_line.to_string());
+            }
+        }
+
+        // script output already contains line endings, our own messages don't
+        // (with a prefix on every line, an unfinished line can't be continued later)
+        let needs_line_ending = match (self.format, line.output_type) {
+            (config::LogFileFormat::Plain, OutputType::StdOut | OutputType::StdErr) => false,
+            _ => !body.ends_with('\n'),
+        };
+
+        let mut length = body.len();
+        if self.output_writer.write_all(prefix.as_bytes()).is_err()
+            || self.output_writer.write_all(body.as_bytes()).is_err()
+        {
+            return;
+        }
+        if needs_line_ending {
+            let _ = self.output_writer.write_all(b"\n");
+            length += 1;
+        }
+        // the text should be readable by the time the index record appears
+        let _ = self.output_writer.flush();
+
+        if let Some(index_writer) = &mut self.index_writer {
+            let record = IndexRecord {
+                offset: self.written_bytes + prefix.len() as u64,
+                length: length as u32,
+                output_type: line.output_type,
+                timestamp_ms: line.timestamp.timestamp_millis(),
+                is_json_line: self.format == config::LogFileFormat::JsonLines,
+            };
+            let _ = index_writer.write_all(&encode_record(&record));
+            let _ = index_writer.flush();
+        }
+
+        self.written_bytes += (prefix.len() + length) as u64;
+    }
+}
+
+impl ScriptOutputReader {
+    pub fn open(output_path: &Path) -> std::io::Result<Self> {
+        // the logs of the finished executions can be compressed
+        let output_file = File::open(log_archive::get_readable_path(output_path))?;
+        let index_file =
+            File::open(log_archive::get_readable_path(&get_index_path(output_path))).ok();
+
+        let mut reader = Self {
+            output_file,
+            index_file,
+            line_count: 0,
+            scanned_line_starts: vec![0],
+            scanned_bytes: 0,
+        };
+        reader.refresh();
+        Ok(reader)
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.line_count
+    }
+
+    // picks up the lines that were written since the last call, returns true if there are new lines
+    pub fn refresh(&mut self) -> bool {
+        let old_line_count = self.line_count;
+
+        if let Some(index_file) = &self.index_file {
+            if let Ok(metadata) = index_file.metadata() {
+                self.line_count = metadata.len() as usize / RECORD_SIZE;
+            }
+        } else {
+            self.scan_new_output();
+        }
+
+        self.line_count != old_line_count
+    }
+
+    pub fn read_lines(&mut self, first_line: usize, count: usize) -> Vec<IndexedLine> {
+        let count = count.min(self.line_count.saturating_sub(first_line));
+        if count == 0 {
+            return Vec::new();
+        }
+
+        let records = self.read_records(first_line, count);
+        let (Some(first_record), Some(last_record)) = (records.first(), records.last()) else {
+            return Vec::new();
+        };
+
+        let span_start = first_record.offset;
+        let span_end = last_record.offset + last_record.length as u64;
+        if span_end.saturating_sub(span_start) <= MAX_CHUNK_BYTES {
+            let mut buffer = vec![0; (span_end - span_start) as usize];
+            if self.read_output(span_start, &mut buffer).is_err() {
+                return Vec::new();
+            }
+
+            records
+                .iter()
+                .map(|record| {
+                    let start = (record.offset - span_start) as usize;
+                    let end = start + record.length as usize;
+                    make_indexed_line(
+                        record,
+                        &buffer[start.min(buffer.len())..end.min(buffer.len())],
+                    )
+                })
+                .collect()
+        } else {
+            records
+                .iter()
+                .map(|record| {
+                    let mut buffer = vec![0; (record.length as usize).min(MAX_LINE_BYTES)];
+                    if self.read_output(record.offset, &mut buffer).is_err() {
+                        buffer.clear();
+                    }
+                    make_indexed_line(record, &buffer)
+                })
+                .collect()
+        }
+    }
+
+    pub fn read_output_types(&mut self, first_line: usize, count: usize) -> Vec<OutputType> {
+        let count = count.min(self.line_count.saturating_sub(first_line));
+        if self.index_file.is_none() {
+            return vec![OutputType::StdOut; count];
+        }
+
+        self.read_records(first_line, count)
+            .into_iter()
+            .map(|record| record.output_type)
+            .collect()
+    }
+
+    fn read_records(&mut self, first_line: usize, count: usize) -> Vec<IndexRecord> {
+        let Some(index_file) = &mut self.index_file else {
+            return (first_line..first_line + count)
+                .map(|line_idx| {
+                    let offset = self.scanned_line_starts[line_idx];
+                    let end = self
+                        .scanned_line_starts
+                        .get(line_idx + 1)
+                        .copied()
+                        .unwrap_or(self.scanned_bytes);
+                    IndexRecord {
+                        offset,
+                        length: (end - offset) as u32,
+                        output_type: OutputType::StdOut,
+                        timestamp_ms: 0,
+                        is_json_line: false,
+                    }
+                })
+                .collect();
+        };
+
+        let mut buffer = vec![0; count * RECORD_SIZE];
+        let read_result = index_file
+            .seek(SeekFrom::Start((first_line * RECORD_SIZE) as u64))
+            .and_then(|_| index_file.read_exact(&mut buffer));
+        if read_result.is_err() {
+            return Vec::new();
+        }
+
+        buffer
+            .chunks_exact(RECORD_SIZE)
+            .map(decode_record)
+            .collect()
+    }
+
+    fn read_output(&mut self, offset: u64, buffer: &mut [u8]) -> std::io::Result<()> {
+        self.output_file.seek(SeekFrom::Start(offset))?;
+        self.output_file.read_exact(buffer)
+    }
+
+    fn scan_new_output(&mut self) {
+        let Ok(metadata) = self.output_file.metadata() else {
+            return;
+        };
+        if metadata.len() <= self.scanned_bytes {
+            return;
+        }
+        if self
+            .output_file
+            .seek(SeekFrom::Start(self.scanned_bytes))
+            .is_err()
+        {
+            return;
+        }
+
+        let mut buffer = vec![0; 64 * 1024];
+        loop {
+            let read_bytes = match self.output_file.read(&mut buffer) {
+                Ok(0) | Err(_) => break,
+                Ok(read_bytes) => read_bytes,
+            };
+            for (idx, byte) in buffer[..read_bytes].iter().enumerate() {
+                if *byte == b'\n' {
+                    self.scanned_line_starts
+                        .push(self.scanned_bytes + idx as u64 + 1);
+                }
+            }
+            self.scanned_bytes += read_bytes as u64;
+        }
+
+        // the last line can be not finished yet, but we still want to show it
+        let is_last_line_empty = self.scanned_line_starts.last() == Some(&self.scanned_bytes);
+        self.line_count = self.scanned_line_starts.len() - if is_last_line_empty { 1 } else { 0 };
+    }
+}
+
+fn encode_record(record: &IndexRecord) -> [u8; RECORD_SIZE] {
+    let mut buffer = [0; RECORD_SIZE];
+    buffer[0..8].copy_from_slice(&record.offset.to_le_bytes());
+    buffer[8..12].copy_from_slice(&record.length.to_le_bytes());
+    buffer[12] = match record.output_type {
+        OutputType::StdOut => 0,
+        OutputType::StdErr => 1,
+        OutputType::Error => 2,
+        OutputType::Event => 3,
+    };
+    if record.is_json_line {
+        buffer[12] |= JSON_LINE_FLAG;
+    }
+    buffer[13..21].copy_from_slice(&record.timestamp_ms.to_le_bytes());
+    buffer
+}
+
+fn decode_record(buffer: &[u8]) -> IndexRecord {
+    let mut offset = [0; 8];
+    offset.copy_from_slice(&buffer[0..8]);
+    let mut length = [0; 4];
+    length.copy_from_slice(&buffer[8..12]);
+    let mut timestamp_ms = [0; 8];
+    timestamp_ms.copy_from_slice(&buffer[13..21]);
+
+    IndexRecord {
+        offset: u64::from_le_bytes(offset),
+        length: u32::from_le_bytes(length),
+        output_type: match buffer[12] & !JSON_LINE_FLAG {
+            1 => OutputType::StdErr,
+            2 => OutputType::Error,
+            3 => OutputType::Event,
+            _ => OutputType::StdOut,
+        },
+        timestamp_ms: i64::from_le_bytes(timestamp_ms),
+        is_json_line: buffer[12] & JSON_LINE_FLAG != 0,
+    }
+}
+
+fn get_stream_name(output_type: OutputType) -> &'static str {
+    match output_type {
+        OutputType::StdOut => "stdout",
+        OutputType::StdErr => "stderr",
+        OutputType::Error => "error",
+        OutputType::Event => "event",
+    }
+}
+
+fn make_indexed_line(record: &IndexRecord, bytes: &[u8]) -> IndexedLine {
+    // a cut JSON line can't be parsed, then it's better to show it as is than nothing
+    let json_text = if record.is_json_line {
+        serde_json::from_slice::<serde_json::Value>(bytes)
+            .ok()
+            .and_then(|json_line| json_line["text"].as_str().map(str::to_string))
+    } else {
+        None
+    };
+    let bytes = json_text.as_ref().map_or(bytes, |text| text.as_bytes());
+    let bytes = &bytes[..bytes.len().min(MAX_LINE_BYTES)];
+    let text = String::from_utf8_lossy(bytes);
+    IndexedLine {
+        text: text.trim_end_matches(['\n', '\r']).to_string(),
+        output_type: record.output_type,
+        timestamp: if record.timestamp_ms != 0 {
+            chrono::DateTime::from_timestamp_millis(record.timestamp_ms)
+                .map(|timestamp| timestamp.with_timezone(&chrono::Local))
+        } else {
+            None
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestDirectory;
+
+    fn make_test_output_path(test_name: &str) -> (TestDirectory, PathBuf) {
+        let directory = TestDirectory::new(test_name);
+        let output_path = directory.join("1_test_output.log");
+        (directory, output_path)
+    }
+
+    fn make_line(text: &str, output_type: OutputType) -> OutputLine {
+        OutputLine {
+            text: text.to_string(),
+            output_type,
+            timestamp: chrono::Local::now(),
+            highlight: None,
+            script_idx: None,
+        }
+    }
+
+    #[test]
+    fn test_written_lines_can_be_read_back_with_their_types() {
+        let (_directory, output_path) = make_test_output_path("log_index_read_back");
+
+        let mut writer =
+            ScriptOutputWriter::create(&output_path, false, config::LogFileFormat::Plain, 0)
+                .unwrap();
+        writer.write_line(&make_line("\x1b[32mfirst\x1b[0m\n", OutputType::StdOut));
+        writer.write_line(&make_line("second\r\n", OutputType::StdErr));
+        writer.write_line(&make_line("timed out", OutputType::Error));
+
+        let mut reader = ScriptOutputReader::open(&output_path).unwrap();
+        assert_eq!(reader.line_count(), 3);
+
+        let lines = reader.read_lines(1, 5);
+        let output_types = reader.read_output_types(0, 3);
+        writer.write_line(&make_line("late line\n", OutputType::StdOut));
+        let has_new_lines = reader.refresh();
+        let late_lines = reader.read_lines(3, 1);
+        let file_content = std::fs::read_to_string(&output_path).unwrap();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].text, "second");
+        assert_eq!(lines[1].text, "timed out");
+        assert_eq!(lines[1].output_type, OutputType::Error);
+        assert!(lines[1].timestamp.is_some());
+        assert_eq!(
+            output_types,
+            vec![OutputType::StdOut, OutputType::StdErr, OutputType::Error]
+        );
+        assert!(has_new_lines);
+        assert_eq!(late_lines[0].text, "late line");
+        assert_eq!(file_content, "first\nsecond\r\ntimed out\nlate line\n");
+    }
+
+    #[test]
+    fn test_timestamped_and_json_lines_are_read_back_without_decorations() {
+        for format in [
+            config::LogFileFormat::Timestamped,
+            config::LogFileFormat::JsonLines,
+        ] {
+            let (_directory, output_path) = make_test_output_path("log_index_formats");
+
+            let mut writer = ScriptOutputWriter::create(&output_path, false, format, 2).unwrap();
+            writer.write_line(&make_line("first \"quoted\"\n", OutputType::StdOut));
+            writer.write_line(&make_line("unfinished", OutputType::StdErr));
+            writer.write_line(&make_line("timed out", OutputType::Error));
+
+            let mut reader = ScriptOutputReader::open(&output_path).unwrap();
+            let lines = reader.read_lines(0, 3);
+            let file_lines: Vec<String> = std::fs::read_to_string(&output_path)
+                .unwrap()
+                .lines()
+                .map(str::to_string)
+                .collect();
+
+            assert_eq!(
+                lines
+                    .iter()
+                    .map(|line| line.text.as_str())
+                    .collect::<Vec<_>>(),
+                vec!["first \"quoted\"", "unfinished", "timed out"]
+            );
+            assert_eq!(lines[1].output_type, OutputType::StdErr);
+            assert_eq!(file_lines.len(), 3);
+            match format {
+                config::LogFileFormat::Timestamped => {
+                    assert!(file_lines[1].starts_with('['));
+                    assert!(file_lines[1].ends_with("] [stderr] unfinished"));
+                }
+                _ => {
+                    let json_line: serde_json::Value =
+                        serde_json::from_str(&file_lines[1]).unwrap();
+                    assert_eq!(json_line["stream"], "stderr");
+                    assert_eq!(json_line["retry"], 2);
+                    assert_eq!(json_line["text"], "unfinished");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_logs_without_index_are_split_into_lines() {
+        let (_directory, output_path) = make_test_output_path("log_index_no_index");
+        std::fs::write(&output_path, "first\n\nthird\nunfinished").unwrap();
+
+        let mut reader = ScriptOutputReader::open(&output_path).unwrap();
+        let line_count = reader.line_count();
+        let lines = reader.read_lines(0, 10);
+
+        assert_eq!(line_count, 4);
+        assert_eq!(
+            lines
+                .iter()
+                .map(|line| line.text.as_str())
+                .collect::<Vec<_>>(),
+            vec!["first", "", "third", "unfinished"]
+        );
+        assert!(lines[0].timestamp.is_none());
+    }
+}
@@ -0,0 +1,259 @@
+// Copyright (C) Pavel Grebnev 2025
+// Distributed under the MIT License (license terms are at http://opensource.org/licenses/MIT).
+
+// The patterns are regular expressions with the syntax of the regex crate, they are matched
+// in linear time, so a pattern can't stall the thread reading the output of a script.
+
+use std::sync::Mutex;
+
+use crate::ansi_escapes;
+use crate::config;
+
+// only this many matches are kept for each attempt of a script
+pub const MAX_PROBLEMS_PER_SCRIPT: usize = 10;
+// the rest of a very long line is not checked, to keep the matching fast
+const MAX_MATCHED_LINE_LENGTH: usize = 2000;
+// the text of a problem is shortened to this number of characters
+const MAX_PROBLEM_TEXT_LENGTH: usize = 200;
+
+pub fn compile_pattern(pattern: &str) -> Result<regex::Regex, String> {
+    regex::Regex::new(pattern).map_err(|error| match error {
+        // the syntax errors point at the pattern over several lines, the last one describes the problem
+        regex::Error::Syntax(description) => description
+            .lines()
+            .last()
+            .map(|line| line.trim_start_matches("error: ").to_string())
+            .unwrap_or(description),
+        _ => error.to_string(),
+    })
+}
+
+// true if the pattern matches any part of the line
+fn is_match(pattern: &regex::Regex, line: &str) -> bool {
+    let line = line.trim_end_matches(['\r', '\n']);
+    let line = match line.char_indices().nth(MAX_MATCHED_LINE_LENGTH) {
+        Some((end, _)) => &line[..end],
+        None => line,
+    };
+    pattern.is_match(line)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogProblem {
+    pub severity: config::LogPatternSeverity,
+    pub text: String,
+}
+
+#[derive(Clone)]
+struct CompiledRule {
+    pattern: regex::Regex,
+    source: String,
+    severity: config::LogPatternSeverity,
+    fails_script: bool,
+}
+
+// the rules of a script together with the global rules, the first matching rule is used
+#[derive(Clone, Default)]
+pub struct LogPatternMatcher {
+    rules: Vec<CompiledRule>,
+}
+
+impl LogPatternMatcher {
+    // returns the errors of the patterns that can't be used, the rest of the rules still work
+    pub fn new<'a>(
+        rules: impl IntoIterator<Item = &'a config::LogPatternRule>,
+    ) -> (Self, Vec<String>) {
+        let mut compiled_rules = Vec::new();
+        let mut errors = Vec::new();
+        for rule in rules {
+            if rule.pattern.is_empty() {
+                continue;
+            }
+            match compile_pattern(&rule.pattern) {
+                Ok(pattern) => compiled_rules.push(CompiledRule {
+                    pattern,
+                    source: rule.pattern.clone(),
+                    severity: rule.severity,
+                    fails_script: rule.fails_script,
+                }),
+                Err(error) => errors.push(format!(
+                    "Log pattern \"{}\" is ignored: {}",
+                    rule.pattern, error
+                )),
+            }
+        }
+
+        (
+            Self {
+                rules: compiled_rules,
+            },
+            errors,
+        )
+    }
+
+    fn find_rule(&self, line: &str) -> Option<&CompiledRule> {
+        if self.rules.is_empty() {
+            return None;
+        }
+        let line = ansi_escapes::strip_escape_sequences(line);
+        self.rules
+            .iter()
+            .find(|rule| is_match(&rule.pattern, &line))
+    }
+}
+
+#[derive(Default)]
+struct CollectedProblems {
+    problems: Vec<LogProblem>,
+    failing_pattern: Option<String>,
+}
+
+// collects the matches of one attempt of a script, shared with the threads reading its output
+pub struct LogProblemCollector {
+    matcher: LogPatternMatcher,
+    collected: Mutex<CollectedProblems>,
+}
+
+impl LogProblemCollector {
+    pub fn new(matcher: LogPatternMatcher) -> Self {
+        Self {
+            matcher,
+            collected: Mutex::new(CollectedProblems::default()),
+        }
+    }
+
+    // returns the severity of the line if it matched any rule
+    pub fn classify_line(&self, line: &str) -> Option<config::LogPatternSeverity> {
+        let rule = self.matcher.find_rule(line)?;
+
+        let mut collected = self.collected.lock().unwrap(); // it is fine to panic on a poisoned mutex
+        if collected.problems.len() < MAX_PROBLEMS_PER_SCRIPT {
+            let text = ansi_escapes::strip_escape_sequences(line.trim_end());
+            collected.problems.push(LogProblem {
+                severity: rule.severity,
+                text: text.chars().take(MAX_PROBLEM_TEXT_LENGTH).collect(),
+            });
+        }
+        if rule.fails_script && collected.failing_pattern.is_none() {
+            collected.failing_pattern = Some(rule.source.clone());
+        }
+
+        Some(rule.severity)
+    }
+
+    pub fn get_problems_count(&self) -> usize {
+        self.collected.lock().unwrap().problems.len() // it is fine to panic on a poisoned mutex
+    }
+
+    pub fn get_problems(&self) -> Vec<LogProblem> {
+        self.collected.lock().unwrap().problems.clone() // it is fine to panic on a poisoned mutex
+    }
+
+    // the pattern of the first matched rule that fails the script, if any
+    pub fn get_failing_pattern(&self) -> Option<String> {
+        self.collected.lock().unwrap().failing_pattern.clone() // it is fine to panic on a poisoned mutex
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_pattern_match(pattern: &str, line: &str) -> bool {
+        is_match(&compile_pattern(pattern).unwrap(), line)
+    }
+
+    #[test]
+    fn test_patterns_match_tool_output() {
+        assert!(is_pattern_match("ERROR:", "[build] ERROR: missing file\n"));
+        assert!(!is_pattern_match(
+            "^ERROR:",
+            "[build] ERROR: missing file\n"
+        ));
+        assert!(is_pattern_match(
+            "^error(\\[E\\d+\\])?:",
+            "error[E0308]: mismatched types"
+        ));
+        assert!(is_pattern_match("^error(\\[E\\d+\\])?:", "error: aborting"));
+        assert!(!is_pattern_match(
+            "^error(\\[E\\d+\\])?:",
+            "error[E03]x: aborting"
+        ));
+        assert!(is_pattern_match(
+            "(?i)\\bwarn(ing)?\\b",
+            "Some WARNING here"
+        ));
+        assert!(!is_pattern_match("(?i)\\bwarn(ing)?\\b", "forewarned"));
+        assert!(is_pattern_match(
+            "[^ ]+\\.rs:\\d{1,5}:",
+            "  --> src/main.rs:42:7"
+        ));
+        assert!(is_pattern_match("failed$", "3 tests failed\r\n"));
+        assert!(!is_pattern_match("failed$", "failed to start, retrying"));
+        assert!(is_pattern_match("a.*b|c", "xxcxx"));
+        assert!(is_pattern_match("(a*)*b", "aaab"));
+        assert!(!is_pattern_match("(a*)*b", "aaaa"));
+
+        assert!(!is_pattern_match(
+            "(a|a)*b",
+            &"a".repeat(MAX_MATCHED_LINE_LENGTH)
+        ));
+        assert!(!is_pattern_match("(\\w+\\s?)+:", &"word ".repeat(1000)));
+
+        assert_eq!(compile_pattern("(unclosed").unwrap_err(), "unclosed group");
+        assert!(compile_pattern("*start").is_err());
+        assert!(compile_pattern("[z-a]").is_err());
+        assert!(compile_pattern("x{2,a}").is_err());
+    }
+
+    #[test]
+    fn test_first_matching_rule_classifies_line_and_can_fail_script() {
+        let script_rules = [config::LogPatternRule {
+            pattern: "known error".to_string(),
+            severity: config::LogPatternSeverity::Warning,
+            fails_script: false,
+        }];
+        let global_rules = [
+            config::LogPatternRule {
+                pattern: "(?i)error".to_string(),
+                severity: config::LogPatternSeverity::Error,
+                fails_script: true,
+            },
+            config::LogPatternRule {
+                pattern: "[broken".to_string(),
+                severity: config::LogPatternSeverity::Info,
+                fails_script: false,
+            },
+        ];
+        let (matcher, errors) = LogPatternMatcher::new(script_rules.iter().chain(&global_rules));
+        assert_eq!(errors.len(), 1);
+
+        let collector = LogProblemCollector::new(matcher);
+        assert_eq!(
+            collector.classify_line("a known error\n"),
+            Some(config::LogPatternSeverity::Warning)
+        );
+        assert_eq!(collector.get_failing_pattern(), None);
+        assert_eq!(collector.classify_line("all good\n"), None);
+        assert_eq!(
+            collector.classify_line("\x1b[31mERROR\x1b[0m: disk is full\n"),
+            Some(config::LogPatternSeverity::Error)
+        );
+        assert_eq!(
+            collector.get_failing_pattern(),
+            Some("(?i)error".to_string())
+        );
+        assert_eq!(
+            collector.get_problems()[1],
+            LogProblem {
+                severity: config::LogPatternSeverity::Error,
+                text: "ERROR: disk is full".to_string(),
+            }
+        );
+
+        for _ in 0..MAX_PROBLEMS_PER_SCRIPT {
+            collector.classify_line("error");
+        }
+        assert_eq!(collector.get_problems_count(), MAX_PROBLEMS_PER_SCRIPT);
+    }
+}
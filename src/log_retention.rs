@@ -0,0 +1,416 @@
+// Copyright (C) Pavel Grebnev 2025
+// Distributed under the MIT License (license terms are at http://opensource.org/licenses/MIT).
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver};
+use std::thread;
+
+use crate::config;
+use crate::execution_history;
+use crate::log_archive;
+
+// the format of the folder names created by file_utils::get_script_log_directory
+const RUN_DIRECTORY_TIME_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LogCleanupSummary {
+    pub removed_runs: usize,
+    pub removed_bytes: u64,
+    pub kept_runs: usize,
+    pub kept_bytes: u64,
+    pub compressed_runs: usize,
+}
+
+pub struct LogCleanupRequest {
+    logs_path: PathBuf,
+    retention: config::LogRetention,
+    compression: config::LogCompression,
+    // folders of the executions that are still open in the app, they are never removed
+    protected_directories: Vec<PathBuf>,
+    // folders that can still be written to, they are never compressed
+    running_directories: Vec<PathBuf>,
+    finished_directories: HashSet<PathBuf>,
+    failed_directories: HashSet<PathBuf>,
+}
+
+// runs the cleanup on a separate thread, since the logs folder can be big
+pub struct LogCleaner {
+    cleanup_thread: Option<thread::JoinHandle<()>>,
+    summary_receiver: Option<Receiver<LogCleanupSummary>>,
+    // a request that came while the previous cleanup was still running
+    pending_request: Option<LogCleanupRequest>,
+    last_summary: Option<LogCleanupSummary>,
+}
+
+struct RunDirectory {
+    path: PathBuf,
+    start_time: chrono::NaiveDateTime,
+    size_bytes: u64,
+    has_failed: bool,
+    is_protected: bool,
+}
+
+impl LogCleaner {
+    pub fn new() -> Self {
+        Self {
+            cleanup_thread: None,
+            summary_receiver: None,
+            pending_request: None,
+            last_summary: None,
+        }
+    }
+
+    pub fn request_cleanup(&mut self, request: LogCleanupRequest) {
+        if !request.is_needed() {
+            return;
+        }
+
+        self.pending_request = Some(request);
+        self.update();
+    }
+
+    pub fn update(&mut self) {
+        if let Some(rx) = &self.summary_receiver {
+            if let Ok(summary) = rx.try_recv() {
+                self.last_summary = Some(summary);
+                self.summary_receiver = None;
+            }
+        }
+
+        if self
+            .cleanup_thread
+            .as_ref()
+            .is_some_and(|cleanup_thread| !cleanup_thread.is_finished())
+        {
+            return;
+        }
+
+        let Some(request) = self.pending_request.take() else {
+            return;
+        };
+
+        if let Some(cleanup_thread) = self.cleanup_thread.take() {
+            // should never block since we checked that the thread is done
+            let _ = cleanup_thread.join();
+        }
+
+        let (summary_sender, summary_receiver) = channel();
+        self.summary_receiver = Some(summary_receiver);
+        self.cleanup_thread = Some(thread::spawn(move || {
+            let _ = summary_sender.send(request.run());
+        }));
+    }
+
+    pub fn get_last_summary(&self) -> Option<&LogCleanupSummary> {
+        self.last_summary.as_ref()
+    }
+}
+
+impl LogCleanupRequest {
+    pub fn new(
+        app_config: &config::AppConfig,
+        protected_directories: Vec<PathBuf>,
+        running_directories: Vec<PathBuf>,
+        history_records: &[execution_history::ExecutionHistoryRecord],
+    ) -> Self {
+        let rewritable_config = config::get_main_rewritable_config(app_config);
+        Self {
+            logs_path: app_config.paths.logs_path.clone(),
+            retention: rewritable_config.log_retention.clone(),
+            compression: rewritable_config.log_compression.clone(),
+            protected_directories,
+            running_directories,
+            finished_directories: history_records
+                .iter()
+                .map(|record| record.log_directory.clone())
+                .collect(),
+            failed_directories: history_records
+                .iter()
+                .filter(|record| record.has_failed_scripts)
+                .map(|record| record.log_directory.clone())
+                .collect(),
+        }
+    }
+
+    pub fn is_needed(&self) -> bool {
+        self.retention.is_enabled() || self.compression.mode != config::LogCompressionMode::Disabled
+    }
+
+    pub fn run(&self) -> LogCleanupSummary {
+        // without limits nothing is removed, but we still count what the logs take
+        let mut summary = clean_up_logs(
+            &self.logs_path,
+            &self.retention,
+            &self.protected_directories,
+            &self.failed_directories,
+        );
+        if self.compression.mode != config::LogCompressionMode::Disabled {
+            summary.compressed_runs = compress_finished_logs(
+                &self.logs_path,
+                &self.compression,
+                &self.running_directories,
+                &self.finished_directories,
+            );
+        }
+        summary
+    }
+}
+
+impl std::fmt::Display for LogCleanupSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "The logs of {} runs take {}",
+            self.kept_runs,
+            format_size(self.kept_bytes)
+        )?;
+        if self.removed_runs > 0 {
+            write!(
+                f,
+                ", the last cleanup removed {} runs ({})",
+                self.removed_runs,
+                format_size(self.removed_bytes)
+            )?;
+        }
+        if self.compressed_runs > 0 {
+            write!(f, ", compressed the logs of {} runs", self.compressed_runs)?;
+        }
+        Ok(())
+    }
+}
+
+// removes the log folders of the old executions that don't fit the retention settings
+pub fn clean_up_logs(
+    logs_path: &Path,
+    retention: &config::LogRetention,
+    protected_directories: &[PathBuf],
+    failed_directories: &HashSet<PathBuf>,
+) -> LogCleanupSummary {
+    let mut run_directories = find_run_directories(logs_path, failed_directories);
+    for run_directory in &mut run_directories {
+        run_directory.is_protected = protected_directories.contains(&run_directory.path);
+    }
+    // the newest first
+    run_directories.sort_by_key(|run_directory| std::cmp::Reverse(run_directory.start_time));
+
+    let now = chrono::Local::now().naive_local();
+    let mut directories_to_remove = Vec::new();
+
+    if retention.max_age_days > 0 {
+        run_directories.retain(|run_directory| {
+            let mut max_age_days = retention.max_age_days as i64;
+            if run_directory.has_failed && retention.keep_failed_runs_longer {
+                max_age_days *= 2;
+            }
+            let is_expired = now - run_directory.start_time > chrono::Duration::days(max_age_days);
+            if is_expired && !run_directory.is_protected {
+                directories_to_remove.push((run_directory.path.clone(), run_directory.size_bytes));
+                return false;
+            }
+            true
+        });
+    }
+
+    // the oldest runs are removed first, and the failed ones only when there is nothing else to remove
+    let mut removal_order: Vec<usize> = (0..run_directories.len())
+        .rev()
+        .filter(|idx| !run_directories[*idx].is_protected)
+        .collect();
+    if retention.keep_failed_runs_longer {
+        removal_order.sort_by_key(|idx| run_directories[*idx].has_failed);
+    }
+
+    let max_total_size_bytes = retention.max_total_size_mb * 1024 * 1024;
+    let mut kept_runs = run_directories.len();
+    let mut kept_bytes: u64 = run_directories.iter().map(|dir| dir.size_bytes).sum();
+    for idx in removal_order {
+        let is_over_count = retention.max_runs > 0 && kept_runs > retention.max_runs as usize;
+        let is_over_size = max_total_size_bytes > 0 && kept_bytes > max_total_size_bytes;
+        if !is_over_count && !is_over_size {
+            break;
+        }
+
+        let run_directory = &run_directories[idx];
+        directories_to_remove.push((run_directory.path.clone(), run_directory.size_bytes));
+        kept_runs -= 1;
+        kept_bytes -= run_directory.size_bytes;
+    }
+
+    let mut summary = LogCleanupSummary {
+        kept_runs,
+        kept_bytes,
+        ..LogCleanupSummary::default()
+    };
+    for (path, size_bytes) in directories_to_remove {
+        match std::fs::remove_dir_all(&path) {
+            Ok(()) => {
+                log_archive::remove_extracted_directory(&path);
+                summary.removed_runs += 1;
+                summary.removed_bytes += size_bytes;
+            }
+            Err(err) => {
+                eprintln!("Failed to remove old logs at {}: {}", path.display(), err);
+                summary.kept_runs += 1;
+                summary.kept_bytes += size_bytes;
+            }
+        }
+    }
+
+    summary
+}
+
+// compresses the log folders of the finished executions, returns how many folders were compressed
+fn compress_finished_logs(
+    logs_path: &Path,
+    compression: &config::LogCompression,
+    running_directories: &[PathBuf],
+    finished_directories: &HashSet<PathBuf>,
+) -> usize {
+    let now = chrono::Local::now().naive_local();
+    let mut compressed_runs = 0;
+    for run_directory in find_run_directories(logs_path, &HashSet::new()) {
+        if running_directories.contains(&run_directory.path) {
+            continue;
+        }
+
+        let age = now - run_directory.start_time;
+        // only the latest executions are in the history, and the older ones are surely finished
+        // (this also protects the logs that other scripter instances are writing right now)
+        if !finished_directories.contains(&run_directory.path) && age < chrono::Duration::days(1) {
+            continue;
+        }
+        if compression.mode == config::LogCompressionMode::AfterDays
+            && age < chrono::Duration::days(compression.after_days as i64)
+        {
+            continue;
+        }
+
+        match log_archive::compress_directory(&run_directory.path) {
+            Ok(true) => compressed_runs += 1,
+            Ok(false) => {}
+            Err(err) => eprintln!(
+                "Failed to compress logs at {}: {}",
+                run_directory.path.display(),
+                err
+            ),
+        }
+    }
+    compressed_runs
+}
+
+fn find_run_directories(
+    logs_path: &Path,
+    failed_directories: &HashSet<PathBuf>,
+) -> Vec<RunDirectory> {
+    let Ok(entries) = std::fs::read_dir(logs_path) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_dir()))
+        .filter_map(|entry| {
+            let file_name = entry.file_name();
+            let start_time = parse_run_directory_name(file_name.to_str()?)?;
+            let path = entry.path();
+            Some(RunDirectory {
+                size_bytes: get_directory_size(&path),
+                has_failed: failed_directories.contains(&path),
+                path,
+                start_time,
+                is_protected: false,
+            })
+        })
+        .collect()
+}
+
+// only the folders named like "20250131-235959-1234" are touched, anything else in the logs folder is kept
+fn parse_run_directory_name(name: &str) -> Option<chrono::NaiveDateTime> {
+    let (time, process_id) = name.rsplit_once('-')?;
+    if process_id.is_empty() || !process_id.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    chrono::NaiveDateTime::parse_from_str(time, RUN_DIRECTORY_TIME_FORMAT).ok()
+}
+
+fn get_directory_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => get_directory_size(&entry.path()),
+            Ok(_) => entry.metadata().map(|metadata| metadata.len()).unwrap_or(0),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+fn format_size(bytes: u64) -> String {
+    const MIB: u64 = 1024 * 1024;
+    if bytes >= 1024 * MIB {
+        format!("{:.1} GiB", bytes as f64 / (1024 * MIB) as f64)
+    } else {
+        format!("{:.1} MiB", bytes as f64 / MIB as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestDirectory;
+
+    fn make_run_directory(logs_path: &Path, days_ago: i64, size_bytes: usize) -> PathBuf {
+        let start_time = chrono::Local::now() - chrono::Duration::days(days_ago);
+        let path = logs_path.join(format!(
+            "{}-{}",
+            start_time.format(RUN_DIRECTORY_TIME_FORMAT),
+            days_ago
+        ));
+        std::fs::create_dir_all(&path).unwrap();
+        std::fs::write(path.join("1_script_output.log"), vec![b'a'; size_bytes]).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_old_and_extra_runs_are_removed_and_failed_runs_kept_longer() {
+        let logs_path = TestDirectory::new("log_retention");
+
+        let newest = make_run_directory(&logs_path, 0, 100);
+        let recent = make_run_directory(&logs_path, 1, 100);
+        let failed = make_run_directory(&logs_path, 2, 100);
+        let older = make_run_directory(&logs_path, 3, 100);
+        let failed_expired = make_run_directory(&logs_path, 15, 100);
+        let expired = make_run_directory(&logs_path, 11, 100);
+        let not_a_run = logs_path.join("something-else");
+        std::fs::create_dir_all(&not_a_run).unwrap();
+
+        let retention = config::LogRetention {
+            max_age_days: 10,
+            max_total_size_mb: 0,
+            max_runs: 4,
+            keep_failed_runs_longer: true,
+        };
+        let summary = clean_up_logs(
+            &logs_path,
+            &retention,
+            &[],
+            &HashSet::from([failed.clone(), failed_expired.clone()]),
+        );
+
+        assert!(newest.exists());
+        assert!(recent.exists());
+        assert!(failed.exists());
+        assert!(!older.exists());
+        assert!(failed_expired.exists());
+        assert!(!expired.exists());
+        assert!(not_a_run.exists());
+        // the old failed run is kept instead of a newer successful one
+        assert_eq!(summary.kept_runs, 4);
+        assert_eq!(summary.removed_runs, 2);
+        assert_eq!(summary.removed_bytes, 200);
+    }
+}
@@ -0,0 +1,996 @@
+// Copyright (C) Pavel Grebnev 2025
+// Distributed under the MIT License (license terms are at http://opensource.org/licenses/MIT).
+
+// The log viewer shows the full output of all scripts of an execution as one list of lines.
+// Only a small window of lines around the visible part is kept in memory,
+// everything else is read from the output files on demand using their indexes.
+// Search and filtering go through the whole files, so they run in separate threads.
+
+use crossbeam_channel::{unbounded, Receiver, Sender, TryRecvError};
+use std::cell::Cell;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::ansi_escapes;
+use crate::execution_history;
+use crate::execution_thread::OutputType;
+use crate::file_utils;
+use crate::log_archive;
+use crate::log_index;
+use crate::parallel_execution_manager;
+
+// more than fits in a maximized pane on any reasonable screen
+const CACHED_LINES: usize = 300;
+const SEARCH_CHUNK_LINES: usize = 4096;
+const FILTER_CHUNK_LINES: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineFilter {
+    StdOut,
+    StdErr,
+    Events,
+}
+
+pub struct LogSourceDescription {
+    pub title: String,
+    pub output_path: PathBuf,
+}
+
+pub struct ViewerLine {
+    // position of the line in the list of lines that pass the filters
+    pub position: usize,
+    pub text: String,
+    pub output_type: OutputType,
+    pub timestamp: Option<chrono::DateTime<chrono::Local>>,
+}
+
+struct LogSource {
+    description: LogSourceDescription,
+    reader: Option<log_index::ScriptOutputReader>,
+    // indexes of the lines that pass the filters, None when all the lines pass
+    filtered_lines: Option<Vec<usize>>,
+    // how many lines of the file were already checked against the filters
+    checked_line_count: usize,
+}
+
+#[derive(Clone, Copy)]
+struct LineFilters {
+    show_stdout: bool,
+    show_stderr: bool,
+    show_events: bool,
+}
+
+// lines that passed the filters in a part of a source file
+struct FilteredChunk {
+    source_idx: usize,
+    checked_line_count: usize,
+    passing_lines: Vec<usize>,
+}
+
+struct FilterRange {
+    source_idx: usize,
+    output_path: PathBuf,
+    first_line: usize,
+    end_line: usize,
+}
+
+// the lines of a source are addressed by keys, 0 is the header and N + 1 is the line N of the file
+struct SearchSource {
+    title: String,
+    output_path: PathBuf,
+    line_count: usize,
+}
+
+struct SearchRequest {
+    text: String,
+    sources: Vec<SearchSource>,
+    filters: LineFilters,
+    // source index and line key, the forward search includes it and the backward one doesn't
+    start: (usize, usize),
+    is_forward: bool,
+}
+
+// work running in a separate thread, dropping the task tells the thread to stop
+struct BackgroundTask<T> {
+    receiver: Receiver<T>,
+    is_cancelled: Arc<AtomicBool>,
+}
+
+impl<T: Send + 'static> BackgroundTask<T> {
+    fn start(work: impl FnOnce(&Sender<T>, &AtomicBool) + Send + 'static) -> Self {
+        let (sender, receiver) = unbounded();
+        let is_cancelled = Arc::new(AtomicBool::new(false));
+        std::thread::spawn({
+            let is_cancelled = is_cancelled.clone();
+            move || work(&sender, &is_cancelled)
+        });
+        Self {
+            receiver,
+            is_cancelled,
+        }
+    }
+
+    // returns the results that arrived since the last call and whether the work has finished
+    fn receive(&self) -> (Vec<T>, bool) {
+        let mut results = Vec::new();
+        loop {
+            match self.receiver.try_recv() {
+                Ok(result) => results.push(result),
+                Err(TryRecvError::Empty) => return (results, false),
+                Err(TryRecvError::Disconnected) => return (results, true),
+            }
+        }
+    }
+}
+
+impl<T> Drop for BackgroundTask<T> {
+    fn drop(&mut self) {
+        self.is_cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+pub struct LogViewer {
+    pub title: String,
+    // if set, new scripts of this execution will be added to the viewer as they start
+    pub execution_id: Option<parallel_execution_manager::ExecutionId>,
+    pub show_stdout: bool,
+    pub show_stderr: bool,
+    pub show_events: bool,
+    pub search_text: String,
+    pub search_result: Option<usize>,
+    pub has_search_failed: bool,
+    pub cached_lines: Vec<ViewerLine>,
+    // updated when the viewer is drawn, since only then we know how much space we have
+    pub rendered_line_count: Cell<usize>,
+    sources: Vec<LogSource>,
+    first_line: usize,
+    is_following_end: bool,
+    has_loaded_final_output: bool,
+    filter_task: Option<BackgroundTask<FilteredChunk>>,
+    // while the filtering is in progress, the first line of this source stays on the top
+    anchored_source_idx: Option<usize>,
+    search_task: Option<BackgroundTask<Option<(usize, usize)>>>,
+    // the search starts when the filtering has finished, since it needs the line positions
+    pending_search: Option<bool>,
+}
+
+impl LogViewer {
+    pub fn new(
+        title: String,
+        execution_id: Option<parallel_execution_manager::ExecutionId>,
+        sources: Vec<LogSourceDescription>,
+    ) -> Self {
+        let mut log_viewer = Self {
+            title,
+            execution_id,
+            show_stdout: true,
+            show_stderr: true,
+            show_events: true,
+            search_text: String::new(),
+            search_result: None,
+            has_search_failed: false,
+            cached_lines: Vec::new(),
+            rendered_line_count: Cell::new(1),
+            sources: Vec::new(),
+            first_line: 0,
+            is_following_end: true,
+            has_loaded_final_output: execution_id.is_none(),
+            filter_task: None,
+            anchored_source_idx: None,
+            search_task: None,
+            pending_search: None,
+        };
+        log_viewer.add_new_sources(sources);
+        log_viewer.reload_cached_lines();
+        log_viewer
+    }
+
+    pub fn get_total_line_count(&self) -> usize {
+        (0..self.sources.len())
+            .map(|source_idx| self.get_source_line_count(source_idx))
+            .sum()
+    }
+
+    pub fn get_first_visible_line(&self) -> usize {
+        if self.is_following_end {
+            self.get_max_first_line()
+        } else {
+            self.first_line
+        }
+    }
+
+    pub fn get_max_first_line(&self) -> usize {
+        self.get_total_line_count()
+            .saturating_sub(self.rendered_line_count.get())
+    }
+
+    pub fn get_source_count(&self) -> usize {
+        self.sources.len()
+    }
+
+    pub fn is_filtering(&self) -> bool {
+        self.filter_task.is_some()
+    }
+
+    pub fn is_searching(&self) -> bool {
+        self.search_task.is_some() || self.pending_search.is_some()
+    }
+
+    // picks up the new output of the execution, after it has finished its logs don't change
+    pub fn refresh_from_execution(
+        &mut self,
+        execution: Option<&parallel_execution_manager::Execution>,
+    ) {
+        if self.has_loaded_final_output {
+            return;
+        }
+
+        let Some(execution) = execution else {
+            self.has_loaded_final_output = true;
+            return;
+        };
+        // checked before reading the logs, so nothing written after it is missed
+        let has_finished = execution.has_finished_execution();
+        self.refresh(get_execution_log_sources(execution));
+        self.has_loaded_final_output = has_finished;
+    }
+
+    // applies the results of the search and filtering that arrived since the last call
+    pub fn receive_background_results(&mut self) {
+        if let Some(filter_task) = &self.filter_task {
+            let (chunks, has_finished) = filter_task.receive();
+            let has_received_chunks = !chunks.is_empty();
+            for chunk in chunks {
+                let source = &mut self.sources[chunk.source_idx];
+                if let Some(filtered_lines) = &mut source.filtered_lines {
+                    filtered_lines.extend(chunk.passing_lines);
+                }
+                source.checked_line_count = chunk.checked_line_count;
+            }
+
+            if has_finished {
+                self.filter_task = None;
+                // the lines written meanwhile are checked by the next task
+                self.update_filtered_lines();
+                if self.filter_task.is_none() {
+                    self.anchored_source_idx = None;
+                    if let Some(is_forward) = self.pending_search.take() {
+                        self.find(is_forward);
+                    }
+                }
+            }
+
+            if has_received_chunks {
+                if let Some(anchored_source_idx) = self.anchored_source_idx {
+                    self.first_line = self.get_source_first_line(anchored_source_idx);
+                }
+                self.reload_cached_lines();
+            }
+        }
+
+        if let Some(search_task) = &self.search_task {
+            let (results, has_finished) = search_task.receive();
+            if has_finished {
+                self.search_task = None;
+            }
+            if let Some(found_key) = results.into_iter().next() {
+                self.search_task = None;
+                match found_key.and_then(|(source_idx, key)| self.get_line_by_key(source_idx, key))
+                {
+                    Some(found_line) => self.show_search_result(found_line),
+                    None => self.has_search_failed = true,
+                }
+            }
+        }
+    }
+
+    // returns true if anything changed
+    pub fn refresh(&mut self, new_sources: Vec<LogSourceDescription>) -> bool {
+        let mut has_changed = self.add_new_sources(new_sources);
+
+        for source in &mut self.sources {
+            match &mut source.reader {
+                Some(reader) => has_changed |= reader.refresh(),
+                None => {
+                    // the file could be not created yet when we learned about the script
+                    source.reader =
+                        log_index::ScriptOutputReader::open(&source.description.output_path).ok();
+                    has_changed |= source.reader.is_some();
+                }
+            }
+        }
+
+        if has_changed {
+            self.update_filtered_lines();
+            self.reload_cached_lines();
+        }
+        has_changed
+    }
+
+    pub fn set_filter(&mut self, filter: LineFilter, is_enabled: bool) {
+        let first_visible_line = self.get_first_visible_line();
+        let top_source_idx = self
+            .locate(first_visible_line)
+            .map(|(source_idx, _)| source_idx);
+
+        match filter {
+            LineFilter::StdOut => self.show_stdout = is_enabled,
+            LineFilter::StdErr => self.show_stderr = is_enabled,
+            LineFilter::Events => self.show_events = is_enabled,
+        }
+
+        // the old results are for the old filters
+        self.filter_task = None;
+        self.search_task = None;
+        self.pending_search = None;
+
+        let do_all_lines_pass = self.show_stdout && self.show_stderr && self.show_events;
+        for source in &mut self.sources {
+            source.filtered_lines = if do_all_lines_pass {
+                None
+            } else {
+                Some(Vec::new())
+            };
+            source.checked_line_count = 0;
+        }
+        self.update_filtered_lines();
+
+        // positions are different now, keep at least the same script on the screen
+        self.search_result = None;
+        self.anchored_source_idx = None;
+        if let Some(top_source_idx) = top_source_idx {
+            self.first_line = self.get_source_first_line(top_source_idx);
+            if self.filter_task.is_some() {
+                self.anchored_source_idx = Some(top_source_idx);
+            }
+        }
+        self.reload_cached_lines();
+    }
+
+    pub fn scroll_by(&mut self, line_count: isize) {
+        let first_visible_line = self.get_first_visible_line();
+        self.scroll_to(first_visible_line.saturating_add_signed(line_count));
+    }
+
+    pub fn scroll_to(&mut self, line: usize) {
+        let max_first_line = self.get_max_first_line();
+        self.first_line = line.min(max_first_line);
+        self.is_following_end = self.first_line >= max_first_line;
+        self.anchored_source_idx = None;
+        self.reload_cached_lines();
+    }
+
+    pub fn jump_to_script(&mut self, is_forward: bool) {
+        let first_visible_line = self.get_first_visible_line();
+        let Some((source_idx, line_in_source)) = self.locate(first_visible_line) else {
+            return;
+        };
+
+        let target_source_idx = if is_forward {
+            source_idx + 1
+        } else if line_in_source > 0 {
+            source_idx
+        } else {
+            source_idx.saturating_sub(1)
+        };
+
+        if target_source_idx >= self.sources.len() {
+            return;
+        }
+
+        self.first_line = self.get_source_first_line(target_source_idx);
+        self.is_following_end = false;
+        self.anchored_source_idx = None;
+        self.reload_cached_lines();
+    }
+
+    pub fn find(&mut self, is_forward: bool) {
+        if self.search_text.is_empty() {
+            return;
+        }
+
+        self.has_search_failed = false;
+        if self.filter_task.is_some() {
+            self.search_task = None;
+            self.pending_search = Some(is_forward);
+            return;
+        }
+
+        let start_line = self
+            .search_result
+            .unwrap_or_else(|| self.get_first_visible_line());
+        let (source_idx, mut key) = self.get_line_key(start_line);
+        if is_forward && self.search_result.is_some() {
+            key += 1;
+        }
+
+        let request = SearchRequest {
+            text: self.search_text.to_lowercase(),
+            sources: self
+                .sources
+                .iter()
+                .map(|source| SearchSource {
+                    title: source.description.title.clone(),
+                    output_path: source.description.output_path.clone(),
+                    // the lines that are not checked against the filters yet have no positions
+                    line_count: match (&source.filtered_lines, &source.reader) {
+                        (Some(_), _) => source.checked_line_count,
+                        (None, Some(reader)) => reader.line_count(),
+                        (None, None) => 0,
+                    },
+                })
+                .collect(),
+            filters: self.get_filters(),
+            start: (source_idx, key),
+            is_forward,
+        };
+        self.search_task = Some(BackgroundTask::start(move |sender, is_cancelled| {
+            let _ = sender.send(search(&request, is_cancelled));
+        }));
+    }
+
+    pub fn set_search_text(&mut self, search_text: String) {
+        self.search_text = search_text;
+        self.search_result = None;
+        self.has_search_failed = false;
+        self.search_task = None;
+        self.pending_search = None;
+    }
+
+    fn add_new_sources(&mut self, new_sources: Vec<LogSourceDescription>) -> bool {
+        let mut has_added_sources = false;
+        for description in new_sources {
+            if self
+                .sources
+                .iter()
+                .any(|source| source.description.output_path == description.output_path)
+            {
+                continue;
+            }
+
+            let do_all_lines_pass = self.show_stdout && self.show_stderr && self.show_events;
+            self.sources.push(LogSource {
+                reader: log_index::ScriptOutputReader::open(&description.output_path).ok(),
+                description,
+                filtered_lines: if do_all_lines_pass {
+                    None
+                } else {
+                    Some(Vec::new())
+                },
+                checked_line_count: 0,
+            });
+            has_added_sources = true;
+        }
+
+        if has_added_sources {
+            self.update_filtered_lines();
+        }
+        has_added_sources
+    }
+
+    // starts checking the new lines against the filters, unless it is already in progress
+    fn update_filtered_lines(&mut self) {
+        if self.filter_task.is_some() {
+            return;
+        }
+
+        let ranges: Vec<FilterRange> = self
+            .sources
+            .iter()
+            .enumerate()
+            .filter_map(|(source_idx, source)| {
+                let (Some(reader), Some(_)) = (&source.reader, &source.filtered_lines) else {
+                    return None;
+                };
+                (source.checked_line_count < reader.line_count()).then(|| FilterRange {
+                    source_idx,
+                    output_path: source.description.output_path.clone(),
+                    first_line: source.checked_line_count,
+                    end_line: reader.line_count(),
+                })
+            })
+            .collect();
+        if ranges.is_empty() {
+            return;
+        }
+
+        let filters = self.get_filters();
+        self.filter_task = Some(BackgroundTask::start(move |sender, is_cancelled| {
+            for range in ranges {
+                filter_lines(&range, filters, sender, is_cancelled);
+            }
+        }));
+    }
+
+    fn show_search_result(&mut self, found_line: usize) {
+        self.search_result = Some(found_line);
+        self.has_search_failed = false;
+        let rendered_line_count = self.rendered_line_count.get();
+        if found_line < self.get_first_visible_line()
+            || found_line >= self.get_first_visible_line() + rendered_line_count
+        {
+            self.scroll_to(found_line.saturating_sub(rendered_line_count / 2));
+        } else {
+            self.reload_cached_lines();
+        }
+    }
+
+    fn get_filters(&self) -> LineFilters {
+        LineFilters {
+            show_stdout: self.show_stdout,
+            show_stderr: self.show_stderr,
+            show_events: self.show_events,
+        }
+    }
+
+    // a line after the last one gets a key past the last source
+    fn get_line_key(&self, line: usize) -> (usize, usize) {
+        let Some((source_idx, line_in_source)) = self.locate(line) else {
+            return (self.sources.len(), 0);
+        };
+
+        let line_in_source = if self.show_events {
+            if line_in_source == 0 {
+                return (source_idx, 0);
+            }
+            line_in_source - 1
+        } else {
+            line_in_source
+        };
+        let line_idx = match &self.sources[source_idx].filtered_lines {
+            Some(filtered_lines) => filtered_lines[line_in_source],
+            None => line_in_source,
+        };
+        (source_idx, line_idx + 1)
+    }
+
+    fn get_line_by_key(&self, source_idx: usize, key: usize) -> Option<usize> {
+        let line_in_source = if key == 0 {
+            0
+        } else {
+            let header_line_count = if self.show_events { 1 } else { 0 };
+            let line_idx = key - 1;
+            let filtered_idx = match &self.sources.get(source_idx)?.filtered_lines {
+                Some(filtered_lines) => filtered_lines.binary_search(&line_idx).ok()?,
+                None => line_idx,
+            };
+            header_line_count + filtered_idx
+        };
+        Some(self.get_source_first_line(source_idx) + line_in_source)
+    }
+
+    fn reload_cached_lines(&mut self) {
+        let first_line = if self.is_following_end {
+            self.get_total_line_count().saturating_sub(CACHED_LINES)
+        } else {
+            self.first_line
+        };
+        self.cached_lines = self.read_lines(first_line, CACHED_LINES);
+    }
+
+    fn get_source_line_count(&self, source_idx: usize) -> usize {
+        let source = &self.sources[source_idx];
+        let header_line_count = if self.show_events { 1 } else { 0 };
+        let line_count = match (&source.filtered_lines, &source.reader) {
+            (Some(filtered_lines), _) => filtered_lines.len(),
+            (None, Some(reader)) => reader.line_count(),
+            (None, None) => 0,
+        };
+        header_line_count + line_count
+    }
+
+    fn get_source_first_line(&self, source_idx: usize) -> usize {
+        (0..source_idx)
+            .map(|source_idx| self.get_source_line_count(source_idx))
+            .sum()
+    }
+
+    // returns the index of the source and the index of the line inside the filtered source lines
+    fn locate(&self, line: usize) -> Option<(usize, usize)> {
+        let mut source_first_line = 0;
+        for source_idx in 0..self.sources.len() {
+            let line_count = self.get_source_line_count(source_idx);
+            if line < source_first_line + line_count {
+                return Some((source_idx, line - source_first_line));
+            }
+            source_first_line += line_count;
+        }
+        None
+    }
+
+    fn read_lines(&mut self, first_line: usize, count: usize) -> Vec<ViewerLine> {
+        let mut result: Vec<ViewerLine> = Vec::new();
+        let Some((first_source_idx, mut line_in_source)) = self.locate(first_line) else {
+            return result;
+        };
+
+        let mut position = first_line;
+        for source in &mut self.sources[first_source_idx..] {
+            if result.len() >= count {
+                break;
+            }
+
+            if self.show_events {
+                if line_in_source == 0 {
+                    result.push(ViewerLine {
+                        position,
+                        text: source.description.title.clone(),
+                        output_type: OutputType::Event,
+                        timestamp: None,
+                    });
+                    position += 1;
+                } else {
+                    line_in_source -= 1;
+                }
+            }
+
+            let Some(reader) = &mut source.reader else {
+                line_in_source = 0;
+                continue;
+            };
+
+            let lines_left = count - result.len();
+            let lines = match &source.filtered_lines {
+                None => reader.read_lines(line_in_source, lines_left),
+                Some(filtered_lines) => {
+                    let end = filtered_lines.len().min(line_in_source + lines_left);
+                    let line_indexes = &filtered_lines[line_in_source.min(end)..end];
+                    let mut lines = Vec::with_capacity(line_indexes.len());
+                    // read consecutive lines together, this is much faster than one by one
+                    for run in line_indexes.chunk_by(|a, b| a + 1 == *b) {
+                        lines.extend(reader.read_lines(run[0], run.len()));
+                    }
+                    lines
+                }
+            };
+
+            for line in lines {
+                result.push(ViewerLine {
+                    position,
+                    text: line.text,
+                    output_type: line.output_type,
+                    timestamp: line.timestamp,
+                });
+                position += 1;
+            }
+            line_in_source = 0;
+        }
+
+        result
+    }
+}
+
+pub fn get_execution_log_sources(
+    execution: &parallel_execution_manager::Execution,
+) -> Vec<LogSourceDescription> {
+    let mut sources = Vec::new();
+    for (script_idx, record) in execution.get_scheduled_scripts_cache().iter().enumerate() {
+        if !record.status.has_script_started() {
+            continue;
+        }
+
+        for retry_count in 0..=record.status.retry_count {
+            let output_path = file_utils::get_script_output_path(
+                execution.get_log_folder_path().clone(),
+                &record.script.original.name,
+                script_idx as isize,
+                retry_count,
+            );
+            if log_archive::log_exists(&output_path) {
+                sources.push(LogSourceDescription {
+                    title: get_source_title(&record.script.original.name, retry_count),
+                    output_path,
+                });
+            }
+        }
+    }
+    sources
+}
+
+pub fn get_history_log_sources(
+    record: &execution_history::ExecutionHistoryRecord,
+) -> Vec<LogSourceDescription> {
+    record
+        .scripts
+        .iter()
+        .flat_map(|script| {
+            script
+                .log_paths
+                .iter()
+                .enumerate()
+                .map(|(retry_count, output_path)| LogSourceDescription {
+                    title: get_source_title(&script.script.name, retry_count),
+                    output_path: output_path.clone(),
+                })
+        })
+        .collect()
+}
+
+fn get_source_title(script_name: &str, retry_count: usize) -> String {
+    if retry_count > 0 {
+        format!("Running \"{}\" retry #{}", script_name, retry_count)
+    } else {
+        format!("Running \"{}\"", script_name)
+    }
+}
+
+fn does_line_pass(output_type: &OutputType, filters: LineFilters) -> bool {
+    match output_type {
+        OutputType::StdOut => filters.show_stdout,
+        OutputType::StdErr | OutputType::Error => filters.show_stderr,
+        OutputType::Event => filters.show_events,
+    }
+}
+
+fn filter_lines(
+    range: &FilterRange,
+    filters: LineFilters,
+    sender: &Sender<FilteredChunk>,
+    is_cancelled: &AtomicBool,
+) {
+    let mut reader = log_index::ScriptOutputReader::open(&range.output_path).ok();
+    let mut checked_line_count = range.first_line;
+    while checked_line_count < range.end_line {
+        if is_cancelled.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let output_types = match &mut reader {
+            Some(reader) => reader.read_output_types(
+                checked_line_count,
+                FILTER_CHUNK_LINES.min(range.end_line - checked_line_count),
+            ),
+            None => Vec::new(),
+        };
+        // the lines that can't be read are skipped, otherwise they would be checked forever
+        if output_types.is_empty() {
+            let _ = sender.send(FilteredChunk {
+                source_idx: range.source_idx,
+                checked_line_count: range.end_line,
+                passing_lines: Vec::new(),
+            });
+            return;
+        }
+
+        let passing_lines = output_types
+            .iter()
+            .enumerate()
+            .filter(|(_, output_type)| does_line_pass(output_type, filters))
+            .map(|(idx, _)| checked_line_count + idx)
+            .collect();
+        checked_line_count += output_types.len();
+        let _ = sender.send(FilteredChunk {
+            source_idx: range.source_idx,
+            checked_line_count,
+            passing_lines,
+        });
+    }
+}
+
+// returns the source index and the key of the found line
+fn search(request: &SearchRequest, is_cancelled: &AtomicBool) -> Option<(usize, usize)> {
+    let is_match = |text: &str| {
+        ansi_escapes::strip_escape_sequences(text)
+            .to_lowercase()
+            .contains(&request.text)
+    };
+    let (start_source_idx, start_key) = request.start;
+
+    if request.is_forward {
+        for (source_idx, source) in request.sources.iter().enumerate().skip(start_source_idx) {
+            let first_key = if source_idx == start_source_idx {
+                start_key
+            } else {
+                0
+            };
+            if first_key == 0 && request.filters.show_events && is_match(&source.title) {
+                return Some((source_idx, 0));
+            }
+
+            let Ok(mut reader) = log_index::ScriptOutputReader::open(&source.output_path) else {
+                continue;
+            };
+            let mut line_idx = first_key.saturating_sub(1);
+            while line_idx < source.line_count {
+                if is_cancelled.load(Ordering::Relaxed) {
+                    return None;
+                }
+                let lines = reader.read_lines(
+                    line_idx,
+                    SEARCH_CHUNK_LINES.min(source.line_count - line_idx),
+                );
+                if lines.is_empty() {
+                    break;
+                }
+                let found_idx = lines.iter().position(|line| {
+                    does_line_pass(&line.output_type, request.filters) && is_match(&line.text)
+                });
+                if let Some(found_idx) = found_idx {
+                    return Some((source_idx, line_idx + found_idx + 1));
+                }
+                line_idx += lines.len();
+            }
+        }
+    } else {
+        let source_count = request.sources.len().min(start_source_idx + 1);
+        for (source_idx, source) in request.sources.iter().enumerate().take(source_count).rev() {
+            let end_key = if source_idx == start_source_idx {
+                start_key
+            } else {
+                usize::MAX
+            };
+            if end_key == 0 {
+                continue;
+            }
+
+            if let Ok(mut reader) = log_index::ScriptOutputReader::open(&source.output_path) {
+                let mut end_line_idx = (end_key - 1).min(source.line_count);
+                while end_line_idx > 0 {
+                    if is_cancelled.load(Ordering::Relaxed) {
+                        return None;
+                    }
+                    let line_idx = end_line_idx.saturating_sub(SEARCH_CHUNK_LINES);
+                    let lines = reader.read_lines(line_idx, end_line_idx - line_idx);
+                    if lines.is_empty() {
+                        break;
+                    }
+                    let found_idx = lines.iter().rposition(|line| {
+                        does_line_pass(&line.output_type, request.filters) && is_match(&line.text)
+                    });
+                    if let Some(found_idx) = found_idx {
+                        return Some((source_idx, line_idx + found_idx + 1));
+                    }
+                    end_line_idx = line_idx;
+                }
+            }
+
+            if request.filters.show_events && is_match(&source.title) {
+                return Some((source_idx, 0));
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config;
+    use crate::execution_thread::OutputLine;
+    use crate::test_utils::TestDirectory;
+
+    fn make_test_sources(test_name: &str) -> (TestDirectory, Vec<LogSourceDescription>) {
+        let directory = TestDirectory::new(test_name);
+
+        let mut sources = Vec::new();
+        for (script_idx, lines) in [
+            vec![
+                ("build", OutputType::StdOut),
+                ("warning", OutputType::StdErr),
+            ],
+            vec![
+                ("test 1", OutputType::StdOut),
+                ("test 2 failed", OutputType::StdErr),
+                ("test 3", OutputType::StdOut),
+            ],
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let output_path = directory.join(format!("{}_output.log", script_idx));
+            let mut writer = log_index::ScriptOutputWriter::create(
+                &output_path,
+                false,
+                config::LogFileFormat::Plain,
+                0,
+            )
+            .unwrap();
+            for (text, output_type) in lines {
+                writer.write_line(&OutputLine {
+                    text: format!("{}\n", text),
+                    output_type,
+                    timestamp: chrono::Local::now(),
+                    highlight: None,
+                    script_idx: None,
+                });
+            }
+            sources.push(LogSourceDescription {
+                title: format!("script {}", script_idx),
+                output_path,
+            });
+        }
+        (directory, sources)
+    }
+
+    fn get_cached_texts(log_viewer: &LogViewer) -> Vec<&str> {
+        log_viewer
+            .cached_lines
+            .iter()
+            .map(|line| line.text.as_str())
+            .collect()
+    }
+
+    fn wait_for_background_work(log_viewer: &mut LogViewer) {
+        let start_time = std::time::Instant::now();
+        while log_viewer.is_filtering() || log_viewer.is_searching() {
+            assert!(start_time.elapsed() < std::time::Duration::from_secs(10));
+            std::thread::sleep(std::time::Duration::from_millis(1));
+            log_viewer.receive_background_results();
+        }
+    }
+
+    #[test]
+    fn test_lines_of_all_sources_are_shown_and_can_be_filtered() {
+        let (_directory, sources) = make_test_sources("log_viewer_filter");
+        let mut log_viewer = LogViewer::new(String::new(), None, sources);
+
+        let all_lines = get_cached_texts(&log_viewer).join("|");
+        log_viewer.set_filter(LineFilter::StdOut, false);
+        wait_for_background_work(&mut log_viewer);
+        let stderr_and_events = get_cached_texts(&log_viewer).join("|");
+        log_viewer.set_filter(LineFilter::Events, false);
+        wait_for_background_work(&mut log_viewer);
+        let stderr_only = get_cached_texts(&log_viewer).join("|");
+
+        assert_eq!(
+            all_lines,
+            "script 0|build|warning|script 1|test 1|test 2 failed|test 3"
+        );
+        assert_eq!(stderr_and_events, "script 0|warning|script 1|test 2 failed");
+        assert_eq!(stderr_only, "warning|test 2 failed");
+    }
+
+    #[test]
+    fn test_search_goes_through_scripts_in_both_directions() {
+        let (_directory, sources) = make_test_sources("log_viewer_search");
+        let mut log_viewer = LogViewer::new(String::new(), None, sources);
+        log_viewer.scroll_to(0);
+
+        log_viewer.set_search_text("TEST".to_string());
+        log_viewer.find(true);
+        wait_for_background_work(&mut log_viewer);
+        let first_result = log_viewer.search_result;
+        log_viewer.find(true);
+        wait_for_background_work(&mut log_viewer);
+        let second_result = log_viewer.search_result;
+        log_viewer.find(false);
+        wait_for_background_work(&mut log_viewer);
+        let third_result = log_viewer.search_result;
+        log_viewer.find(false);
+        wait_for_background_work(&mut log_viewer);
+        let has_failed_at_start = log_viewer.has_search_failed;
+
+        log_viewer.jump_to_script(false);
+        let script_start = log_viewer.get_first_visible_line();
+
+        assert_eq!(first_result, Some(4));
+        assert_eq!(second_result, Some(5));
+        assert_eq!(third_result, Some(4));
+        assert!(has_failed_at_start);
+        assert_eq!(script_start, 3);
+    }
+
+    #[test]
+    fn test_search_started_during_filtering_only_finds_lines_that_pass_the_filters() {
+        let (_directory, sources) = make_test_sources("log_viewer_filtered_search");
+        let mut log_viewer = LogViewer::new(String::new(), None, sources);
+        log_viewer.scroll_to(0);
+
+        log_viewer.set_filter(LineFilter::StdOut, false);
+        log_viewer.set_search_text("test".to_string());
+        log_viewer.find(true);
+        wait_for_background_work(&mut log_viewer);
+        let first_result = log_viewer.search_result;
+        log_viewer.find(true);
+        wait_for_background_work(&mut log_viewer);
+        let has_failed_at_end = log_viewer.has_search_failed;
+
+        assert_eq!(first_result, Some(3));
+        assert!(has_failed_at_end);
+        assert_eq!(
+            get_cached_texts(&log_viewer).join("|"),
+            "script 0|warning|script 1|test 2 failed"
+        );
+    }
+}
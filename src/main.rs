@@ -3,28 +3,45 @@
 
 #![windows_subsystem = "windows"]
 
+mod ansi_escapes;
 mod app_arguments;
 mod color_utils;
 mod config;
 mod config_updaters;
+mod control_socket;
 mod custom_keybinds;
 mod drag_and_drop;
+mod duration_history;
 mod events;
+mod execution_history;
+mod execution_report;
 mod execution_thread;
+mod file_references;
 mod file_utils;
+mod file_watch_triggers;
 mod git_support;
+mod headless_runner;
 mod json_file_updater;
 mod key_mapping;
 mod keybind_editing;
+mod log_archive;
+mod log_index;
+mod log_patterns;
+mod log_retention;
+mod log_viewer;
 mod main_window;
 mod main_window_utils;
 mod main_window_widgets;
 mod parallel_execution_manager;
+mod resource_usage;
 mod ring_buffer;
 mod scenario;
 mod scenario_updaters;
+mod schedules;
 mod sorted_vec;
 mod style;
+#[cfg(test)]
+mod test_utils;
 mod ui_icons;
 
 use iced::window::icon;
@@ -35,6 +52,18 @@ pub fn main() -> iced::Result {
         return Ok(());
     }
 
+    if let Some(send_command) = app_arguments::get_app_arguments().send_command {
+        std::process::exit(control_socket::run_send_command(&send_command));
+    }
+
+    if config::is_headless_mode() {
+        std::process::exit(headless_runner::run_headless());
+    }
+
+    if let Some(exit_code) = control_socket::forward_launch_to_open_instance() {
+        std::process::exit(exit_code);
+    }
+
     let icon = icon::from_rgba(include_bytes!("../res/icon.rgba").to_vec(), 128, 128);
     let icon = if let Ok(icon) = icon {
         Some(icon)
@@ -46,7 +75,7 @@ pub fn main() -> iced::Result {
         icon,
         ..Default::default()
     };
-    iced::application(
+    let result = iced::application(
         main_window::MainWindow::title,
         main_window::MainWindow::update,
         main_window::MainWindow::view,
@@ -54,5 +83,9 @@ pub fn main() -> iced::Result {
     .window(window_settings)
     .subscription(main_window::MainWindow::subscription)
     .theme(main_window::MainWindow::theme)
-    .run_with(main_window::MainWindow::new)
+    .run_with(main_window::MainWindow::new);
+
+    log_archive::remove_extracted_logs();
+
+    result
 }
@@ -1,19 +1,28 @@
 // Copyright (C) Pavel Grebnev 2023-2025
 // Distributed under the MIT License (license terms are at http://opensource.org/licenses/MIT).
 
+use crate::ansi_escapes;
 use crate::color_utils;
 use crate::config;
+use crate::control_socket;
 use crate::custom_keybinds;
 use crate::drag_and_drop;
 use crate::drag_and_drop::{DragAndDropList, DragResult, DropArea};
 use crate::events;
+use crate::execution_history;
 use crate::execution_thread;
+use crate::file_references;
+use crate::file_watch_triggers;
 use crate::git_support;
 use crate::keybind_editing;
+use crate::log_archive;
+use crate::log_retention;
+use crate::log_viewer;
 use crate::main_window_utils::*;
 use crate::main_window_widgets::*;
 use crate::parallel_execution_manager;
 use crate::scenario;
+use crate::schedules;
 use crate::style;
 use crate::ui_icons;
 use drag_and_drop::DropAreaState;
@@ -25,11 +34,11 @@ use iced::mouse::Event;
 use iced::theme::Theme;
 use iced::widget::pane_grid::{self, Configuration, PaneGrid};
 use iced::widget::scrollable::{AbsoluteOffset, RelativeOffset, Scrollbar};
-use iced::widget::text::LineHeight;
+use iced::widget::text::{LineHeight, Wrapping};
 use iced::widget::{
     button, checkbox, column, container, horizontal_rule, horizontal_space, image, image::Handle,
-    opaque, pick_list, responsive, row, scrollable, stack, text, text_input, tooltip,
-    vertical_space, Column, Space,
+    mouse_area, opaque, pick_list, responsive, rich_text, row, scrollable, span, stack, text,
+    text_input, tooltip, vertical_slider, vertical_space, Column, Space,
 };
 use iced::window::{self, request_user_attention};
 use iced::{keyboard, mouse, ContentFit, Task};
@@ -48,6 +57,65 @@ static EMPTY_STRING: String = String::new();
 static EDITING_TITLE: std::sync::LazyLock<String> =
     std::sync::LazyLock::new(|| format!("Scripter [Editing] v{}", env!("CARGO_PKG_VERSION")));
 
+const EXECUTION_REPORT_FORMAT_PICK_LIST: &[config::ExecutionReportFormat] = &[
+    config::ExecutionReportFormat::None,
+    config::ExecutionReportFormat::Json,
+    config::ExecutionReportFormat::JUnitXml,
+];
+impl std::fmt::Display for config::ExecutionReportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                config::ExecutionReportFormat::None => "Don't write reports",
+                config::ExecutionReportFormat::Json => "JSON",
+                config::ExecutionReportFormat::JUnitXml => "JUnit XML",
+            }
+        )
+    }
+}
+
+const LOG_FILE_FORMAT_PICK_LIST: &[config::LogFileFormat] = &[
+    config::LogFileFormat::Plain,
+    config::LogFileFormat::Timestamped,
+    config::LogFileFormat::JsonLines,
+];
+impl std::fmt::Display for config::LogFileFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                config::LogFileFormat::Plain => "Plain text",
+                config::LogFileFormat::Timestamped => "Text with timestamps",
+                config::LogFileFormat::JsonLines => "JSON lines",
+            }
+        )
+    }
+}
+
+const LOG_COMPRESSION_MODE_PICK_LIST: &[config::LogCompressionMode] = &[
+    config::LogCompressionMode::Disabled,
+    config::LogCompressionMode::WhenExecutionFinishes,
+    config::LogCompressionMode::AfterDays,
+];
+impl std::fmt::Display for config::LogCompressionMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                config::LogCompressionMode::Disabled => "Don't compress logs",
+                config::LogCompressionMode::WhenExecutionFinishes => {
+                    "Compress when execution finishes"
+                }
+                config::LogCompressionMode::AfterDays => "Compress after some days",
+            }
+        )
+    }
+}
+
 const CONFIG_UPDATE_BEHAVIOR_PICK_LIST: &[config::ConfigUpdateBehavior] = &[
     config::ConfigUpdateBehavior::OnStartup,
     config::ConfigUpdateBehavior::OnManualSave,
@@ -71,21 +139,38 @@ static ARGUMENTS_INPUT_ID: Lazy<text_input::Id> = Lazy::new(text_input::Id::uniq
 static SCRIPTS_PANE_SCROLL_ID: Lazy<scrollable::Id> = Lazy::new(scrollable::Id::unique);
 static EXECUTIONS_PANE_SCROLL_ID: Lazy<scrollable::Id> = Lazy::new(scrollable::Id::unique);
 static LOGS_SCROLL_ID: Lazy<scrollable::Id> = Lazy::new(scrollable::Id::unique);
+static SCRIPT_INPUT_ID: Lazy<text_input::Id> = Lazy::new(text_input::Id::unique);
+
+const LOG_VIEWER_LINE_HEIGHT: f32 = 18.0;
+// readable on both light and dark themes
+const SLOW_SCRIPT_TEXT_COLOR: iced::Color = iced::Color::from_rgb(0.9, 0.6, 0.1);
+const LOG_WARNING_TEXT_COLOR: iced::Color = iced::Color::from_rgb(0.9, 0.6, 0.1);
+const LOG_INFO_TEXT_COLOR: iced::Color = iced::Color::from_rgb(0.3, 0.6, 0.95);
+const LOG_VIEWER_LINES_PER_WHEEL_STEP: f32 = 3.0;
+// longer lines are cut, drawing them would make the UI unresponsive
+const LOG_VIEWER_MAX_DISPLAYED_LINE_LENGTH: usize = 1000;
 
 // caches for visual elements content
 pub(crate) struct VisualCaches {
     pub(crate) autorerun_count: String,
     pub(crate) autorerun_delay_sec: String,
+    pub(crate) timeout_sec: String,
     pub(crate) is_custom_title_editing: bool,
     pub(crate) icons: ui_icons::IconCaches,
     pub(crate) keybind_hints: HashMap<keybind_editing::KeybindAssociatedData, String>,
     pub(crate) selected_execution_log: Option<parallel_execution_manager::ExecutionId>,
+    pub(crate) selected_history_record: Option<PathBuf>,
+    pub(crate) log_viewer: Option<log_viewer::LogViewer>,
+    pub(crate) script_input: String,
+    pub(crate) is_script_input_secret: bool,
     pub(crate) git_branch_requester: Option<git_support::GitCurrentBranchRequester>,
+    pub(crate) log_cleaner: log_retention::LogCleaner,
     pub(crate) button_key_caches: ButtonKeyCaches,
     pub(crate) quick_launch_buttons: Vec<QuickLaunchButton>,
     pub(crate) enable_script_filtering: bool,
     pub(crate) enable_title_editing: bool,
     pub(crate) custom_theme: Option<config::CustomTheme>,
+    pub(crate) next_scheduled_runs: HashMap<config::Guid, chrono::DateTime<chrono::Local>>,
 }
 
 #[derive(Default)]
@@ -250,8 +335,37 @@ pub(crate) enum WindowMessage {
     EditScriptCommandPathType(ConfigScriptId, config::PathType),
     EditScriptWorkingDirectory(ConfigScriptId, String),
     EditScriptWorkingDirectoryPathType(ConfigScriptId, config::PathType),
+    AddScriptEnvVar(ConfigScriptId),
+    RemoveScriptEnvVar(ConfigScriptId, usize),
+    EditScriptEnvVarName(ConfigScriptId, usize, String),
+    EditScriptEnvVarValue(ConfigScriptId, usize, String),
+    EditScriptEnvFilePath(ConfigScriptId, String),
+    EditScriptEnvFilePathType(ConfigScriptId, config::PathType),
+    AddScriptExitCode(ConfigScriptId),
+    RemoveScriptExitCode(ConfigScriptId, usize),
+    EditScriptExitCode(ConfigScriptId, usize, String),
+    EditScriptExitCodeResult(ConfigScriptId, usize, config::ExitCodeResult),
+    AddScriptRunCondition(ConfigScriptId),
+    RemoveScriptRunCondition(ConfigScriptId, usize),
+    EditScriptRunConditionType(ConfigScriptId, usize, config::RunConditionType),
+    EditScriptRunConditionValue(ConfigScriptId, usize, String),
+    EditScriptLogPatternRules(ConfigScriptId, LogPatternRuleEdit),
     EditScriptIconPath(ConfigScriptId, String),
     EditScriptIconPathType(ConfigScriptId, config::PathType),
+    AddPresetItemToPreset(ConfigScriptId, config::Guid),
+    RemovePresetItemFromPreset(ConfigScriptId, usize),
+    AddScriptSchedule(config::Guid),
+    RemoveScriptSchedule(usize),
+    EditScheduleTriggerKind(usize, ScheduleTriggerKind),
+    EditScheduleInterval(usize, String),
+    EditScheduleCronExpression(usize, String),
+    EditScheduleMissedRunPolicy(usize, config::MissedRunPolicy),
+    AddScriptFileWatch(config::Guid),
+    RemoveScriptFileWatch(usize),
+    EditFileWatchPath(usize, String, usize),
+    EditFileWatchIgnoredPath(usize, String, usize),
+    EditFileWatchDebounce(usize, String),
+    EditFileWatchRunningScriptPolicy(usize, config::FileWatchRunningScriptPolicy),
     EditArgumentsLineForConfig(ConfigScriptId, String),
     EditArgumentsLineForScriptExecution(String),
     EditArgumentsRequirement(ConfigScriptId, config::ArgumentRequirement),
@@ -268,14 +382,20 @@ pub(crate) enum WindowMessage {
     EditAutorerunCountForExecutionList(String),
     EditAutorerunDelaySecForConfig(ConfigScriptId, String),
     EditAutorerunDelaySecForExecutionList(String),
+    EditTimeoutSecForConfig(ConfigScriptId, String),
+    EditTimeoutSecForExecutionList(String),
     EditReactionToPreviousFailuresForConfig(ConfigScriptId, config::ReactionToPreviousFailures),
     EditReactionToPreviousFailuresForExecutionList(config::ReactionToPreviousFailures),
+    ToggleWaitForPreviousScriptsForExecutionList(bool),
+    ToggleScriptDependencyForExecutionList(usize, bool),
     ToggleUseCustomExecutor(ConfigScriptId, bool),
     EditCustomExecutor(ConfigScriptId, String, usize),
     EditExecutorArguments(ConfigScriptId, String, usize),
     ToggleAutocleanOnSuccessForConfig(ConfigScriptId, bool),
     ToggleAutocleanOnSuccessForExecutionList(bool),
     ToggleIgnoreOutput(ConfigScriptId, bool),
+    ToggleKeepChildProcesses(ConfigScriptId, bool),
+    ToggleAcceptInput(ConfigScriptId, bool),
     ToggleIsHidden(ConfigScriptId, bool),
     EnterWindowEditMode,
     ExitWindowEditMode,
@@ -288,6 +408,23 @@ pub(crate) enum WindowMessage {
     SettingsToggleKeepWindowSize(config::ConfigEditMode, bool),
     SettingsToggleScriptFiltering(config::ConfigEditMode, bool),
     SettingsToggleShowWorkingDirectory(config::ConfigEditMode, bool),
+    SettingsToggleKeepEscapeSequencesInLogs(config::ConfigEditMode, bool),
+    SettingsLogFileFormatChanged(config::ConfigEditMode, config::LogFileFormat),
+    SettingsEditLogPatternRules(config::ConfigEditMode, LogPatternRuleEdit),
+    SettingsEditEditorCommand(config::ConfigEditMode, String),
+    SettingsToggleSingleInstance(config::ConfigEditMode, bool),
+    SettingsToggleEnableControlSocket(config::ConfigEditMode, bool),
+    SettingsEditParallelScriptsLimit(config::ConfigEditMode, String),
+    SettingsEditSlowScriptThreshold(config::ConfigEditMode, String),
+    SettingsEditLogRetentionMaxAgeDays(config::ConfigEditMode, String),
+    SettingsEditLogRetentionMaxTotalSizeMb(config::ConfigEditMode, String),
+    SettingsEditLogRetentionMaxRuns(config::ConfigEditMode, String),
+    SettingsToggleKeepFailedRunsLonger(config::ConfigEditMode, bool),
+    SettingsLogCompressionModeChanged(config::ConfigEditMode, config::LogCompressionMode),
+    SettingsEditLogCompressionAfterDays(config::ConfigEditMode, String),
+    SettingsExecutionReportFormatChanged(config::ConfigEditMode, config::ExecutionReportFormat),
+    SettingsEditExecutionReportFolder(config::ConfigEditMode, String),
+    SettingsEditExecutionReportFolderPathType(config::ConfigEditMode, config::PathType),
     SettingsToggleTitleEditing(config::ConfigEditMode, bool),
     SettingsUpdateBehaviorChanged(config::ConfigEditMode, config::ConfigUpdateBehavior),
     SettingsToggleShowCurrentGitBranch(config::ConfigEditMode, bool),
@@ -324,6 +461,8 @@ pub(crate) enum WindowMessage {
     SetExecutionListTitleEditing(bool),
     EditExecutionListTitle(String),
     OpenWithDefaultApplication(PathBuf),
+    // path, line, column
+    OpenFileReference(PathBuf, u32, Option<u32>),
     OpenUrl(String),
     OpenLogFolder(parallel_execution_manager::ExecutionId),
     OpenFirstFailedScriptLog(parallel_execution_manager::ExecutionId),
@@ -335,6 +474,21 @@ pub(crate) enum WindowMessage {
     OnQuickLaunchButtonPressed(config::Guid),
     AddToQuickLaunchPanel(config::Guid),
     RemoveFromQuickLaunchPanel(config::Guid),
+    SelectHistoryRecord(PathBuf),
+    OpenHistoryLog(PathBuf),
+    RescheduleFromHistory(PathBuf),
+    OpenLogViewer(parallel_execution_manager::ExecutionId),
+    OpenHistoryLogViewer(PathBuf),
+    CloseLogViewer,
+    LogViewerScroll(isize),
+    LogViewerScrollTo(f64),
+    LogViewerToggleFilter(log_viewer::LineFilter, bool),
+    LogViewerSearchTextChanged(String),
+    LogViewerFind(bool),
+    LogViewerJumpToScript(bool),
+    EditScriptInput(String),
+    ToggleScriptInputSecret(bool),
+    SendScriptInput(parallel_execution_manager::ExecutionId),
 }
 
 pub(crate) struct MainWindow {
@@ -349,6 +503,10 @@ pub(crate) struct MainWindow {
     pub(crate) window_state: WindowState,
     pub(crate) keybinds: custom_keybinds::CustomKeybinds<keybind_editing::KeybindAssociatedData>,
     pub(crate) displayed_configs_list_cache: Vec<ScriptListCacheRecord>,
+    pub(crate) execution_history: Vec<execution_history::ExecutionHistoryRecord>,
+    pub(crate) scheduler: schedules::Scheduler,
+    pub(crate) file_watch_triggers: file_watch_triggers::FileWatchTriggers,
+    pub(crate) control_server: Option<control_socket::ControlServer>,
 }
 
 impl MainWindow {
@@ -365,9 +523,14 @@ impl MainWindow {
             b: Box::new(Configuration::Split {
                 axis: pane_grid::Axis::Vertical,
                 ratio: 0.5,
-                a: Box::new(Configuration::Pane(AppPane::new(
-                    PaneVariant::ExecutionList,
-                ))),
+                a: Box::new(Configuration::Split {
+                    axis: pane_grid::Axis::Horizontal,
+                    ratio: 0.7,
+                    a: Box::new(Configuration::Pane(AppPane::new(
+                        PaneVariant::ExecutionList,
+                    ))),
+                    b: Box::new(Configuration::Pane(AppPane::new(PaneVariant::History))),
+                }),
                 b: Box::new(Configuration::Pane(AppPane::new(PaneVariant::LogOutput))),
             }),
         };
@@ -383,31 +546,41 @@ impl MainWindow {
             config::get_current_rewritable_config(&app_config).show_current_git_branch;
 
         let scenario = scenario::get_scenario_copy();
+        let execution_history = execution_history::read_records(&app_config.paths.logs_path);
 
         let mut main_window = MainWindow {
             panes,
             pane_by_pane_type,
-            execution_manager: parallel_execution_manager::ParallelExecutionManager::new(),
+            execution_manager: parallel_execution_manager::ParallelExecutionManager::new(
+                &app_config.paths.logs_path,
+            ),
             theme: get_theme(&app_config),
             app_config,
             scenario,
             visual_caches: VisualCaches {
                 autorerun_count: String::new(),
                 autorerun_delay_sec: String::new(),
+                timeout_sec: String::new(),
                 is_custom_title_editing: false,
                 icons: ui_icons::IconCaches::new(),
                 keybind_hints: HashMap::new(),
                 selected_execution_log: None,
+                selected_history_record: None,
+                log_viewer: None,
+                script_input: String::new(),
+                is_script_input_secret: false,
                 git_branch_requester: if show_current_git_branch {
                     Some(git_support::GitCurrentBranchRequester::new())
                 } else {
                     None
                 },
+                log_cleaner: log_retention::LogCleaner::new(),
                 button_key_caches: ButtonKeyCaches::default(),
                 quick_launch_buttons: Vec::new(),
                 enable_script_filtering: false,
                 enable_title_editing: false,
                 custom_theme: None,
+                next_scheduled_runs: HashMap::new(),
             },
             edit_data: EditData {
                 script_filter: String::new(),
@@ -464,6 +637,10 @@ impl MainWindow {
             },
             keybinds: custom_keybinds::CustomKeybinds::new(),
             displayed_configs_list_cache: Vec::new(),
+            execution_history,
+            scheduler: schedules::Scheduler::new(),
+            file_watch_triggers: file_watch_triggers::FileWatchTriggers::new(),
+            control_server: None,
         };
 
         update_theme_icons(&mut main_window);
@@ -471,8 +648,11 @@ impl MainWindow {
         keybind_editing::update_keybinds(&mut main_window);
         let edit_mode = config::get_main_edit_mode(&main_window.app_config);
         keybind_editing::update_keybind_visual_caches(&mut main_window, edit_mode);
+        start_log_cleanup(&mut main_window);
+        update_control_server(&mut main_window);
 
-        let task = init_from_scenario(&mut main_window);
+        let scenario = main_window.scenario.clone();
+        let task = init_from_scenario(&mut main_window, scenario, &[]);
         if let Some(task) = task {
             (main_window, task)
         } else {
@@ -819,7 +999,7 @@ impl MainWindow {
                 if self.window_state.is_command_key_down {
                     if self.window_state.is_alt_key_down {
                         let scripts = get_resulting_scripts_from_guid(&self.app_config, script_uid);
-                        start_new_execution_from_provided_scripts(self, scripts);
+                        start_new_execution_from_provided_execution_scripts(self, scripts);
                     } else {
                         try_add_script_to_execution_or_start_new(self, script_uid);
                     }
@@ -836,7 +1016,7 @@ impl MainWindow {
             }
             WindowMessage::RunScriptInParallel(script_uid) => {
                 let scripts = get_resulting_scripts_from_guid(&self.app_config, script_uid);
-                start_new_execution_from_provided_scripts(self, scripts);
+                start_new_execution_from_provided_execution_scripts(self, scripts);
             }
             WindowMessage::RunEditedScriptsInParallel => {
                 if !self.edit_data.window_edit_data.is_some() {
@@ -923,9 +1103,15 @@ impl MainWindow {
                 }
             }
             WindowMessage::Tick(_now) => {
+                let mut tasks = Vec::new();
+                // a launch forwarded from another instance brings the window to front
+                tasks.extend(process_control_requests(self));
                 let tick_result = self.execution_manager.tick(&self.app_config);
+                run_scheduled_scripts(self);
+                run_scripts_triggered_by_file_changes(self);
                 if !tick_result.just_finished_executions.is_empty() {
                     for execution_id in tick_result.just_finished_executions {
+                        record_execution_to_history(self, execution_id);
                         if should_autoclean_on_success(self, execution_id) {
                             remove_execution(self, execution_id);
                         }
@@ -935,18 +1121,29 @@ impl MainWindow {
 
                     if config::get_main_rewritable_config(&self.app_config).window_status_reactions
                     {
-                        return window::get_oldest().and_then(|window_id| {
+                        tasks.push(window::get_oldest().and_then(|window_id| {
                             request_user_attention(
                                 window_id,
                                 Some(window::UserAttention::Informational),
                             )
-                        });
+                        }));
                     }
                 }
 
                 if let Some(git_branch_requester) = &mut self.visual_caches.git_branch_requester {
                     git_branch_requester.update();
                 }
+                self.visual_caches.log_cleaner.update();
+
+                if let Some(log_viewer) = &mut self.visual_caches.log_viewer {
+                    log_viewer.receive_background_results();
+                    let execution = log_viewer.execution_id.and_then(|execution_id| {
+                        self.execution_manager
+                            .get_started_executions()
+                            .get(execution_id)
+                    });
+                    log_viewer.refresh_from_execution(execution);
+                }
 
                 if tick_result.has_just_disconnected_executions {
                     events::on_execution_pane_content_height_decreased(self);
@@ -955,8 +1152,12 @@ impl MainWindow {
                 }
 
                 if !self.window_state.has_maximized_pane {
-                    return scrollable::snap_to(LOGS_SCROLL_ID.clone(), RelativeOffset::END);
+                    tasks.push(scrollable::snap_to(
+                        LOGS_SCROLL_ID.clone(),
+                        RelativeOffset::END,
+                    ));
                 }
+                return Task::batch(tasks);
             }
             WindowMessage::SelectExecutionScript(script_idx) => {
                 let is_incompatible_script_selected = match &self.window_state.selected_scripts {
@@ -1108,6 +1309,116 @@ impl MainWindow {
                     script.working_directory.path_type = value
                 });
             }
+            WindowMessage::AddScriptEnvVar(config_script_id) => {
+                apply_config_script_edit(self, config_script_id, |script| {
+                    script.env_vars.push(config::EnvironmentVariable {
+                        name: String::new(),
+                        value: String::new(),
+                    });
+                });
+            }
+            WindowMessage::RemoveScriptEnvVar(config_script_id, index) => {
+                apply_config_script_edit(self, config_script_id, move |script| {
+                    if index < script.env_vars.len() {
+                        script.env_vars.remove(index);
+                    }
+                });
+            }
+            WindowMessage::EditScriptEnvVarName(config_script_id, index, new_name) => {
+                apply_config_script_edit(self, config_script_id, move |script| {
+                    if let Some(env_var) = script.env_vars.get_mut(index) {
+                        env_var.name = new_name;
+                    }
+                });
+            }
+            WindowMessage::EditScriptEnvVarValue(config_script_id, index, new_value) => {
+                apply_config_script_edit(self, config_script_id, move |script| {
+                    if let Some(env_var) = script.env_vars.get_mut(index) {
+                        env_var.value = new_value;
+                    }
+                });
+            }
+            WindowMessage::EditScriptEnvFilePath(config_script_id, new_path) => {
+                apply_config_script_edit(self, config_script_id, move |script| {
+                    script.env_file.path = new_path
+                });
+            }
+            WindowMessage::EditScriptEnvFilePathType(config_script_id, value) => {
+                apply_config_script_edit(self, config_script_id, |script| {
+                    script.env_file.path_type = value
+                });
+            }
+            WindowMessage::AddScriptExitCode(config_script_id) => {
+                apply_config_script_edit(self, config_script_id, |script| {
+                    script.exit_codes.push(config::ExitCodeReaction {
+                        exit_code: 1,
+                        result: config::ExitCodeResult::Warning,
+                    });
+                });
+            }
+            WindowMessage::RemoveScriptExitCode(config_script_id, index) => {
+                apply_config_script_edit(self, config_script_id, move |script| {
+                    if index < script.exit_codes.len() {
+                        script.exit_codes.remove(index);
+                    }
+                });
+            }
+            WindowMessage::EditScriptExitCode(config_script_id, index, new_value) => {
+                let new_value = new_value.trim();
+                let exit_code = if new_value.is_empty() {
+                    Some(0)
+                } else {
+                    new_value.parse::<i32>().ok()
+                };
+                if let Some(exit_code) = exit_code {
+                    apply_config_script_edit(self, config_script_id, move |script| {
+                        if let Some(reaction) = script.exit_codes.get_mut(index) {
+                            reaction.exit_code = exit_code;
+                        }
+                    });
+                }
+            }
+            WindowMessage::EditScriptExitCodeResult(config_script_id, index, value) => {
+                apply_config_script_edit(self, config_script_id, move |script| {
+                    if let Some(reaction) = script.exit_codes.get_mut(index) {
+                        reaction.result = value;
+                    }
+                });
+            }
+            WindowMessage::AddScriptRunCondition(config_script_id) => {
+                apply_config_script_edit(self, config_script_id, |script| {
+                    script.run_conditions.push(config::RunCondition {
+                        condition_type: config::RunConditionType::ScriptSucceeded,
+                        value: String::new(),
+                    });
+                });
+            }
+            WindowMessage::RemoveScriptRunCondition(config_script_id, index) => {
+                apply_config_script_edit(self, config_script_id, move |script| {
+                    if index < script.run_conditions.len() {
+                        script.run_conditions.remove(index);
+                    }
+                });
+            }
+            WindowMessage::EditScriptRunConditionType(config_script_id, index, value) => {
+                apply_config_script_edit(self, config_script_id, move |script| {
+                    if let Some(run_condition) = script.run_conditions.get_mut(index) {
+                        run_condition.condition_type = value;
+                    }
+                });
+            }
+            WindowMessage::EditScriptRunConditionValue(config_script_id, index, new_value) => {
+                apply_config_script_edit(self, config_script_id, move |script| {
+                    if let Some(run_condition) = script.run_conditions.get_mut(index) {
+                        run_condition.value = new_value;
+                    }
+                });
+            }
+            WindowMessage::EditScriptLogPatternRules(config_script_id, edit) => {
+                apply_config_script_edit(self, config_script_id, move |script| {
+                    apply_log_pattern_rule_edit(&mut script.log_pattern_rules, edit);
+                });
+            }
             WindowMessage::EditScriptIconPath(config_script_id, new_icon_path) => {
                 if let Some(preset) = get_editing_preset(&mut self.app_config, config_script_id) {
                     preset.icon.path = new_icon_path;
@@ -1130,6 +1441,163 @@ impl MainWindow {
                     });
                 }
             }
+            WindowMessage::AddPresetItemToPreset(config_script_id, item_uid) => {
+                apply_config_preset_edit(self, config_script_id, move |preset| {
+                    preset.items.push(config::PresetItem {
+                        uid: item_uid,
+                        name: None,
+                        arguments_line: None,
+                        executor_arguments: None,
+                        overridden_placeholder_values: HashMap::new(),
+                        autorerun_count: None,
+                        autorerun_delay_sec: None,
+                        timeout_sec: None,
+                        reaction_to_previous_failures: None,
+                        autoclean_on_success: None,
+                        env_vars: Vec::new(),
+                        exit_codes: Vec::new(),
+                        run_conditions: Vec::new(),
+                        dependencies: None,
+                    });
+                });
+            }
+            WindowMessage::RemovePresetItemFromPreset(config_script_id, item_idx) => {
+                apply_config_preset_edit(self, config_script_id, move |preset| {
+                    if item_idx >= preset.items.len() {
+                        return;
+                    }
+                    preset.items.remove(item_idx);
+                    // keep the dependencies of other items pointing to the same items
+                    for item in &mut preset.items {
+                        if let Some(dependencies) = &mut item.dependencies {
+                            if dependencies.as_slice() == [item_idx] {
+                                item.dependencies = None;
+                                continue;
+                            }
+                            dependencies.retain(|idx| *idx != item_idx);
+                            for idx in dependencies.iter_mut() {
+                                if *idx > item_idx {
+                                    *idx -= 1;
+                                }
+                            }
+                        }
+                    }
+                });
+            }
+            WindowMessage::AddScriptSchedule(script_uid) => {
+                config::get_main_rewritable_config_mut(&mut self.app_config)
+                    .schedules
+                    .push(config::ScriptSchedule {
+                        script_uid,
+                        trigger: config::ScheduleTrigger::Cron {
+                            expression: "0 18 * * mon-fri".to_string(),
+                        },
+                        missed_run_policy: config::MissedRunPolicy::Skip,
+                    });
+                self.edit_data.is_dirty = true;
+            }
+            WindowMessage::RemoveScriptSchedule(schedule_idx) => {
+                let schedules =
+                    &mut config::get_main_rewritable_config_mut(&mut self.app_config).schedules;
+                if schedule_idx < schedules.len() {
+                    schedules.remove(schedule_idx);
+                    self.edit_data.is_dirty = true;
+                }
+            }
+            WindowMessage::EditScheduleTriggerKind(schedule_idx, trigger_kind) => {
+                apply_schedule_edit(self, schedule_idx, |schedule| {
+                    schedule.trigger = match trigger_kind {
+                        ScheduleTriggerKind::Interval => config::ScheduleTrigger::Interval {
+                            interval_sec: 60 * 60,
+                        },
+                        ScheduleTriggerKind::Cron => config::ScheduleTrigger::Cron {
+                            expression: "0 18 * * mon-fri".to_string(),
+                        },
+                    };
+                });
+            }
+            WindowMessage::EditScheduleInterval(schedule_idx, new_value) => {
+                let new_interval_sec = if new_value.is_empty() {
+                    Some(0)
+                } else {
+                    new_value.parse::<u64>().ok()
+                };
+                if let Some(interval_sec) = new_interval_sec {
+                    apply_schedule_edit(self, schedule_idx, |schedule| {
+                        schedule.trigger = config::ScheduleTrigger::Interval { interval_sec };
+                    });
+                }
+            }
+            WindowMessage::EditScheduleCronExpression(schedule_idx, expression) => {
+                apply_schedule_edit(self, schedule_idx, |schedule| {
+                    schedule.trigger = config::ScheduleTrigger::Cron { expression };
+                });
+            }
+            WindowMessage::EditScheduleMissedRunPolicy(schedule_idx, missed_run_policy) => {
+                apply_schedule_edit(self, schedule_idx, |schedule| {
+                    schedule.missed_run_policy = missed_run_policy;
+                });
+            }
+            WindowMessage::AddScriptFileWatch(script_uid) => {
+                config::get_main_rewritable_config_mut(&mut self.app_config)
+                    .file_watches
+                    .push(config::ScriptFileWatch {
+                        script_uid,
+                        paths: Vec::new(),
+                        ignored_paths: Vec::new(),
+                        debounce_ms: 500,
+                        running_script_policy: config::FileWatchRunningScriptPolicy::Restart,
+                    });
+                self.edit_data.is_dirty = true;
+            }
+            WindowMessage::RemoveScriptFileWatch(file_watch_idx) => {
+                let file_watches =
+                    &mut config::get_main_rewritable_config_mut(&mut self.app_config).file_watches;
+                if file_watch_idx < file_watches.len() {
+                    file_watches.remove(file_watch_idx);
+                    self.edit_data.is_dirty = true;
+                }
+            }
+            WindowMessage::EditFileWatchPath(file_watch_idx, new_path, index) => {
+                apply_file_watch_edit(self, file_watch_idx, |file_watch| {
+                    if new_path.is_empty() && index + 1 == file_watch.paths.len() {
+                        file_watch.paths.pop();
+                    } else if !new_path.is_empty() && index == file_watch.paths.len() {
+                        file_watch.paths.push(new_path);
+                    } else if index < file_watch.paths.len() {
+                        file_watch.paths[index] = new_path;
+                    }
+                });
+            }
+            WindowMessage::EditFileWatchIgnoredPath(file_watch_idx, new_path, index) => {
+                apply_file_watch_edit(self, file_watch_idx, |file_watch| {
+                    let ignored_paths = &mut file_watch.ignored_paths;
+                    if new_path.is_empty() && index + 1 == ignored_paths.len() {
+                        ignored_paths.pop();
+                    } else if !new_path.is_empty() && index == ignored_paths.len() {
+                        ignored_paths.push(new_path);
+                    } else if index < ignored_paths.len() {
+                        ignored_paths[index] = new_path;
+                    }
+                });
+            }
+            WindowMessage::EditFileWatchDebounce(file_watch_idx, new_value) => {
+                let new_debounce_ms = if new_value.is_empty() {
+                    Some(0)
+                } else {
+                    new_value.parse::<u64>().ok()
+                };
+                if let Some(debounce_ms) = new_debounce_ms {
+                    apply_file_watch_edit(self, file_watch_idx, |file_watch| {
+                        file_watch.debounce_ms = debounce_ms;
+                    });
+                }
+            }
+            WindowMessage::EditFileWatchRunningScriptPolicy(file_watch_idx, policy) => {
+                apply_file_watch_edit(self, file_watch_idx, |file_watch| {
+                    file_watch.running_script_policy = policy;
+                });
+            }
             WindowMessage::EditArgumentsLineForConfig(config_script_id, new_arguments) => {
                 apply_config_script_edit(self, config_script_id, move |script| {
                     script.arguments_line = new_arguments;
@@ -1286,6 +1754,28 @@ impl MainWindow {
                     }
                 }
             }
+            WindowMessage::EditTimeoutSecForConfig(config_script_id, new_timeout_sec_str) => {
+                let new_timeout_sec = update_timeout_sec_text(self, new_timeout_sec_str);
+
+                if let Some(new_timeout_sec) = new_timeout_sec {
+                    apply_config_script_edit(self, config_script_id, |script| {
+                        script.timeout_sec = new_timeout_sec
+                    });
+                }
+            }
+            WindowMessage::EditTimeoutSecForExecutionList(new_timeout_sec_str) => {
+                let new_timeout_sec = update_timeout_sec_text(self, new_timeout_sec_str);
+
+                if let Some(new_timeout_sec) = new_timeout_sec {
+                    if let Some((idx, _)) =
+                        get_only_selected_script(&self.window_state.selected_scripts)
+                    {
+                        apply_execution_script_edit(self, idx, |script| {
+                            script.timeout_sec = new_timeout_sec;
+                        });
+                    }
+                }
+            }
             WindowMessage::EditReactionToPreviousFailuresForConfig(config_script_id, value) => {
                 apply_config_script_edit(self, config_script_id, |script| {
                     script.reaction_to_previous_failures = value
@@ -1300,6 +1790,49 @@ impl MainWindow {
                     });
                 }
             }
+            WindowMessage::ToggleWaitForPreviousScriptsForExecutionList(value) => {
+                if let Some((idx, _)) =
+                    get_only_selected_script(&self.window_state.selected_scripts)
+                {
+                    let edited_scripts = self.execution_manager.get_edited_scripts_mut();
+                    let new_dependencies = if value {
+                        None
+                    } else {
+                        // start from what the script waits for now, so nothing changes until edited
+                        let dependencies = execution_thread::resolve_dependencies(edited_scripts);
+                        dependencies.get(idx).map(|dependencies| {
+                            dependencies
+                                .iter()
+                                .map(|dependency_idx| edited_scripts[*dependency_idx].uid.clone())
+                                .collect()
+                        })
+                    };
+                    if let Some(script) = edited_scripts.get_mut(idx) {
+                        script.dependencies = new_dependencies;
+                    }
+                }
+            }
+            WindowMessage::ToggleScriptDependencyForExecutionList(dependency_idx, value) => {
+                if let Some((idx, _)) =
+                    get_only_selected_script(&self.window_state.selected_scripts)
+                {
+                    let edited_scripts = self.execution_manager.get_edited_scripts_mut();
+                    if let Some(dependency_uid) = edited_scripts
+                        .get(dependency_idx)
+                        .map(|dependency| dependency.uid.clone())
+                    {
+                        if let Some(dependencies) = edited_scripts
+                            .get_mut(idx)
+                            .and_then(|script| script.dependencies.as_mut())
+                        {
+                            dependencies.retain(|uid| *uid != dependency_uid);
+                            if value {
+                                dependencies.push(dependency_uid);
+                            }
+                        }
+                    }
+                }
+            }
             WindowMessage::ToggleUseCustomExecutor(config_script_id, should_use_custom) => {
                 apply_config_script_edit(self, config_script_id, |script| {
                     if script.custom_executor.is_none() && should_use_custom {
@@ -1360,6 +1893,16 @@ impl MainWindow {
                     script.ignore_output = value
                 });
             }
+            WindowMessage::ToggleKeepChildProcesses(config_script_id, value) => {
+                apply_config_script_edit(self, config_script_id, |script| {
+                    script.keep_child_processes = value
+                });
+            }
+            WindowMessage::ToggleAcceptInput(config_script_id, value) => {
+                apply_config_script_edit(self, config_script_id, |script| {
+                    script.accept_input = value
+                });
+            }
             WindowMessage::ToggleIsHidden(config_script_id, value) => {
                 apply_config_script_edit(self, config_script_id, |script| script.is_hidden = value);
             }
@@ -1437,6 +1980,124 @@ impl MainWindow {
                     .show_working_directory = is_checked;
                 self.edit_data.is_dirty = true;
             }
+            WindowMessage::SettingsToggleKeepEscapeSequencesInLogs(edit_mode, is_checked) => {
+                config::get_rewritable_config_mut(&mut self.app_config, edit_mode)
+                    .keep_escape_sequences_in_logs = is_checked;
+                self.edit_data.is_dirty = true;
+            }
+            WindowMessage::SettingsLogFileFormatChanged(edit_mode, value) => {
+                config::get_rewritable_config_mut(&mut self.app_config, edit_mode)
+                    .log_file_format = value;
+                self.edit_data.is_dirty = true;
+            }
+            WindowMessage::SettingsEditLogPatternRules(edit_mode, edit) => {
+                apply_log_pattern_rule_edit(
+                    &mut config::get_rewritable_config_mut(&mut self.app_config, edit_mode)
+                        .log_pattern_rules,
+                    edit,
+                );
+                self.edit_data.is_dirty = true;
+            }
+            WindowMessage::SettingsEditEditorCommand(edit_mode, new_value) => {
+                config::get_rewritable_config_mut(&mut self.app_config, edit_mode).editor_command =
+                    new_value;
+                self.edit_data.is_dirty = true;
+            }
+            WindowMessage::SettingsToggleSingleInstance(edit_mode, is_checked) => {
+                config::get_rewritable_config_mut(&mut self.app_config, edit_mode)
+                    .single_instance = is_checked;
+                self.edit_data.is_dirty = true;
+            }
+            WindowMessage::SettingsToggleEnableControlSocket(edit_mode, is_checked) => {
+                config::get_rewritable_config_mut(&mut self.app_config, edit_mode)
+                    .enable_control_socket = is_checked;
+                self.edit_data.is_dirty = true;
+            }
+            WindowMessage::SettingsEditParallelScriptsLimit(edit_mode, new_value) => {
+                let new_limit = if new_value.is_empty() {
+                    Some(0)
+                } else {
+                    new_value.parse::<usize>().ok()
+                };
+                if let Some(new_limit) = new_limit {
+                    config::get_rewritable_config_mut(&mut self.app_config, edit_mode)
+                        .parallel_scripts_limit = new_limit;
+                    self.edit_data.is_dirty = true;
+                }
+            }
+            WindowMessage::SettingsEditSlowScriptThreshold(edit_mode, new_value) => {
+                let new_threshold = if new_value.is_empty() {
+                    Some(0)
+                } else {
+                    new_value.parse::<u32>().ok()
+                };
+                if let Some(new_threshold) = new_threshold {
+                    config::get_rewritable_config_mut(&mut self.app_config, edit_mode)
+                        .slow_script_threshold_percent = new_threshold;
+                    self.edit_data.is_dirty = true;
+                }
+            }
+            WindowMessage::SettingsEditLogRetentionMaxAgeDays(edit_mode, new_value) => {
+                if let Some(new_value) = parse_optional_limit(&new_value) {
+                    config::get_rewritable_config_mut(&mut self.app_config, edit_mode)
+                        .log_retention
+                        .max_age_days = new_value;
+                    self.edit_data.is_dirty = true;
+                }
+            }
+            WindowMessage::SettingsEditLogRetentionMaxTotalSizeMb(edit_mode, new_value) => {
+                if let Some(new_value) = parse_optional_limit(&new_value) {
+                    config::get_rewritable_config_mut(&mut self.app_config, edit_mode)
+                        .log_retention
+                        .max_total_size_mb = new_value;
+                    self.edit_data.is_dirty = true;
+                }
+            }
+            WindowMessage::SettingsEditLogRetentionMaxRuns(edit_mode, new_value) => {
+                if let Some(new_value) = parse_optional_limit(&new_value) {
+                    config::get_rewritable_config_mut(&mut self.app_config, edit_mode)
+                        .log_retention
+                        .max_runs = new_value;
+                    self.edit_data.is_dirty = true;
+                }
+            }
+            WindowMessage::SettingsToggleKeepFailedRunsLonger(edit_mode, is_checked) => {
+                config::get_rewritable_config_mut(&mut self.app_config, edit_mode)
+                    .log_retention
+                    .keep_failed_runs_longer = is_checked;
+                self.edit_data.is_dirty = true;
+            }
+            WindowMessage::SettingsLogCompressionModeChanged(edit_mode, value) => {
+                config::get_rewritable_config_mut(&mut self.app_config, edit_mode)
+                    .log_compression
+                    .mode = value;
+                self.edit_data.is_dirty = true;
+            }
+            WindowMessage::SettingsEditLogCompressionAfterDays(edit_mode, new_value) => {
+                if let Ok(new_value) = new_value.parse::<u32>() {
+                    config::get_rewritable_config_mut(&mut self.app_config, edit_mode)
+                        .log_compression
+                        .after_days = new_value;
+                    self.edit_data.is_dirty = true;
+                }
+            }
+            WindowMessage::SettingsExecutionReportFormatChanged(edit_mode, value) => {
+                config::get_rewritable_config_mut(&mut self.app_config, edit_mode)
+                    .execution_report_format = value;
+                self.edit_data.is_dirty = true;
+            }
+            WindowMessage::SettingsEditExecutionReportFolder(edit_mode, new_path) => {
+                config::get_rewritable_config_mut(&mut self.app_config, edit_mode)
+                    .execution_report_folder
+                    .path = new_path;
+                self.edit_data.is_dirty = true;
+            }
+            WindowMessage::SettingsEditExecutionReportFolderPathType(edit_mode, new_path_type) => {
+                config::get_rewritable_config_mut(&mut self.app_config, edit_mode)
+                    .execution_report_folder
+                    .path_type = new_path_type;
+                self.edit_data.is_dirty = true;
+            }
             WindowMessage::SettingsToggleTitleEditing(edit_mode, is_checked) => {
                 config::get_rewritable_config_mut(&mut self.app_config, edit_mode)
                     .enable_title_editing = is_checked;
@@ -1928,7 +2589,9 @@ impl MainWindow {
                                         &self.app_config,
                                         script.original_script_uid.clone(),
                                     );
-                                    start_new_execution_from_provided_scripts(self, scripts);
+                                    start_new_execution_from_provided_execution_scripts(
+                                        self, scripts,
+                                    );
                                 } else {
                                     try_add_script_to_execution_or_start_new(
                                         self,
@@ -2009,6 +2672,18 @@ impl MainWindow {
                     eprintln!("Failed to open file with default application: {}", e);
                 }
             }
+            WindowMessage::OpenFileReference(file_path, line, column) => {
+                let editor_command =
+                    &config::get_main_rewritable_config(&self.app_config).editor_command;
+                if editor_command.trim().is_empty() {
+                    return self.update(WindowMessage::OpenWithDefaultApplication(file_path));
+                }
+
+                if let Err(e) = open_in_editor(editor_command, &file_path, line, column) {
+                    eprintln!("Failed to open the file with the editor command: {}", e);
+                    return self.update(WindowMessage::OpenWithDefaultApplication(file_path));
+                }
+            }
             WindowMessage::OpenUrl(url) => {
                 if let Err(e) = open::that(url) {
                     eprintln!("Failed to open URL: {}", e);
@@ -2020,7 +2695,9 @@ impl MainWindow {
                     .get_started_executions()
                     .get(execution_id)
                 {
-                    open::that_in_background(execution.get_log_folder_path());
+                    open::that_in_background(log_archive::get_readable_path(
+                        execution.get_log_folder_path(),
+                    ));
                 }
             }
             WindowMessage::OpenFirstFailedScriptLog(execution_id) => {
@@ -2030,22 +2707,141 @@ impl MainWindow {
                     .get(execution_id)
                 {
                     if let Some(log_path) = execution.get_first_failed_script_log_path() {
-                        open::that_in_background(&log_path);
+                        open::that_in_background(log_archive::get_readable_path(&log_path));
                     }
                 }
             }
-            WindowMessage::OpenLogRootFolder => {
-                match std::fs::exists(&self.app_config.paths.logs_path) {
-                    Ok(true) => {}
-                    _ => {
-                        let _ = std::fs::create_dir_all(&self.app_config.paths.logs_path);
-                    }
+            WindowMessage::SelectHistoryRecord(log_directory) => {
+                if self.visual_caches.selected_history_record.as_ref() == Some(&log_directory) {
+                    self.visual_caches.selected_history_record = None;
+                } else {
+                    self.visual_caches.selected_history_record = Some(log_directory);
                 }
-                open::that_in_background(&self.app_config.paths.logs_path);
             }
-            WindowMessage::ProcessKeyPress(iced_key, iced_modifiers) => {
-                self.window_state.is_command_key_down = iced_modifiers.command();
-                self.window_state.is_alt_key_down = iced_modifiers.alt();
+            WindowMessage::OpenHistoryLog(path) => {
+                open::that_in_background(log_archive::get_readable_path(&path));
+            }
+            WindowMessage::RescheduleFromHistory(log_directory) => {
+                let Some(record) = self
+                    .execution_history
+                    .iter()
+                    .find(|record| record.log_directory == log_directory)
+                else {
+                    return Task::none();
+                };
+
+                let mut scripts: Vec<execution_thread::ExecutionScript> = record
+                    .scripts
+                    .iter()
+                    .map(|script| {
+                        execution_thread::ExecutionScript::from_original(script.script.clone())
+                    })
+                    .collect();
+                let dependency_indexes: Vec<Option<Vec<usize>>> = record
+                    .scripts
+                    .iter()
+                    .map(|script| script.dependencies.clone())
+                    .collect();
+                execution_thread::set_dependencies_from_indexes(&mut scripts, &dependency_indexes);
+                self.execution_manager.add_scripts_to_edited_list(scripts);
+                update_edited_execution_list_script_number(self);
+                update_drag_and_drop_area_bounds(self);
+                return scrollable::snap_to(EXECUTIONS_PANE_SCROLL_ID.clone(), RelativeOffset::END);
+            }
+            WindowMessage::OpenLogViewer(execution_id) => {
+                if let Some(execution) = self
+                    .execution_manager
+                    .get_started_executions()
+                    .get(execution_id)
+                {
+                    self.visual_caches.log_viewer = Some(log_viewer::LogViewer::new(
+                        execution.get_name().clone(),
+                        Some(execution_id),
+                        log_viewer::get_execution_log_sources(execution),
+                    ));
+                }
+            }
+            WindowMessage::OpenHistoryLogViewer(log_directory) => {
+                if let Some(record) = self
+                    .execution_history
+                    .iter()
+                    .find(|record| record.log_directory == log_directory)
+                {
+                    self.visual_caches.log_viewer = Some(log_viewer::LogViewer::new(
+                        record.start_time.clone(),
+                        None,
+                        log_viewer::get_history_log_sources(record),
+                    ));
+                }
+            }
+            WindowMessage::CloseLogViewer => {
+                self.visual_caches.log_viewer = None;
+            }
+            WindowMessage::LogViewerScroll(line_count) => {
+                if let Some(log_viewer) = &mut self.visual_caches.log_viewer {
+                    log_viewer.scroll_by(line_count);
+                }
+            }
+            WindowMessage::LogViewerScrollTo(slider_value) => {
+                if let Some(log_viewer) = &mut self.visual_caches.log_viewer {
+                    // the slider has its maximum at the top
+                    let line = log_viewer
+                        .get_max_first_line()
+                        .saturating_sub(slider_value as usize);
+                    log_viewer.scroll_to(line);
+                }
+            }
+            WindowMessage::LogViewerToggleFilter(filter, is_enabled) => {
+                if let Some(log_viewer) = &mut self.visual_caches.log_viewer {
+                    log_viewer.set_filter(filter, is_enabled);
+                }
+            }
+            WindowMessage::LogViewerSearchTextChanged(search_text) => {
+                if let Some(log_viewer) = &mut self.visual_caches.log_viewer {
+                    log_viewer.set_search_text(search_text);
+                }
+            }
+            WindowMessage::LogViewerFind(is_forward) => {
+                if let Some(log_viewer) = &mut self.visual_caches.log_viewer {
+                    log_viewer.find(is_forward);
+                }
+            }
+            WindowMessage::LogViewerJumpToScript(is_forward) => {
+                if let Some(log_viewer) = &mut self.visual_caches.log_viewer {
+                    log_viewer.jump_to_script(is_forward);
+                }
+            }
+            WindowMessage::EditScriptInput(script_input) => {
+                self.visual_caches.script_input = script_input;
+            }
+            WindowMessage::ToggleScriptInputSecret(is_secret) => {
+                self.visual_caches.is_script_input_secret = is_secret;
+            }
+            WindowMessage::SendScriptInput(execution_id) => {
+                if let Some(execution) = self
+                    .execution_manager
+                    .get_started_executions()
+                    .get(execution_id)
+                {
+                    execution.send_input_to_running_script(execution_thread::ScriptInput {
+                        text: std::mem::take(&mut self.visual_caches.script_input),
+                        is_secret: self.visual_caches.is_script_input_secret,
+                        is_end_of_input: false,
+                    });
+                }
+            }
+            WindowMessage::OpenLogRootFolder => {
+                match std::fs::exists(&self.app_config.paths.logs_path) {
+                    Ok(true) => {}
+                    _ => {
+                        let _ = std::fs::create_dir_all(&self.app_config.paths.logs_path);
+                    }
+                }
+                open::that_in_background(&self.app_config.paths.logs_path);
+            }
+            WindowMessage::ProcessKeyPress(iced_key, iced_modifiers) => {
+                self.window_state.is_command_key_down = iced_modifiers.command();
+                self.window_state.is_alt_key_down = iced_modifiers.alt();
                 self.window_state.is_shift_key_down = iced_modifiers.shift();
 
                 if keybind_editing::process_key_press(self, iced_key.clone(), iced_modifiers) {
@@ -2110,7 +2906,7 @@ impl MainWindow {
                 if !self.edit_data.window_edit_data.is_some() {
                     let scripts_to_execute =
                         get_resulting_scripts_from_guid(&self.app_config, script_uid);
-                    start_new_execution_from_provided_scripts(self, scripts_to_execute);
+                    start_new_execution_from_provided_execution_scripts(self, scripts_to_execute);
                 }
             }
             WindowMessage::AddToQuickLaunchPanel(script_uid) => {
@@ -2185,15 +2981,17 @@ impl MainWindow {
 
                 pane_grid::Content::new(responsive(move |_size| {
                     view_content(
-                        &self.execution_manager,
                         variant,
-                        &self.theme,
-                        &self.displayed_configs_list_cache,
-                        &self.app_config.paths,
-                        &self.visual_caches,
-                        &self.app_config,
-                        &self.edit_data,
-                        &self.window_state,
+                        &PaneContentSources {
+                            execution_lists: &self.execution_manager,
+                            theme: &self.theme,
+                            displayed_configs_list_cache: &self.displayed_configs_list_cache,
+                            visual_caches: &self.visual_caches,
+                            config: &self.app_config,
+                            edit_data: &self.edit_data,
+                            window_state: &self.window_state,
+                            execution_history: &self.execution_history,
+                        },
                     )
                 }))
                 .title_bar(title_bar)
@@ -2319,6 +3117,7 @@ pub(crate) enum PaneVariant {
     ExecutionList,
     LogOutput,
     Parameters,
+    History,
 }
 
 pub(crate) struct AppPane {
@@ -2407,12 +3206,24 @@ fn produce_script_list_content<'a>(
 
                 let is_dragged = dragged_element_index == Some(i);
 
+                let next_run_hint = match visual_caches
+                    .next_scheduled_runs
+                    .get(&script.original_script_uid)
+                {
+                    Some(next_run_time) => row![text(format_next_run_time(*next_run_time))
+                        .size(12)
+                        .height(22)
+                        .align_y(alignment::Vertical::Center)],
+                    None => row![],
+                };
+
                 let item_button = button(
                     row![
                         icon,
                         Space::with_width(6),
                         text(script.name.clone()).height(22),
-                        horizontal_space()
+                        horizontal_space(),
+                        next_run_hint,
                     ]
                     .height(22),
                 )
@@ -2816,9 +3627,9 @@ fn produce_execution_list_content<'a>(
             };
 
             let status;
-            let status_tooltip: &'static str;
+            let status_tooltip: String;
             let progress;
-            let color = if script_status.has_script_failed() {
+            let mut color = if script_status.has_script_failed() {
                 if let Some(custom_theme) = &visual_caches.custom_theme {
                     iced::Color::from_rgb(
                         custom_theme.error_text[0],
@@ -2836,17 +3647,32 @@ fn produce_execution_list_content<'a>(
                 status = match script_status.result {
                     execution_thread::ScriptResultStatus::Failed => image(icons.failed.clone()),
                     execution_thread::ScriptResultStatus::Success => image(icons.succeeded.clone()),
+                    execution_thread::ScriptResultStatus::Warning => image(icons.warning.clone()),
                     execution_thread::ScriptResultStatus::Skipped => image(icons.skipped.clone()),
                     execution_thread::ScriptResultStatus::Disconnected => {
                         image(icons.skipped.clone())
                     }
                 };
-                status_tooltip = match script_status.result {
+                let result_name = match script_status.result {
                     execution_thread::ScriptResultStatus::Failed => "Failed",
                     execution_thread::ScriptResultStatus::Success => "Success",
+                    execution_thread::ScriptResultStatus::Warning => "Warning",
                     execution_thread::ScriptResultStatus::Skipped => "Skipped",
                     execution_thread::ScriptResultStatus::Disconnected => "",
                 };
+                let mut tooltip_text = if script_status.exit_statuses.is_empty() {
+                    result_name.to_string()
+                } else {
+                    format!(
+                        "{} ({})",
+                        result_name,
+                        script_status.get_exit_statuses_description()
+                    )
+                };
+                if let Some(resource_usage) = script_status.resource_usages.last() {
+                    tooltip_text.push_str(&format!("\n{}", resource_usage));
+                }
+                status_tooltip = tooltip_text;
                 if script_status.result != execution_thread::ScriptResultStatus::Skipped {
                     let time_taken_sec = script_status
                         .finish_time
@@ -2863,21 +3689,52 @@ fn produce_execution_list_content<'a>(
                     progress = String::new();
                 }
             } else if script_status.has_script_started() {
-                let time_taken_sec = Instant::now()
-                    .duration_since(script_status.start_time.unwrap_or(Instant::now()))
-                    .as_secs();
+                let time_taken = Instant::now()
+                    .duration_since(script_status.start_time.unwrap_or(Instant::now()));
+                let time_taken_sec = time_taken.as_secs();
+                let typical_duration = execution_lists
+                    .get_duration_history()
+                    .get_typical_duration(&record.script.original);
                 status = image(icons.in_progress.clone());
-                status_tooltip = "In progress";
+                let mut tooltip_text = if script_status.exit_statuses.is_empty() {
+                    "In progress".to_string()
+                } else {
+                    format!(
+                        "In progress (previous attempts: {})",
+                        script_status.get_exit_statuses_description()
+                    )
+                };
 
-                progress = format!(
-                    "({:02}:{:02}){}",
-                    time_taken_sec / 60,
-                    time_taken_sec % 60,
-                    repeat_text,
-                );
+                if let Some(typical_duration) = typical_duration {
+                    let typical_duration_sec = typical_duration.as_secs();
+                    progress = format!(
+                        "({:02}:{:02} / ~{:02}:{:02}){}",
+                        time_taken_sec / 60,
+                        time_taken_sec % 60,
+                        typical_duration_sec / 60,
+                        typical_duration_sec % 60,
+                        repeat_text,
+                    );
+
+                    let slow_threshold_percent = rewritable_config.slow_script_threshold_percent;
+                    if slow_threshold_percent > 0
+                        && time_taken > typical_duration * slow_threshold_percent / 100
+                    {
+                        color = SLOW_SCRIPT_TEXT_COLOR;
+                        tooltip_text.push_str("\nRunning longer than usual");
+                    }
+                } else {
+                    progress = format!(
+                        "({:02}:{:02}){}",
+                        time_taken_sec / 60,
+                        time_taken_sec % 60,
+                        repeat_text,
+                    );
+                }
+                status_tooltip = tooltip_text;
             } else {
                 status = image(icons.idle.clone());
-                status_tooltip = "Idle";
+                status_tooltip = "Idle".to_string();
                 progress = String::new();
             };
 
@@ -2886,7 +3743,7 @@ fn produce_execution_list_content<'a>(
             row_data.push(
                 tooltip(
                     status.width(22).height(22).content_fit(ContentFit::None),
-                    status_tooltip,
+                    text(status_tooltip),
                     tooltip::Position::Right,
                 )
                 .style(container::bordered_box)
@@ -2914,8 +3771,52 @@ fn produce_execution_list_content<'a>(
                 .style(container::bordered_box)
                 .into(),
             );
+            // sequential scripts obviously wait for the previous ones, show only the explicit links
+            if record.script.dependencies.is_some() && !record.dependencies.is_empty() {
+                row_data.push(produce_dependencies_hint(
+                    record
+                        .dependencies
+                        .iter()
+                        .filter_map(|dependency_idx| scripts.get(*dependency_idx))
+                        .map(|dependency| dependency.script.original.name.as_str()),
+                    theme,
+                ));
+            }
 
             data_lines.push(row(row_data).height(30).into());
+
+            if !script_status.problems.is_empty() {
+                let (_, error_color) = get_log_text_colors(theme, rewritable_config);
+                for problem in &script_status.problems {
+                    data_lines.push(
+                        row![
+                            Space::with_width(26),
+                            text(problem.text.clone())
+                                .size(13)
+                                .color(get_log_pattern_color(problem.severity, error_color)),
+                        ]
+                        .into(),
+                    );
+                }
+            }
+        }
+
+        if !execution.has_finished_execution() {
+            if let Some(progress_estimate) =
+                execution.get_progress_estimate(execution_lists.get_duration_history())
+            {
+                let time_left_sec = progress_estimate.time_left.as_secs();
+                data_lines.push(
+                    text(format!(
+                        "About {:02}:{:02} left ({}%)",
+                        time_left_sec / 60,
+                        time_left_sec % 60,
+                        (progress_estimate.completed_fraction * 100.0) as u32,
+                    ))
+                    .color(theme.extended_palette().background.strong.text)
+                    .into(),
+                );
+            }
         }
 
         data_lines.push(Space::with_height(8).into());
@@ -3074,9 +3975,11 @@ fn produce_execution_list_content<'a>(
 
     let drop_marker = drop_marker(insert_position_index, ONE_EXECUTION_LIST_ELEMENT_HEIGHT);
 
+    let edited_scripts = execution_lists.get_edited_scripts();
+    let edited_script_dependencies = execution_thread::resolve_dependencies(edited_scripts);
+
     let edited_data: Element<_> = column(
-        execution_lists
-            .get_edited_scripts()
+        edited_scripts
             .iter()
             .enumerate()
             .map(|(i, script)| {
@@ -3110,6 +4013,14 @@ fn produce_execution_list_content<'a>(
                     row_data.push(Space::with_width(4).into());
                 }
                 row_data.push(text(script.original.name.clone()).color(color).into());
+                if script.dependencies.is_some() && !edited_script_dependencies[i].is_empty() {
+                    row_data.push(produce_dependencies_hint(
+                        edited_script_dependencies[i].iter().map(|dependency_idx| {
+                            edited_scripts[*dependency_idx].original.name.as_str()
+                        }),
+                        theme,
+                    ));
+                }
 
                 if is_focused {
                     row_data.push(horizontal_space().into());
@@ -3350,8 +4261,22 @@ fn produce_log_output_content<'a>(
     execution_lists: &parallel_execution_manager::ParallelExecutionManager,
     theme: &Theme,
     main_config: &config::RewritableConfig,
-    visual_caches: &VisualCaches,
+    path_caches: &config::PathCaches,
+    visual_caches: &'a VisualCaches,
 ) -> Column<'a, WindowMessage> {
+    if let Some(log_viewer) = &visual_caches.log_viewer {
+        let running_execution = log_viewer
+            .execution_id
+            .and_then(|execution_id| execution_lists.get_started_executions().get(execution_id));
+        return produce_log_viewer_content(
+            log_viewer,
+            running_execution,
+            theme,
+            main_config,
+            visual_caches,
+        );
+    }
+
     let tabs = if execution_lists.get_started_executions().len() > 1 {
         let tabs = row(execution_lists
             .get_started_executions()
@@ -3387,53 +4312,59 @@ fn produce_log_output_content<'a>(
         let mut data_lines: Vec<Element<'_, WindowMessage, Theme, iced::Renderer>> = Vec::new();
         if let Ok(logs) = selected_execution.get_recent_logs().try_lock() {
             if !logs.is_empty() {
-                let (caption_color, error_color) =
-                    if let Some(custom_theme) = &main_config.custom_theme {
-                        (
-                            iced::Color::from_rgb(
-                                custom_theme.caption_text[0],
-                                custom_theme.caption_text[1],
-                                custom_theme.caption_text[2],
-                            ),
-                            iced::Color::from_rgb(
-                                custom_theme.error_text[0],
-                                custom_theme.error_text[1],
-                                custom_theme.error_text[2],
-                            ),
-                        )
-                    } else {
-                        (
-                            theme.extended_palette().primary.strong.color,
-                            theme.extended_palette().danger.weak.color,
-                        )
-                    };
+                let (caption_color, error_color) = get_log_text_colors(theme, main_config);
 
                 data_lines.extend(logs.iter().map(|element| {
-                    text(format!(
-                        "[{}] {}",
-                        element.timestamp.format("%H:%M:%S"),
-                        element.text
-                    ))
-                    .color(match element.output_type {
-                        execution_thread::OutputType::StdOut => {
+                    let color = match (element.highlight, element.output_type) {
+                        (Some(severity), _) => get_log_pattern_color(severity, error_color),
+                        (None, execution_thread::OutputType::StdOut) => {
                             theme.extended_palette().secondary.base.text
                         }
-                        execution_thread::OutputType::StdErr => error_color,
-                        execution_thread::OutputType::Error => error_color,
-                        execution_thread::OutputType::Event => caption_color,
-                    })
-                    .into()
+                        (None, execution_thread::OutputType::StdErr) => error_color,
+                        (None, execution_thread::OutputType::Error) => error_color,
+                        (None, execution_thread::OutputType::Event) => caption_color,
+                    };
+                    let mut spans =
+                        vec![span(format!("[{}] ", element.timestamp.format("%H:%M:%S")))
+                            .color(color)];
+                    // file references in the output are relative to the script that printed them
+                    let working_directory = element
+                        .script_idx
+                        .and_then(|script_idx| {
+                            selected_execution
+                                .get_scheduled_scripts_cache()
+                                .get(script_idx)
+                        })
+                        .map(|record| {
+                            config::get_full_path(
+                                path_caches,
+                                &record.script.original.working_directory,
+                            )
+                        });
+                    spans.extend(get_styled_log_spans(
+                        &element.text,
+                        color,
+                        working_directory.as_deref(),
+                    ));
+                    rich_text(spans).into()
                 }));
             }
         }
 
         let logs_button = if selected_execution.has_non_skipped_scripts() {
-            column![main_button(
-                "Log directory",
-                Some(WindowMessage::OpenLogFolder(selected_execution.get_id())),
-            )]
+            row![
+                main_button(
+                    "Full log",
+                    Some(WindowMessage::OpenLogViewer(selected_execution.get_id())),
+                ),
+                main_button(
+                    "Log directory",
+                    Some(WindowMessage::OpenLogFolder(selected_execution.get_id())),
+                )
+            ]
+            .spacing(5)
         } else {
-            column![]
+            row![]
         };
 
         let first_failed_log_button = if selected_execution.has_failed_scripts() {
@@ -3449,16 +4380,15 @@ fn produce_log_output_content<'a>(
 
         let data: Element<_> = column(data_lines).spacing(10).width(Length::Fill).into();
 
-        column![
-            tabs,
-            row![logs_button, first_failed_log_button].spacing(5),
-            stack![
+        column![tabs, row![logs_button, first_failed_log_button].spacing(5),]
+            .push_maybe(produce_script_input_row(selected_execution, visual_caches))
+            .push(stack![
+                // under the logs, so the file references in them can still be clicked
+                opaque(row![].width(Length::Fill).height(Length::Fill)),
                 scrollable(data)
                     .style(style::log_scrollable_style)
                     .id(LOGS_SCROLL_ID.clone()),
-                opaque(row![].width(Length::Fill).height(Length::Fill))
-            ]
-        ]
+            ])
     } else {
         column![tabs]
     }
@@ -3468,86 +4398,570 @@ fn produce_log_output_content<'a>(
     .align_x(Alignment::Start)
 }
 
-fn produce_script_edit_content<'a>(
-    execution_lists: &parallel_execution_manager::ParallelExecutionManager,
-    visual_caches: &VisualCaches,
-    edit_data: &EditData,
-    app_config: &config::AppConfig,
-    window_state: &WindowState,
-    theme: &Theme,
-) -> Column<'a, WindowMessage> {
-    let Some((edited_script_idx, edited_script_type)) =
-        get_only_selected_script(&window_state.selected_scripts)
-    else {
-        return Column::new();
-    };
-
-    if edited_script_type == EditScriptType::ScriptConfig {
-        if edit_data.window_edit_data.is_none() {
-            return Column::new();
-        }
+fn produce_script_input_row<'a>(
+    execution: &parallel_execution_manager::Execution,
+    visual_caches: &'a VisualCaches,
+) -> Option<Element<'a, WindowMessage>> {
+    let script = execution.get_running_script_accepting_input()?;
 
-        produce_script_config_edit_content(
-            visual_caches,
-            edit_data,
-            app_config,
-            edited_script_idx,
-            get_script_definition(
-                &app_config,
-                config::get_main_edit_mode(app_config),
-                edited_script_idx,
-            ),
-            theme,
-        )
-    } else {
-        match execution_lists.get_edited_scripts().get(edited_script_idx) {
-            Some(script) => produce_script_to_execute_edit_content(
-                visual_caches,
-                edited_script_idx,
-                &script.original,
+    Some(
+        row![
+            text_input(
+                &format!("Input for \"{}\"", script.script.original.name),
+                &visual_caches.script_input,
+            )
+            .id(SCRIPT_INPUT_ID.clone())
+            .secure(visual_caches.is_script_input_secret)
+            .on_input(WindowMessage::EditScriptInput)
+            .on_submit(WindowMessage::SendScriptInput(execution.get_id()))
+            .padding(5),
+            checkbox("Hide", visual_caches.is_script_input_secret)
+                .on_toggle(WindowMessage::ToggleScriptInputSecret),
+            main_button(
+                "Send",
+                Some(WindowMessage::SendScriptInput(execution.get_id())),
             ),
-            _ => {
-                eprintln!("Could not find script with index {}", edited_script_idx);
-                Column::new()
-            }
-        }
-    }
+        ]
+        .spacing(5)
+        .align_y(Alignment::Center)
+        .into(),
+    )
 }
 
-fn produce_script_config_edit_content<'a>(
-    visual_caches: &VisualCaches,
-    edit_data: &EditData,
-    app_config: &config::AppConfig,
-    edited_script_idx: usize,
-    script: &config::ScriptDefinition,
+fn produce_log_viewer_content<'a>(
+    log_viewer: &'a log_viewer::LogViewer,
+    running_execution: Option<&parallel_execution_manager::Execution>,
     theme: &Theme,
+    main_config: &config::RewritableConfig,
+    visual_caches: &'a VisualCaches,
 ) -> Column<'a, WindowMessage> {
-    let mut parameters: Vec<Element<'_, WindowMessage, Theme, iced::Renderer>> =
-        Vec::with_capacity(40);
+    let (caption_color, error_color) = get_log_text_colors(theme, main_config);
+    let text_color = theme.extended_palette().secondary.base.text;
+    let highlight_color = theme.extended_palette().primary.weak.color;
+
+    let header = row![
+        text(format!("Full log: {}", log_viewer.title)),
+        horizontal_space(),
+        main_button("Close", Some(WindowMessage::CloseLogViewer)),
+    ]
+    .spacing(5)
+    .align_y(Alignment::Center);
+
+    let filters = row![
+        checkbox("stdout", log_viewer.show_stdout).on_toggle(|value| {
+            WindowMessage::LogViewerToggleFilter(log_viewer::LineFilter::StdOut, value)
+        }),
+        checkbox("stderr", log_viewer.show_stderr).on_toggle(|value| {
+            WindowMessage::LogViewerToggleFilter(log_viewer::LineFilter::StdErr, value)
+        }),
+        checkbox("events", log_viewer.show_events).on_toggle(|value| {
+            WindowMessage::LogViewerToggleFilter(log_viewer::LineFilter::Events, value)
+        }),
+        horizontal_space(),
+        main_button(
+            "Previous script",
+            if log_viewer.get_source_count() > 1 {
+                Some(WindowMessage::LogViewerJumpToScript(false))
+            } else {
+                None
+            },
+        ),
+        main_button(
+            "Next script",
+            if log_viewer.get_source_count() > 1 {
+                Some(WindowMessage::LogViewerJumpToScript(true))
+            } else {
+                None
+            },
+        ),
+    ]
+    .spacing(10)
+    .align_y(Alignment::Center);
+
+    let search = row![
+        text_input("Search", &log_viewer.search_text)
+            .on_input(WindowMessage::LogViewerSearchTextChanged)
+            .on_submit(WindowMessage::LogViewerFind(true))
+            .padding(5),
+        main_button("Previous", Some(WindowMessage::LogViewerFind(false))),
+        main_button("Next", Some(WindowMessage::LogViewerFind(true))),
+    ]
+    .push_maybe(if log_viewer.is_searching() {
+        Some(text("Searching..."))
+    } else if log_viewer.has_search_failed {
+        Some(text("No more matches").color(error_color))
+    } else {
+        None
+    })
+    .push_maybe(if log_viewer.is_filtering() {
+        Some(text("Filtering..."))
+    } else {
+        None
+    })
+    .spacing(5)
+    .align_y(Alignment::Center);
 
-    let Some(window_edit_data) = &edit_data.window_edit_data else {
-        return Column::new();
-    };
+    // only the lines that fit the pane are created, so the size of the log doesn't matter
+    let lines = responsive(move |size| {
+        let rendered_line_count = ((size.height / LOG_VIEWER_LINE_HEIGHT) as usize).max(1);
+        log_viewer.rendered_line_count.set(rendered_line_count);
+        let first_visible_line = log_viewer.get_first_visible_line();
 
-    let config_script_id = ConfigScriptId {
-        idx: edited_script_idx,
-        edit_mode: config::get_main_edit_mode(&app_config),
-    };
+        let data_lines = log_viewer
+            .cached_lines
+            .iter()
+            .filter(|line| line.position >= first_visible_line)
+            .take(rendered_line_count)
+            .map(|line| {
+                let line_text: String = line
+                    .text
+                    .chars()
+                    .take(LOG_VIEWER_MAX_DISPLAYED_LINE_LENGTH)
+                    .collect();
+                let color = match line.output_type {
+                    execution_thread::OutputType::StdOut => text_color,
+                    execution_thread::OutputType::StdErr => error_color,
+                    execution_thread::OutputType::Error => error_color,
+                    execution_thread::OutputType::Event => caption_color,
+                };
+                let mut spans = Vec::new();
+                if let Some(timestamp) = line.timestamp {
+                    spans.push(span(format!("[{}] ", timestamp.format("%H:%M:%S"))).color(color));
+                }
+                spans.extend(get_styled_log_spans(&line_text, color, None));
+                let line_text = rich_text(spans)
+                    .size(14)
+                    .line_height(LineHeight::Absolute(iced::Pixels(LOG_VIEWER_LINE_HEIGHT)))
+                    .wrapping(Wrapping::None);
+
+                let line_container = container(line_text).width(Length::Fill);
+                if log_viewer.search_result == Some(line.position) {
+                    line_container
+                        .style(move |_theme| container::Style {
+                            background: Some(highlight_color.into()),
+                            ..Default::default()
+                        })
+                        .into()
+                } else {
+                    line_container.into()
+                }
+            })
+            .collect::<Vec<Element<'_, WindowMessage, Theme, iced::Renderer>>>();
+
+        let max_first_line = log_viewer.get_max_first_line();
+        let scrollbar: Element<'_, WindowMessage, Theme, iced::Renderer> = if max_first_line > 0 {
+            vertical_slider(
+                0.0..=max_first_line as f64,
+                max_first_line.saturating_sub(first_visible_line) as f64,
+                WindowMessage::LogViewerScrollTo,
+            )
+            .height(Length::Fill)
+            .into()
+        } else {
+            Space::with_width(0).into()
+        };
 
-    match script {
-        config::ScriptDefinition::Original(script) => {
-            populate_original_script_config_edit_content(
-                &mut parameters,
-                config_script_id,
-                script,
-                visual_caches,
-                theme,
-            );
+        row![
+            mouse_area(
+                container(column(data_lines))
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .clip(true)
+            )
+            .on_scroll(|delta| {
+                let lines = match delta {
+                    mouse::ScrollDelta::Lines { y, .. } => y * LOG_VIEWER_LINES_PER_WHEEL_STEP,
+                    mouse::ScrollDelta::Pixels { y, .. } => y / LOG_VIEWER_LINE_HEIGHT,
+                };
+                WindowMessage::LogViewerScroll(-lines.round() as isize)
+            }),
+            scrollbar,
+        ]
+        .spacing(5)
+        .into()
+    });
 
-            parameters.push(horizontal_rule(SEPARATOR_HEIGHT).into());
-            keybind_editing::populate_keybind_editing_content(
-                &mut parameters,
-                &window_edit_data,
+    column![header, filters, search]
+        .push_maybe(
+            running_execution
+                .and_then(|execution| produce_script_input_row(execution, visual_caches)),
+        )
+        .push(lines)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .spacing(10)
+        .align_x(Alignment::Start)
+}
+
+// if the working directory is known, file references like "src/main.rs:42" become links
+fn get_styled_log_spans(
+    text: &str,
+    default_color: iced::Color,
+    working_directory: Option<&std::path::Path>,
+) -> Vec<iced::widget::text::Span<'static, WindowMessage>> {
+    ansi_escapes::parse_styled_spans(text)
+        .into_iter()
+        .flat_map(|styled_span| {
+            let Some(working_directory) = working_directory else {
+                return vec![(styled_span, None)];
+            };
+            split_file_references(styled_span, working_directory)
+        })
+        .map(|(styled_span, link)| {
+            let style = styled_span.style;
+            let to_iced_color = |color| {
+                let (r, g, b) = ansi_escapes::get_color_rgb(color);
+                iced::Color::from_rgb8(r, g, b)
+            };
+
+            let is_link = link.is_some();
+            span(styled_span.text)
+                .link_maybe(link)
+                .color(style.foreground.map_or(default_color, to_iced_color))
+                .background_maybe(style.background.map(to_iced_color))
+                .underline(style.is_underlined || is_link)
+                .font(iced::Font {
+                    weight: if style.is_bold {
+                        iced::font::Weight::Bold
+                    } else {
+                        iced::font::Weight::Normal
+                    },
+                    style: if style.is_italic {
+                        iced::font::Style::Italic
+                    } else {
+                        iced::font::Style::Normal
+                    },
+                    ..iced::Font::default()
+                })
+        })
+        .collect()
+}
+
+fn split_file_references(
+    styled_span: ansi_escapes::StyledSpan,
+    working_directory: &std::path::Path,
+) -> Vec<(ansi_escapes::StyledSpan, Option<WindowMessage>)> {
+    let file_references = file_references::find_file_references(&styled_span.text);
+    if file_references.is_empty() {
+        return vec![(styled_span, None)];
+    }
+
+    let style = styled_span.style;
+    let make_span = |text: &str| ansi_escapes::StyledSpan {
+        text: text.to_string(),
+        style,
+    };
+    let mut result = Vec::new();
+    let mut last_end = 0;
+    for file_reference in file_references {
+        if file_reference.range.start > last_end {
+            result.push((
+                make_span(&styled_span.text[last_end..file_reference.range.start]),
+                None,
+            ));
+        }
+        result.push((
+            make_span(&styled_span.text[file_reference.range.clone()]),
+            Some(WindowMessage::OpenFileReference(
+                working_directory.join(&file_reference.path),
+                file_reference.line,
+                file_reference.column,
+            )),
+        ));
+        last_end = file_reference.range.end;
+    }
+    if last_end < styled_span.text.len() {
+        result.push((make_span(&styled_span.text[last_end..]), None));
+    }
+    result
+}
+
+fn get_log_text_colors(
+    theme: &Theme,
+    main_config: &config::RewritableConfig,
+) -> (iced::Color, iced::Color) {
+    if let Some(custom_theme) = &main_config.custom_theme {
+        (
+            iced::Color::from_rgb(
+                custom_theme.caption_text[0],
+                custom_theme.caption_text[1],
+                custom_theme.caption_text[2],
+            ),
+            iced::Color::from_rgb(
+                custom_theme.error_text[0],
+                custom_theme.error_text[1],
+                custom_theme.error_text[2],
+            ),
+        )
+    } else {
+        (
+            theme.extended_palette().primary.strong.color,
+            theme.extended_palette().danger.weak.color,
+        )
+    }
+}
+
+fn get_log_pattern_color(
+    severity: config::LogPatternSeverity,
+    error_color: iced::Color,
+) -> iced::Color {
+    match severity {
+        config::LogPatternSeverity::Error => error_color,
+        config::LogPatternSeverity::Warning => LOG_WARNING_TEXT_COLOR,
+        config::LogPatternSeverity::Info => LOG_INFO_TEXT_COLOR,
+    }
+}
+
+fn produce_history_content<'a>(
+    execution_history: &'a [execution_history::ExecutionHistoryRecord],
+    theme: &Theme,
+    visual_caches: &'a VisualCaches,
+) -> Column<'a, WindowMessage> {
+    let icons = &visual_caches.icons;
+
+    if execution_history.is_empty() {
+        return column![text("Finished executions will appear here")
+            .color(theme.extended_palette().secondary.strong.color)]
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .align_x(Alignment::Center);
+    }
+
+    let mut data_lines: Vec<Element<'_, WindowMessage, Theme, iced::Renderer>> = Vec::new();
+    for record in execution_history.iter().rev() {
+        let is_selected =
+            visual_caches.selected_history_record.as_ref() == Some(&record.log_directory);
+
+        let status = if record.has_failed_scripts {
+            image(icons.failed.clone())
+        } else {
+            image(icons.succeeded.clone())
+        };
+
+        data_lines.push(
+            button(
+                row![
+                    status.width(22).height(22).content_fit(ContentFit::None),
+                    Space::with_width(4),
+                    text(format!(
+                        "{} ({} {})",
+                        record.start_time,
+                        record.scripts.len(),
+                        if record.scripts.len() == 1 {
+                            "script"
+                        } else {
+                            "scripts"
+                        }
+                    )),
+                ]
+                .align_y(Alignment::Center),
+            )
+            .style(if is_selected {
+                button::primary
+            } else {
+                button::text
+            })
+            .width(Length::Fill)
+            .on_press(WindowMessage::SelectHistoryRecord(
+                record.log_directory.clone(),
+            ))
+            .into(),
+        );
+
+        if !is_selected {
+            continue;
+        }
+
+        for script in &record.scripts {
+            let status = match script.result {
+                execution_history::ExecutedScriptResult::Success => image(icons.succeeded.clone()),
+                execution_history::ExecutedScriptResult::Warning => image(icons.warning.clone()),
+                execution_history::ExecutedScriptResult::Failed => image(icons.failed.clone()),
+                execution_history::ExecutedScriptResult::Skipped => image(icons.skipped.clone()),
+                execution_history::ExecutedScriptResult::NotStarted => image(icons.idle.clone()),
+            };
+
+            let progress = match script.duration_sec {
+                Some(duration_sec) => {
+                    let time_taken_sec = duration_sec as u64;
+                    format!(" ({:02}:{:02})", time_taken_sec / 60, time_taken_sec % 60)
+                }
+                None => String::new(),
+            };
+            let repeat_text = if script.retry_count > 0 {
+                format!(
+                    " [{}/{}]",
+                    script.retry_count, script.script.autorerun_count
+                )
+            } else {
+                String::new()
+            };
+
+            let mut row_data: Vec<Element<'_, WindowMessage, Theme, iced::Renderer>> =
+                Vec::with_capacity(10);
+            row_data.push(Space::with_width(16).into());
+            row_data.push(
+                status
+                    .width(22)
+                    .height(22)
+                    .content_fit(ContentFit::None)
+                    .into(),
+            );
+            row_data.push(Space::with_width(4).into());
+            row_data.push(
+                tooltip(
+                    text(format!("{}{}{}", script.script.name, progress, repeat_text)),
+                    text(execution_thread::get_script_to_execute_description(
+                        &script.script,
+                    )),
+                    tooltip::Position::Bottom,
+                )
+                .style(container::bordered_box)
+                .into(),
+            );
+            row_data.push(horizontal_space().into());
+            for (retry_idx, log_path) in script.log_paths.iter().enumerate() {
+                row_data.push(
+                    button(
+                        text(if retry_idx == 0 {
+                            "log".to_string()
+                        } else {
+                            format!("retry {}", retry_idx)
+                        })
+                        .size(14)
+                        .line_height(LineHeight::Absolute(iced::Pixels(14.0))),
+                    )
+                    .style(button::secondary)
+                    .padding(3)
+                    .on_press(WindowMessage::OpenHistoryLog(log_path.clone()))
+                    .into(),
+                );
+            }
+
+            data_lines.push(
+                row(row_data)
+                    .spacing(4)
+                    .height(30)
+                    .align_y(Alignment::Center)
+                    .into(),
+            );
+        }
+
+        data_lines.push(
+            row![
+                main_icon_button(
+                    icons.themed.retry.clone(),
+                    "Reschedule",
+                    Some(WindowMessage::RescheduleFromHistory(
+                        record.log_directory.clone()
+                    )),
+                ),
+                main_icon_button(
+                    icons.themed.log.clone(),
+                    "Log directory",
+                    Some(WindowMessage::OpenHistoryLog(record.log_directory.clone())),
+                ),
+                main_button(
+                    "Full log",
+                    Some(WindowMessage::OpenHistoryLogViewer(
+                        record.log_directory.clone()
+                    )),
+                ),
+            ]
+            .spacing(5)
+            .into(),
+        );
+        data_lines.push(Space::with_height(8).into());
+    }
+
+    column![scrollable(column(data_lines).spacing(4).padding(5))]
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .align_x(Alignment::Start)
+}
+
+fn produce_script_edit_content<'a>(
+    execution_lists: &parallel_execution_manager::ParallelExecutionManager,
+    visual_caches: &VisualCaches,
+    edit_data: &EditData,
+    app_config: &config::AppConfig,
+    window_state: &WindowState,
+    theme: &Theme,
+) -> Column<'a, WindowMessage> {
+    let Some((edited_script_idx, edited_script_type)) =
+        get_only_selected_script(&window_state.selected_scripts)
+    else {
+        return Column::new();
+    };
+
+    if edited_script_type == EditScriptType::ScriptConfig {
+        if edit_data.window_edit_data.is_none() {
+            return Column::new();
+        }
+
+        produce_script_config_edit_content(
+            visual_caches,
+            edit_data,
+            app_config,
+            edited_script_idx,
+            get_script_definition(
+                &app_config,
+                config::get_main_edit_mode(app_config),
+                edited_script_idx,
+            ),
+            theme,
+        )
+    } else {
+        match execution_lists.get_edited_scripts().get(edited_script_idx) {
+            Some(script) => produce_script_to_execute_edit_content(
+                visual_caches,
+                edited_script_idx,
+                script,
+                execution_lists.get_edited_scripts(),
+                theme,
+            ),
+            _ => {
+                eprintln!("Could not find script with index {}", edited_script_idx);
+                Column::new()
+            }
+        }
+    }
+}
+
+fn produce_script_config_edit_content<'a>(
+    visual_caches: &VisualCaches,
+    edit_data: &EditData,
+    app_config: &config::AppConfig,
+    edited_script_idx: usize,
+    script: &config::ScriptDefinition,
+    theme: &Theme,
+) -> Column<'a, WindowMessage> {
+    let mut parameters: Vec<Element<'_, WindowMessage, Theme, iced::Renderer>> =
+        Vec::with_capacity(40);
+
+    let Some(window_edit_data) = &edit_data.window_edit_data else {
+        return Column::new();
+    };
+
+    let config_script_id = ConfigScriptId {
+        idx: edited_script_idx,
+        edit_mode: config::get_main_edit_mode(&app_config),
+    };
+
+    match script {
+        config::ScriptDefinition::Original(script) => {
+            populate_original_script_config_edit_content(
+                &mut parameters,
+                config_script_id,
+                script,
+                visual_caches,
+                theme,
+            );
+
+            parameters.push(horizontal_rule(SEPARATOR_HEIGHT).into());
+            keybind_editing::populate_keybind_editing_content(
+                &mut parameters,
+                &window_edit_data,
                 visual_caches,
                 "Keybind to schedule:",
                 keybind_editing::KeybindAssociatedData::Script(
@@ -3568,6 +4982,20 @@ fn produce_script_config_edit_content<'a>(
                 ),
             );
             populate_quick_launch_edit_button(&mut parameters, &visual_caches, &script.uid);
+            populate_schedule_editing_content(
+                &mut parameters,
+                app_config,
+                visual_caches,
+                &script.uid,
+                theme,
+            );
+            populate_file_watch_editing_content(
+                &mut parameters,
+                app_config,
+                visual_caches,
+                &script.uid,
+                theme,
+            );
 
             parameters.push(horizontal_rule(SEPARATOR_HEIGHT).into());
             parameters.push(
@@ -3631,6 +5059,7 @@ fn produce_script_config_edit_content<'a>(
                         &mut parameters,
                         original_script_id,
                         preset,
+                        app_config,
                     );
                 }
                 _ => {
@@ -3673,6 +5102,20 @@ fn produce_script_config_edit_content<'a>(
             );
 
             populate_quick_launch_edit_button(&mut parameters, &visual_caches, &reference.uid);
+            populate_schedule_editing_content(
+                &mut parameters,
+                app_config,
+                visual_caches,
+                &reference.uid,
+                theme,
+            );
+            populate_file_watch_editing_content(
+                &mut parameters,
+                app_config,
+                visual_caches,
+                &reference.uid,
+                theme,
+            );
 
             parameters.push(horizontal_rule(SEPARATOR_HEIGHT).into());
             parameters.push(
@@ -3701,7 +5144,12 @@ fn produce_script_config_edit_content<'a>(
             );
         }
         config::ScriptDefinition::Preset(preset) => {
-            populate_original_preset_edit_content(&mut parameters, config_script_id, preset);
+            populate_original_preset_edit_content(
+                &mut parameters,
+                config_script_id,
+                preset,
+                app_config,
+            );
 
             if let Some(window_edit_data) = &edit_data.window_edit_data {
                 parameters.push(horizontal_rule(SEPARATOR_HEIGHT).into());
@@ -3729,6 +5177,20 @@ fn produce_script_config_edit_content<'a>(
                 );
 
                 populate_quick_launch_edit_button(&mut parameters, &visual_caches, &preset.uid);
+                populate_schedule_editing_content(
+                    &mut parameters,
+                    app_config,
+                    visual_caches,
+                    &preset.uid,
+                    theme,
+                );
+                populate_file_watch_editing_content(
+                    &mut parameters,
+                    app_config,
+                    visual_caches,
+                    &preset.uid,
+                    theme,
+                );
             }
 
             parameters.push(horizontal_rule(SEPARATOR_HEIGHT).into());
@@ -3775,11 +5237,31 @@ fn produce_script_config_edit_content<'a>(
         .align_x(Alignment::Start)
 }
 
+fn produce_dependencies_hint<'a, 'b>(
+    dependency_names: impl Iterator<Item = &'b str>,
+    theme: &Theme,
+) -> Element<'a, WindowMessage> {
+    row![
+        Space::with_width(6),
+        text(format!(
+            "after {}",
+            dependency_names.collect::<Vec<_>>().join(", ")
+        ))
+        .size(12)
+        .color(theme.extended_palette().background.strong.color),
+    ]
+    .into()
+}
+
 fn produce_script_to_execute_edit_content<'a>(
     visual_caches: &VisualCaches,
     edited_script_idx: usize,
-    script: &config::OriginalScriptDefinition,
+    execution_script: &execution_thread::ExecutionScript,
+    edited_scripts: &[execution_thread::ExecutionScript],
+    theme: &Theme,
 ) -> Column<'a, WindowMessage> {
+    let script = &execution_script.original;
+
     let mut parameters: Vec<Element<'_, WindowMessage, Theme, iced::Renderer>> =
         Vec::with_capacity(20);
 
@@ -3834,6 +5316,13 @@ fn produce_script_to_execute_edit_content<'a>(
             .padding(5)
             .into(),
     );
+    parameters.push(text("Timeout (seconds):").into());
+    parameters.push(
+        text_input("0", &visual_caches.timeout_sec)
+            .on_input(WindowMessage::EditTimeoutSecForExecutionList)
+            .padding(5)
+            .into(),
+    );
 
     parameters.push(horizontal_rule(SEPARATOR_HEIGHT).into());
     parameters.push(text("Reaction to previous failures:").into());
@@ -3846,6 +5335,38 @@ fn produce_script_to_execute_edit_content<'a>(
         .into(),
     );
 
+    parameters.push(horizontal_rule(SEPARATOR_HEIGHT).into());
+    parameters.push(
+        row![
+            checkbox(
+                "Wait for all previous scripts",
+                execution_script.dependencies.is_none()
+            )
+            .on_toggle(WindowMessage::ToggleWaitForPreviousScriptsForExecutionList),
+            Space::with_width(4),
+            help_icon(SCRIPT_DEPENDENCIES_HELP_TEXT, visual_caches, theme),
+        ]
+        .into(),
+    );
+    if let Some(dependencies) = &execution_script.dependencies {
+        parameters.push(text("Wait for:").into());
+        for (dependency_idx, dependency) in edited_scripts.iter().enumerate() {
+            if dependency_idx == edited_script_idx {
+                continue;
+            }
+            parameters.push(
+                checkbox(
+                    dependency.original.name.clone(),
+                    dependencies.contains(&dependency.uid),
+                )
+                .on_toggle(move |val| {
+                    WindowMessage::ToggleScriptDependencyForExecutionList(dependency_idx, val)
+                })
+                .into(),
+            );
+        }
+    }
+
     parameters.push(horizontal_rule(SEPARATOR_HEIGHT).into());
     parameters.push(
         checkbox("Autoclean on success", script.autoclean_on_success)
@@ -3990,6 +5511,25 @@ fn populate_original_script_config_edit_content<'a>(
         config_script_id,
     );
 
+    parameters.push(horizontal_rule(SEPARATOR_HEIGHT).into());
+    parameters.push(
+        row![
+            text("Environment variables:"),
+            Space::with_width(4),
+            help_icon(ENV_VARS_HELP_TEXT, visual_caches, theme),
+        ]
+        .into(),
+    );
+    populate_env_vars_config_content(parameters, &script.env_vars, config_script_id);
+    parameters.push(text("Env file:").into());
+    populate_path_editing_content(
+        "path/to/.env",
+        &script.env_file,
+        parameters,
+        move |path| WindowMessage::EditScriptEnvFilePath(config_script_id, path),
+        move |val| WindowMessage::EditScriptEnvFilePathType(config_script_id, val),
+    );
+
     parameters.push(horizontal_rule(SEPARATOR_HEIGHT).into());
     parameters.push(
         row![
@@ -4023,6 +5563,22 @@ fn populate_original_script_config_edit_content<'a>(
             .padding(5)
             .into(),
     );
+    parameters.push(
+        row![
+            text("Timeout (seconds):"),
+            Space::with_width(4),
+            help_icon(TIMEOUT_HELP_TEXT, visual_caches, theme),
+        ]
+        .into(),
+    );
+    parameters.push(
+        text_input("0", &visual_caches.timeout_sec)
+            .on_input(move |new_value| {
+                WindowMessage::EditTimeoutSecForConfig(config_script_id, new_value)
+            })
+            .padding(5)
+            .into(),
+    );
 
     parameters.push(horizontal_rule(SEPARATOR_HEIGHT).into());
     parameters.push(
@@ -4076,6 +5632,41 @@ fn populate_original_script_config_edit_content<'a>(
         .into(),
     );
 
+    parameters.push(horizontal_rule(SEPARATOR_HEIGHT).into());
+    parameters.push(
+        row![
+            text("Exit codes:"),
+            Space::with_width(4),
+            help_icon(EXIT_CODES_HELP_TEXT, visual_caches, theme),
+        ]
+        .into(),
+    );
+    populate_exit_codes_config_content(parameters, &script.exit_codes, config_script_id);
+
+    parameters.push(horizontal_rule(SEPARATOR_HEIGHT).into());
+    parameters.push(
+        row![
+            text("Run only if:"),
+            Space::with_width(4),
+            help_icon(RUN_CONDITIONS_HELP_TEXT, visual_caches, theme),
+        ]
+        .into(),
+    );
+    populate_run_conditions_config_content(parameters, &script.run_conditions, config_script_id);
+
+    parameters.push(horizontal_rule(SEPARATOR_HEIGHT).into());
+    parameters.push(
+        row![
+            text("Log patterns:"),
+            Space::with_width(4),
+            help_icon(LOG_PATTERNS_HELP_TEXT, visual_caches, theme),
+        ]
+        .into(),
+    );
+    populate_log_pattern_rules_content(parameters, &script.log_pattern_rules, move |edit| {
+        WindowMessage::EditScriptLogPatternRules(config_script_id, edit)
+    });
+
     parameters.push(horizontal_rule(SEPARATOR_HEIGHT).into());
     parameters.push(
         checkbox("Autoclean on success", script.autoclean_on_success)
@@ -4096,6 +5687,29 @@ fn populate_original_script_config_edit_content<'a>(
         .into(),
     );
 
+    parameters.push(horizontal_rule(SEPARATOR_HEIGHT).into());
+    parameters.push(
+        row![
+            checkbox("Keep child processes", script.keep_child_processes).on_toggle(move |val| {
+                WindowMessage::ToggleKeepChildProcesses(config_script_id, val)
+            }),
+            Space::with_width(4),
+            help_icon(KEEP_CHILD_PROCESSES_HELP_TEXT, visual_caches, theme),
+        ]
+        .into(),
+    );
+
+    parameters.push(horizontal_rule(SEPARATOR_HEIGHT).into());
+    parameters.push(
+        row![
+            checkbox("Accept input", script.accept_input)
+                .on_toggle(move |val| WindowMessage::ToggleAcceptInput(config_script_id, val)),
+            Space::with_width(4),
+            help_icon(ACCEPT_INPUT_HELP_TEXT, visual_caches, theme),
+        ]
+        .into(),
+    );
+
     parameters.push(horizontal_rule(SEPARATOR_HEIGHT).into());
     parameters.push(
         checkbox("Is script hidden", script.is_hidden)
@@ -4104,10 +5718,23 @@ fn populate_original_script_config_edit_content<'a>(
     );
 }
 
+#[derive(Debug, Clone, PartialEq)]
+struct PresetPickListItem {
+    uid: config::Guid,
+    name: String,
+}
+
+impl std::fmt::Display for PresetPickListItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
 fn populate_original_preset_edit_content<'a>(
     parameters: &mut Vec<Element<'_, WindowMessage, Theme, iced::Renderer>>,
     config_script_id: ConfigScriptId,
     preset: &config::ScriptPreset,
+    app_config: &config::AppConfig,
 ) {
     parameters.push(text("Preset name:").into());
     parameters.push(
@@ -4117,17 +5744,73 @@ fn populate_original_preset_edit_content<'a>(
             })
             .padding(5)
             .into(),
-    );
+    );
+
+    parameters.push(horizontal_rule(SEPARATOR_HEIGHT).into());
+    parameters.push(text("Path to the icon:").into());
+    populate_path_editing_content(
+        "path/to/icon.png",
+        &preset.icon,
+        parameters,
+        move |path| WindowMessage::EditScriptIconPath(config_script_id, path),
+        move |val| WindowMessage::EditScriptIconPathType(config_script_id, val),
+    );
+
+    parameters.push(horizontal_rule(SEPARATOR_HEIGHT).into());
+    parameters.push(text("Items:").into());
+    for (item_idx, item) in preset.items.iter().enumerate() {
+        let item_name = match config::get_original_script_definition_by_uid(app_config, &item.uid) {
+            Some((config::ScriptDefinition::Original(script), _idx)) => {
+                item.name.clone().unwrap_or_else(|| script.name.clone())
+            }
+            Some((config::ScriptDefinition::Preset(nested_preset), _idx)) => {
+                format!("{} (preset)", nested_preset.name)
+            }
+            _ => "[missing script]".to_string(),
+        };
+        parameters.push(
+            row![
+                text(item_name),
+                horizontal_space(),
+                edit_button(
+                    "Remove",
+                    WindowMessage::RemovePresetItemFromPreset(config_script_id, item_idx),
+                ),
+            ]
+            .align_y(Alignment::Center)
+            .into(),
+        );
+    }
 
-    parameters.push(horizontal_rule(SEPARATOR_HEIGHT).into());
-    parameters.push(text("Path to the icon:").into());
-    populate_path_editing_content(
-        "path/to/icon.png",
-        &preset.icon,
-        parameters,
-        move |path| WindowMessage::EditScriptIconPath(config_script_id, path),
-        move |val| WindowMessage::EditScriptIconPathType(config_script_id, val),
-    );
+    // presets that already contain this preset can't be added, they would never finish expanding
+    let addable_presets: Vec<PresetPickListItem> = app_config
+        .local_config_body
+        .iter()
+        .flat_map(|local_config| local_config.script_definitions.iter())
+        .chain(app_config.script_definitions.iter())
+        .filter_map(|script_definition| match script_definition {
+            config::ScriptDefinition::Preset(other_preset) => Some(other_preset),
+            _ => None,
+        })
+        .filter(|other_preset| {
+            other_preset.uid != preset.uid
+                && !does_preset_contain_script(app_config, &other_preset.uid, &preset.uid)
+        })
+        .map(|other_preset| PresetPickListItem {
+            uid: other_preset.uid.clone(),
+            name: other_preset.name.clone(),
+        })
+        .collect();
+
+    if !addable_presets.is_empty() {
+        parameters.push(
+            pick_list(addable_presets, None::<PresetPickListItem>, move |item| {
+                WindowMessage::AddPresetItemToPreset(config_script_id, item.uid)
+            })
+            .placeholder("Add a preset")
+            .into(),
+        );
+    }
 }
 
 fn produce_settings_edit_content<'a>(
@@ -4221,6 +5904,263 @@ fn produce_settings_edit_content<'a>(
         .into(),
     );
     list_elements.push(horizontal_rule(SEPARATOR_HEIGHT).into());
+    list_elements.push(
+        row![
+            checkbox(
+                "Keep colors in log files",
+                rewritable_config.keep_escape_sequences_in_logs,
+            )
+            .on_toggle(move |val| {
+                WindowMessage::SettingsToggleKeepEscapeSequencesInLogs(edit_mode, val)
+            }),
+            help_icon(KEEP_ESCAPE_SEQUENCES_HELP_TEXT, visual_caches, theme)
+        ]
+        .into(),
+    );
+    list_elements.push(
+        row![
+            text("Log files format:"),
+            Space::with_width(4),
+            pick_list(
+                LOG_FILE_FORMAT_PICK_LIST,
+                Some(rewritable_config.log_file_format),
+                move |selected| WindowMessage::SettingsLogFileFormatChanged(edit_mode, selected),
+            ),
+            Space::with_width(4),
+            help_icon(LOG_FILE_FORMAT_HELP_TEXT, visual_caches, theme),
+        ]
+        .align_y(Alignment::Center)
+        .into(),
+    );
+    list_elements.push(horizontal_rule(SEPARATOR_HEIGHT).into());
+    list_elements.push(
+        row![
+            text("Log patterns for all scripts:"),
+            Space::with_width(4),
+            help_icon(GLOBAL_LOG_PATTERNS_HELP_TEXT, visual_caches, theme),
+        ]
+        .into(),
+    );
+    populate_log_pattern_rules_content(
+        &mut list_elements,
+        &rewritable_config.log_pattern_rules,
+        move |edit| WindowMessage::SettingsEditLogPatternRules(edit_mode, edit),
+    );
+    list_elements.push(horizontal_rule(SEPARATOR_HEIGHT).into());
+    list_elements.push(
+        row![
+            text("Editor command for file links in logs:"),
+            Space::with_width(4),
+            help_icon(EDITOR_COMMAND_HELP_TEXT, visual_caches, theme),
+        ]
+        .into(),
+    );
+    list_elements.push(
+        text_input(
+            "open with the default application",
+            &rewritable_config.editor_command,
+        )
+        .on_input(move |new_value| WindowMessage::SettingsEditEditorCommand(edit_mode, new_value))
+        .padding(5)
+        .into(),
+    );
+    list_elements.push(horizontal_rule(SEPARATOR_HEIGHT).into());
+    list_elements.push(
+        row![
+            checkbox(
+                "Reuse the open window for new launches",
+                rewritable_config.single_instance,
+            )
+            .on_toggle(move |val| WindowMessage::SettingsToggleSingleInstance(edit_mode, val)),
+            help_icon(SINGLE_INSTANCE_HELP_TEXT, visual_caches, theme)
+        ]
+        .into(),
+    );
+    list_elements.push(horizontal_rule(SEPARATOR_HEIGHT).into());
+    list_elements.push(
+        row![
+            checkbox(
+                "Allow control from the command line",
+                rewritable_config.enable_control_socket,
+            )
+            .on_toggle(move |val| WindowMessage::SettingsToggleEnableControlSocket(edit_mode, val)),
+            help_icon(CONTROL_SOCKET_HELP_TEXT, visual_caches, theme)
+        ]
+        .into(),
+    );
+    list_elements.push(horizontal_rule(SEPARATOR_HEIGHT).into());
+    list_elements.push(
+        row![
+            text("Max scripts running at once per execution:"),
+            Space::with_width(4),
+            help_icon(PARALLEL_SCRIPTS_LIMIT_HELP_TEXT, visual_caches, theme),
+        ]
+        .into(),
+    );
+    list_elements.push(
+        text_input(
+            "no limit",
+            &if rewritable_config.parallel_scripts_limit > 0 {
+                rewritable_config.parallel_scripts_limit.to_string()
+            } else {
+                String::new()
+            },
+        )
+        .on_input(move |new_value| {
+            WindowMessage::SettingsEditParallelScriptsLimit(edit_mode, new_value)
+        })
+        .padding(5)
+        .into(),
+    );
+    list_elements.push(horizontal_rule(SEPARATOR_HEIGHT).into());
+    list_elements.push(
+        row![
+            text("Highlight running scripts after % of their usual duration:"),
+            Space::with_width(4),
+            help_icon(SLOW_SCRIPT_THRESHOLD_HELP_TEXT, visual_caches, theme),
+        ]
+        .into(),
+    );
+    list_elements.push(
+        text_input(
+            "disabled",
+            &if rewritable_config.slow_script_threshold_percent > 0 {
+                rewritable_config.slow_script_threshold_percent.to_string()
+            } else {
+                String::new()
+            },
+        )
+        .on_input(move |new_value| {
+            WindowMessage::SettingsEditSlowScriptThreshold(edit_mode, new_value)
+        })
+        .padding(5)
+        .into(),
+    );
+    list_elements.push(horizontal_rule(SEPARATOR_HEIGHT).into());
+    list_elements.push(
+        row![
+            text("Remove old logs:"),
+            Space::with_width(4),
+            help_icon(LOG_RETENTION_HELP_TEXT, visual_caches, theme),
+        ]
+        .into(),
+    );
+    let log_retention = &rewritable_config.log_retention;
+    list_elements.push(
+        row![
+            text("Older than (days):").width(180),
+            text_input(
+                "no limit",
+                &format_optional_limit(log_retention.max_age_days)
+            )
+            .on_input(move |new_value| {
+                WindowMessage::SettingsEditLogRetentionMaxAgeDays(edit_mode, new_value)
+            })
+            .padding(5),
+        ]
+        .align_y(Alignment::Center)
+        .into(),
+    );
+    list_elements.push(
+        row![
+            text("Above total size (MB):").width(180),
+            text_input(
+                "no limit",
+                &format_optional_limit(log_retention.max_total_size_mb)
+            )
+            .on_input(move |new_value| {
+                WindowMessage::SettingsEditLogRetentionMaxTotalSizeMb(edit_mode, new_value)
+            })
+            .padding(5),
+        ]
+        .align_y(Alignment::Center)
+        .into(),
+    );
+    list_elements.push(
+        row![
+            text("Above number of runs:").width(180),
+            text_input("no limit", &format_optional_limit(log_retention.max_runs))
+                .on_input(move |new_value| {
+                    WindowMessage::SettingsEditLogRetentionMaxRuns(edit_mode, new_value)
+                })
+                .padding(5),
+        ]
+        .align_y(Alignment::Center)
+        .into(),
+    );
+    list_elements.push(
+        checkbox(
+            "Keep failed runs longer",
+            log_retention.keep_failed_runs_longer,
+        )
+        .on_toggle(move |val| WindowMessage::SettingsToggleKeepFailedRunsLonger(edit_mode, val))
+        .into(),
+    );
+    list_elements.push(
+        row![
+            pick_list(
+                LOG_COMPRESSION_MODE_PICK_LIST,
+                Some(rewritable_config.log_compression.mode),
+                move |selected| WindowMessage::SettingsLogCompressionModeChanged(
+                    edit_mode, selected
+                ),
+            ),
+            Space::with_width(4),
+            help_icon(LOG_COMPRESSION_HELP_TEXT, visual_caches, theme),
+        ]
+        .align_y(Alignment::Center)
+        .into(),
+    );
+    if rewritable_config.log_compression.mode == config::LogCompressionMode::AfterDays {
+        list_elements.push(
+            row![
+                text("Compress after (days):").width(180),
+                text_input(
+                    "0",
+                    &rewritable_config.log_compression.after_days.to_string()
+                )
+                .on_input(move |new_value| {
+                    WindowMessage::SettingsEditLogCompressionAfterDays(edit_mode, new_value)
+                })
+                .padding(5),
+            ]
+            .align_y(Alignment::Center)
+            .into(),
+        );
+    }
+    if let Some(summary) = visual_caches.log_cleaner.get_last_summary() {
+        list_elements.push(text(summary.to_string()).into());
+    }
+    list_elements.push(horizontal_rule(SEPARATOR_HEIGHT).into());
+    list_elements.push(
+        row![
+            text("Execution report:"),
+            Space::with_width(4),
+            help_icon(EXECUTION_REPORT_HELP_TEXT, visual_caches, theme),
+        ]
+        .into(),
+    );
+    list_elements.push(
+        pick_list(
+            EXECUTION_REPORT_FORMAT_PICK_LIST,
+            Some(rewritable_config.execution_report_format),
+            move |selected| {
+                WindowMessage::SettingsExecutionReportFormatChanged(edit_mode, selected)
+            },
+        )
+        .into(),
+    );
+    if rewritable_config.execution_report_format != config::ExecutionReportFormat::None {
+        list_elements.push(text("Report folder:").into());
+        populate_path_editing_content(
+            "Log folder of the execution",
+            &rewritable_config.execution_report_folder,
+            &mut list_elements,
+            move |path| WindowMessage::SettingsEditExecutionReportFolder(edit_mode, path),
+            move |val| WindowMessage::SettingsEditExecutionReportFolderPathType(edit_mode, val),
+        );
+    }
+    list_elements.push(horizontal_rule(SEPARATOR_HEIGHT).into());
     list_elements.push(text("Update config version:").into());
     list_elements.push(
         pick_list(
@@ -4451,17 +6391,34 @@ fn produce_settings_edit_content<'a>(
     .align_x(Alignment::Start)
 }
 
+// the window state that the content of the panes is produced from
+struct PaneContentSources<'a> {
+    execution_lists: &'a parallel_execution_manager::ParallelExecutionManager,
+    theme: &'a Theme,
+    displayed_configs_list_cache: &'a Vec<ScriptListCacheRecord>,
+    visual_caches: &'a VisualCaches,
+    config: &'a config::AppConfig,
+    edit_data: &'a EditData,
+    window_state: &'a WindowState,
+    execution_history: &'a [execution_history::ExecutionHistoryRecord],
+}
+
 fn view_content<'a>(
-    execution_lists: &parallel_execution_manager::ParallelExecutionManager,
     variant: &PaneVariant,
-    theme: &Theme,
-    displayed_configs_list_cache: &Vec<ScriptListCacheRecord>,
-    paths: &config::PathCaches,
-    visual_caches: &'a VisualCaches,
-    config: &config::AppConfig,
-    edit_data: &EditData,
-    window_state: &WindowState,
+    sources: &PaneContentSources<'a>,
 ) -> Element<'a, WindowMessage> {
+    let PaneContentSources {
+        execution_lists,
+        theme,
+        displayed_configs_list_cache,
+        visual_caches,
+        config,
+        edit_data,
+        window_state,
+        execution_history,
+    } = *sources;
+    let paths = &config.paths;
+
     let content = match variant {
         PaneVariant::ScriptList => produce_script_list_content(
             execution_lists,
@@ -4485,8 +6442,10 @@ fn view_content<'a>(
             execution_lists,
             theme,
             config::get_main_rewritable_config(&config),
+            &config.paths,
             &visual_caches,
         ),
+        PaneVariant::History => produce_history_content(execution_history, theme, visual_caches),
         PaneVariant::Parameters => match &edit_data.window_edit_data {
             Some(window_edit_data) if window_edit_data.settings_edit_mode.is_some() => {
                 let edit_mode = window_edit_data.settings_edit_mode.unwrap();
@@ -4664,6 +6623,39 @@ fn update_autorerun_delay_sec_text(
     new_autorerun_delay_sec
 }
 
+// empty text means no limit, which is stored as 0
+fn parse_optional_limit<T: FromStr + Default>(text: &str) -> Option<T> {
+    if text.is_empty() {
+        Some(T::default())
+    } else {
+        text.parse::<T>().ok()
+    }
+}
+
+fn format_optional_limit<T: std::fmt::Display + Default + PartialEq>(value: T) -> String {
+    if value == T::default() {
+        String::new()
+    } else {
+        value.to_string()
+    }
+}
+
+fn update_timeout_sec_text(app: &mut MainWindow, new_timeout_sec_str: String) -> Option<f32> {
+    let parse_result = f32::from_str(&new_timeout_sec_str);
+    let mut new_timeout_sec = None;
+    if let Ok(parse_result) = parse_result {
+        app.visual_caches.timeout_sec = new_timeout_sec_str;
+        new_timeout_sec = Some(parse_result);
+    } else {
+        // if input is empty, then keep it empty and assume 0, otherwise keep the old value
+        if new_timeout_sec_str.is_empty() {
+            app.visual_caches.timeout_sec = new_timeout_sec_str;
+            new_timeout_sec = Some(0.0);
+        }
+    }
+    new_timeout_sec
+}
+
 fn get_script_config_bring_into_view_scroll_offset(
     app: &MainWindow,
     index: usize,
@@ -4801,13 +6793,17 @@ fn get_schedulable_dragged_execution_scripts(
     Vec::new()
 }
 
-fn init_from_scenario(app: &mut MainWindow) -> Option<Task<WindowMessage>> {
+pub(crate) fn init_from_scenario(
+    app: &mut MainWindow,
+    scenario: Option<Result<scenario::Scenario, String>>,
+    launch_env_vars: &[(std::ffi::OsString, std::ffi::OsString)],
+) -> Option<Task<WindowMessage>> {
     let log_error = |app: &mut MainWindow, error| {
         eprintln!("{}", &error);
         app.window_state.errors_to_show.push(error);
     };
 
-    let scenario = match app.scenario.clone() {
+    let scenario = match scenario {
         None => {
             return None;
         }
@@ -4818,60 +6814,9 @@ fn init_from_scenario(app: &mut MainWindow) -> Option<Task<WindowMessage>> {
         Some(Ok(scenario)) => scenario,
     };
 
-    let mut missing_scripts = Vec::new();
-
     for execution in scenario.parallel_executions {
-        let scripts: Vec<_> = execution
-            .scripts
-            .into_iter()
-            .map(|mut script| {
-                let mut scripts =
-                    get_resulting_scripts_from_guid(&app.app_config, script.uid.clone());
-                if scripts.is_empty() {
-                    missing_scripts.push(script.uid.clone());
-                }
-
-                if scripts.len() == 1 {
-                    if let Some(name) = script.name.take() {
-                        scripts[0].name = name;
-                    }
-
-                    if let Some(arguments) = script.arguments.take() {
-                        scripts[0].arguments_line = arguments;
-                    }
-
-                    if let Some(placeholders) = script.placeholders.take() {
-                        let result_script = &mut scripts[0];
-                        for (placeholder_tag, value) in placeholders {
-                            // let mut is_found = false;
-                            match result_script
-                                .argument_placeholders
-                                .iter_mut()
-                                .find(|placeholder| placeholder.placeholder == placeholder_tag)
-                            {
-                                Some(placeholder) => {
-                                    placeholder.value = value;
-                                }
-                                None => {
-                                    result_script.argument_placeholders.push(
-                                        config::ArgumentPlaceholder {
-                                            name: placeholder_tag.clone(),
-                                            placeholder: placeholder_tag.clone(),
-                                            value,
-                                            hint: String::new(),
-                                            is_required: false,
-                                        },
-                                    );
-                                }
-                            }
-                        }
-                    }
-                }
-
-                scripts
-            })
-            .flatten()
-            .collect();
+        let (mut scripts, missing_scripts) =
+            get_scripts_from_scenario_execution(&app.app_config, &execution);
 
         if !missing_scripts.is_empty() {
             // unfortunate extra allocation and string copies because of the extra collect() call
@@ -4890,16 +6835,18 @@ fn init_from_scenario(app: &mut MainWindow) -> Option<Task<WindowMessage>> {
             return None;
         }
 
+        for script in &mut scripts {
+            script.launch_env_vars = launch_env_vars.to_vec();
+        }
+
         if execution.only_schedule == Some(true)
             || scripts
                 .iter()
-                .any(|script| is_original_script_missing_arguments(&script))
+                .any(|script| is_original_script_missing_arguments(&script.original))
         {
-            for script in scripts {
-                app.execution_manager.add_script_to_edited_list(script);
-            }
+            app.execution_manager.add_scripts_to_edited_list(scripts);
         } else {
-            start_new_execution_from_provided_scripts(app, scripts);
+            start_new_execution_from_provided_execution_scripts(app, scripts);
         }
         update_edited_execution_list_script_number(app);
         update_drag_and_drop_area_bounds(app);
@@ -4944,7 +6891,9 @@ fn create_preset_with_scripts(
         items: vec![],
     };
 
-    for execution_script in edited_scripts {
+    let dependency_indexes = execution_thread::get_dependency_indexes(edited_scripts);
+
+    for (execution_script, dependencies) in edited_scripts.iter().zip(dependency_indexes) {
         let original_script = config::get_original_script_definition_by_uid(
             &app_config,
             &execution_script.original.uid,
@@ -5040,6 +6989,16 @@ fn create_preset_with_scripts(
             Some(script.autorerun_delay_sec)
         };
 
+        let timeout_sec = if let Some(original_script) = &original_script {
+            if original_script.timeout_sec == script.timeout_sec {
+                None
+            } else {
+                Some(script.timeout_sec)
+            }
+        } else {
+            Some(script.timeout_sec)
+        };
+
         let reaction_to_previous_failures = if let Some(original_script) = original_script {
             if original_script.reaction_to_previous_failures == script.reaction_to_previous_failures
             {
@@ -5061,6 +7020,38 @@ fn create_preset_with_scripts(
             Some(script.autoclean_on_success)
         };
 
+        // only the variables that the original script doesn't already have with the same value
+        let env_vars = script
+            .env_vars
+            .iter()
+            .filter(|env_var| {
+                original_script
+                    .is_none_or(|original_script| !original_script.env_vars.contains(env_var))
+            })
+            .cloned()
+            .collect();
+
+        let exit_codes = script
+            .exit_codes
+            .iter()
+            .filter(|exit_code| {
+                original_script
+                    .is_none_or(|original_script| !original_script.exit_codes.contains(exit_code))
+            })
+            .cloned()
+            .collect();
+
+        let run_conditions = script
+            .run_conditions
+            .iter()
+            .filter(|run_condition| {
+                original_script.is_none_or(|original_script| {
+                    !original_script.run_conditions.contains(run_condition)
+                })
+            })
+            .cloned()
+            .collect();
+
         preset.items.push(config::PresetItem {
             uid: script.uid.clone(),
             name,
@@ -5069,8 +7060,13 @@ fn create_preset_with_scripts(
             overridden_placeholder_values,
             autorerun_count,
             autorerun_delay_sec,
+            timeout_sec,
             reaction_to_previous_failures,
             autoclean_on_success,
+            env_vars,
+            exit_codes,
+            run_conditions,
+            dependencies,
         });
     }
 
@@ -2,12 +2,19 @@
 // Distributed under the MIT License (license terms are at http://opensource.org/licenses/MIT).
 
 use crate::config;
+use crate::control_socket;
 use crate::drag_and_drop::{DragAndDropList, DropArea};
 use crate::events;
+use crate::execution_history;
+use crate::execution_report;
+use crate::file_references;
 use crate::git_support;
 use crate::keybind_editing;
+use crate::log_retention;
 use crate::main_window::*;
+use crate::main_window_widgets;
 use crate::parallel_execution_manager;
+use crate::scenario;
 use crate::style;
 use crate::{color_utils, execution_thread};
 
@@ -18,6 +25,7 @@ use iced::widget::{pane_grid, text_input};
 use iced::window::resize;
 use iced::{keyboard, window, Size, Task, Theme};
 use std::path::PathBuf;
+use std::time::Instant;
 
 pub(crate) const ONE_EXECUTION_LIST_ELEMENT_HEIGHT: f32 = 30.0;
 pub(crate) const ONE_SCRIPT_LIST_ELEMENT_HEIGHT: f32 = 30.0;
@@ -133,7 +141,7 @@ fn get_script_definition_mut(
 pub fn get_resulting_scripts_from_guid(
     app_config: &config::AppConfig,
     script_uid: config::Guid,
-) -> Vec<config::OriginalScriptDefinition> {
+) -> Vec<execution_thread::ExecutionScript> {
     let original_script = config::get_original_script_definition_by_uid(&app_config, &script_uid);
 
     let (original_script, _idx) = if let Some(original_script) = original_script {
@@ -145,75 +153,313 @@ pub fn get_resulting_scripts_from_guid(
     match original_script {
         config::ScriptDefinition::ReferenceToShared(_) => Vec::new(),
         config::ScriptDefinition::Original(script) => {
-            vec![script.clone()]
+            vec![execution_thread::ExecutionScript::from_original(
+                script.clone(),
+            )]
         }
         config::ScriptDefinition::Preset(preset) => {
-            let resulting_scripts = preset
-                .items
-                .iter()
-                .map(|preset_item| {
-                    (
-                        config::get_original_script_definition_by_uid(
-                            &app_config,
-                            &preset_item.uid,
-                        ),
-                        preset_item,
-                    )
-                })
-                .filter(|(optional_definition, _preset_item)| optional_definition.is_some())
-                .map(|(optional_definition, preset_item)| {
-                    let (new_script, _idx) = optional_definition.unwrap();
-                    let script = match new_script.clone() {
-                        config::ScriptDefinition::Original(mut script) => {
-                            if let Some(name) = &preset_item.name {
-                                script.name = name.clone();
-                            }
+            let mut resulting_scripts = get_resulting_scripts_from_preset(
+                app_config,
+                preset,
+                &mut vec![preset.uid.clone()],
+            );
+            execution_thread::simplify_dependencies(&mut resulting_scripts);
+            resulting_scripts
+        }
+    }
+}
 
-                            if let Some(arguments) = &preset_item.arguments_line {
-                                script.arguments_line = arguments.clone();
-                            }
+struct PresetItemScripts {
+    scripts: Vec<execution_thread::ExecutionScript>,
+    // the scripts of the item that no other script of the item waits for
+    last_script_uids: Vec<config::Guid>,
+    is_nested_preset: bool,
+}
 
-                            for (placeholder, value) in &preset_item.overridden_placeholder_values {
-                                for script in &mut script.argument_placeholders {
-                                    if script.placeholder == *placeholder {
-                                        script.value = value.clone();
-                                    }
-                                }
-                            }
+// presets can contain other presets, the presets that are already being expanded are skipped
+// so a preset that (indirectly) contains itself doesn't expand infinitely
+fn get_resulting_scripts_from_preset(
+    app_config: &config::AppConfig,
+    preset: &config::ScriptPreset,
+    expanded_preset_uids: &mut Vec<config::Guid>,
+) -> Vec<execution_thread::ExecutionScript> {
+    let item_scripts: Vec<PresetItemScripts> = preset
+        .items
+        .iter()
+        .map(|preset_item| {
+            match config::get_original_script_definition_by_uid(app_config, &preset_item.uid) {
+                Some((config::ScriptDefinition::Original(script), _idx)) => {
+                    let mut script = script.clone();
+                    apply_preset_item_overrides(&mut script, preset_item);
+                    let script = execution_thread::ExecutionScript::from_original(script);
+                    PresetItemScripts {
+                        last_script_uids: vec![script.uid.clone()],
+                        scripts: vec![script],
+                        is_nested_preset: false,
+                    }
+                }
+                Some((config::ScriptDefinition::Preset(nested_preset), _idx))
+                    if !expanded_preset_uids.contains(&nested_preset.uid) =>
+                {
+                    expanded_preset_uids.push(nested_preset.uid.clone());
+                    let mut scripts = get_resulting_scripts_from_preset(
+                        app_config,
+                        nested_preset,
+                        expanded_preset_uids,
+                    );
+                    expanded_preset_uids.pop();
+
+                    // make the dependencies explicit, so the scripts of the nested preset keep
+                    // their order when they are mixed with the scripts of other items
+                    let resolved_dependencies = execution_thread::resolve_dependencies(&scripts);
+                    let uids: Vec<config::Guid> =
+                        scripts.iter().map(|script| script.uid.clone()).collect();
+                    for (script, dependencies) in scripts.iter_mut().zip(resolved_dependencies) {
+                        script.dependencies =
+                            Some(dependencies.iter().map(|idx| uids[*idx].clone()).collect());
+                    }
 
-                            if let Some(autorerun_count) = preset_item.autorerun_count {
-                                script.autorerun_count = autorerun_count;
-                            }
+                    apply_preset_item_overrides_to_nested_preset(&mut scripts, preset_item);
+                    PresetItemScripts {
+                        last_script_uids: execution_thread::get_last_script_uids(&scripts),
+                        scripts,
+                        is_nested_preset: true,
+                    }
+                }
+                _ => PresetItemScripts {
+                    scripts: Vec::new(),
+                    last_script_uids: Vec::new(),
+                    is_nested_preset: false,
+                },
+            }
+        })
+        .collect();
+
+    let mut resulting_scripts = Vec::new();
+    for (preset_item, item) in preset.items.iter().zip(&item_scripts) {
+        let item_dependencies: Option<Vec<config::Guid>> =
+            preset_item.dependencies.as_ref().map(|dependencies| {
+                dependencies
+                    .iter()
+                    .filter_map(|item_idx| item_scripts.get(*item_idx))
+                    .flat_map(|item| item.last_script_uids.iter().cloned())
+                    .collect()
+            });
 
-                            if let Some(autorerun_delay_sec) = preset_item.autorerun_delay_sec {
-                                script.autorerun_delay_sec = autorerun_delay_sec;
-                            }
+        if !item.is_nested_preset {
+            for script in &item.scripts {
+                let mut script = script.clone();
+                script.dependencies = item_dependencies.clone();
+                resulting_scripts.push(script);
+            }
+            continue;
+        }
 
-                            if let Some(reaction_to_previous_failures) =
-                                preset_item.reaction_to_previous_failures
-                            {
-                                script.reaction_to_previous_failures =
-                                    reaction_to_previous_failures;
-                            }
+        // the first scripts of the nested preset wait for what the item itself waits for
+        let mut scripts = item.scripts.clone();
+        match item_dependencies {
+            Some(item_dependencies) => {
+                for script in &mut scripts {
+                    if script.dependencies.as_ref().is_some_and(Vec::is_empty) {
+                        script.dependencies = Some(item_dependencies.clone());
+                    }
+                }
+                resulting_scripts.append(&mut scripts);
+            }
+            None => execution_thread::append_scripts_to_list(&mut resulting_scripts, scripts),
+        }
+    }
 
-                            if let Some(autoclean_on_success) = preset_item.autoclean_on_success {
-                                script.autoclean_on_success = autoclean_on_success;
-                            }
+    resulting_scripts
+}
 
-                            script
-                        }
-                        _ => {
-                            panic!("Preset shouldn't contain presets or references");
-                        }
-                    };
+fn apply_preset_item_overrides(
+    script: &mut config::OriginalScriptDefinition,
+    preset_item: &config::PresetItem,
+) {
+    if let Some(name) = &preset_item.name {
+        script.name = name.clone();
+    }
 
-                    script
-                })
-                .collect();
+    if let Some(arguments) = &preset_item.arguments_line {
+        script.arguments_line = arguments.clone();
+    }
 
-            resulting_scripts
+    apply_common_preset_item_overrides(script, preset_item);
+
+    if let Some(reaction_to_previous_failures) = preset_item.reaction_to_previous_failures {
+        script.reaction_to_previous_failures = reaction_to_previous_failures;
+    }
+}
+
+// name and arguments are specific to one script, so they don't apply to the scripts of a nested
+// preset, and the reaction to failures is only applied to the scripts that start the nested preset
+fn apply_preset_item_overrides_to_nested_preset(
+    scripts: &mut [execution_thread::ExecutionScript],
+    preset_item: &config::PresetItem,
+) {
+    for script in scripts {
+        apply_common_preset_item_overrides(&mut script.original, preset_item);
+
+        if let Some(reaction_to_previous_failures) = preset_item.reaction_to_previous_failures {
+            if script.dependencies.as_ref().is_some_and(Vec::is_empty) {
+                script.original.reaction_to_previous_failures = reaction_to_previous_failures;
+            }
+        }
+    }
+}
+
+fn apply_common_preset_item_overrides(
+    script: &mut config::OriginalScriptDefinition,
+    preset_item: &config::PresetItem,
+) {
+    for (placeholder, value) in &preset_item.overridden_placeholder_values {
+        for argument_placeholder in &mut script.argument_placeholders {
+            if argument_placeholder.placeholder == *placeholder {
+                argument_placeholder.value = value.clone();
+            }
+        }
+    }
+
+    if let Some(autorerun_count) = preset_item.autorerun_count {
+        script.autorerun_count = autorerun_count;
+    }
+
+    if let Some(autorerun_delay_sec) = preset_item.autorerun_delay_sec {
+        script.autorerun_delay_sec = autorerun_delay_sec;
+    }
+
+    if let Some(timeout_sec) = preset_item.timeout_sec {
+        script.timeout_sec = timeout_sec;
+    }
+
+    if let Some(autoclean_on_success) = preset_item.autoclean_on_success {
+        script.autoclean_on_success = autoclean_on_success;
+    }
+
+    for env_var in &preset_item.env_vars {
+        match script
+            .env_vars
+            .iter_mut()
+            .find(|script_env_var| script_env_var.name == env_var.name)
+        {
+            Some(script_env_var) => script_env_var.value = env_var.value.clone(),
+            None => script.env_vars.push(env_var.clone()),
+        }
+    }
+
+    for exit_code in &preset_item.exit_codes {
+        match script
+            .exit_codes
+            .iter_mut()
+            .find(|script_exit_code| script_exit_code.exit_code == exit_code.exit_code)
+        {
+            Some(script_exit_code) => script_exit_code.result = exit_code.result,
+            None => script.exit_codes.push(exit_code.clone()),
+        }
+    }
+
+    script
+        .run_conditions
+        .extend(preset_item.run_conditions.iter().cloned());
+}
+
+// whether the preset has the script as one of its items, directly or through nested presets
+pub fn does_preset_contain_script(
+    app_config: &config::AppConfig,
+    preset_uid: &config::Guid,
+    script_uid: &config::Guid,
+) -> bool {
+    let mut presets_to_check = vec![preset_uid.clone()];
+    let mut checked_presets = Vec::new();
+    while let Some(preset_uid) = presets_to_check.pop() {
+        let Some((config::ScriptDefinition::Preset(preset), _idx)) =
+            config::get_original_script_definition_by_uid(app_config, &preset_uid)
+        else {
+            continue;
+        };
+        checked_presets.push(preset_uid);
+
+        for item in &preset.items {
+            if item.uid == *script_uid {
+                return true;
+            }
+            if !checked_presets.contains(&item.uid) {
+                presets_to_check.push(item.uid.clone());
+            }
         }
     }
+
+    false
+}
+
+// returns the resulting scripts with the scenario overrides applied and the list of missing uids
+pub fn get_scripts_from_scenario_execution(
+    app_config: &config::AppConfig,
+    execution: &scenario::Execution,
+) -> (Vec<execution_thread::ExecutionScript>, Vec<config::Guid>) {
+    let mut missing_scripts = Vec::new();
+
+    let scripts = execution
+        .scripts
+        .iter()
+        .map(|script| {
+            let mut scripts = get_resulting_scripts_from_guid(app_config, script.uid.clone());
+            if scripts.is_empty() {
+                missing_scripts.push(script.uid.clone());
+            }
+
+            if scripts.len() == 1 {
+                if let Some(name) = &script.name {
+                    scripts[0].original.name = name.clone();
+                }
+
+                if let Some(arguments) = &script.arguments {
+                    scripts[0].original.arguments_line = arguments.clone();
+                }
+
+                if let Some(placeholders) = &script.placeholders {
+                    let result_script = &mut scripts[0].original;
+                    for (placeholder_tag, value) in placeholders {
+                        match result_script
+                            .argument_placeholders
+                            .iter_mut()
+                            .find(|placeholder| placeholder.placeholder == *placeholder_tag)
+                        {
+                            Some(placeholder) => {
+                                placeholder.value = value.clone();
+                            }
+                            None => {
+                                result_script.argument_placeholders.push(
+                                    config::ArgumentPlaceholder {
+                                        name: placeholder_tag.clone(),
+                                        placeholder: placeholder_tag.clone(),
+                                        value: value.clone(),
+                                        hint: String::new(),
+                                        is_required: false,
+                                    },
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+
+            // unlike other overrides, timeout makes sense for every script of a preset
+            if let Some(timeout_sec) = script.timeout_sec {
+                for resulting_script in &mut scripts {
+                    resulting_script.original.timeout_sec = timeout_sec;
+                }
+            }
+
+            scripts
+        })
+        .fold(Vec::new(), |mut resulting_scripts, scripts| {
+            execution_thread::append_scripts_to_list(&mut resulting_scripts, scripts);
+            resulting_scripts
+        });
+
+    (scripts, missing_scripts)
 }
 
 fn find_script_idx_by_id(
@@ -450,15 +696,16 @@ pub fn try_add_script_to_execution_or_start_new(app: &mut MainWindow, script_uid
             .unwrap()
             .get_id();
 
-        app.execution_manager.add_scripts_to_running_execution(
-            &app.app_config,
-            execution_id,
-            scripts_to_add,
-        );
+        app.execution_manager
+            .add_execution_scripts_to_running_execution(
+                &app.app_config,
+                execution_id,
+                scripts_to_add,
+            );
         update_drag_and_drop_area_bounds(app);
     } else if executions_number == 0 {
         // if there are no executions, then we can start a new one
-        start_new_execution_from_provided_scripts(app, scripts_to_add);
+        start_new_execution_from_provided_execution_scripts(app, scripts_to_add);
     }
 
     events::on_execution_pane_content_height_decreased(app);
@@ -760,6 +1007,26 @@ pub fn update_git_branch_visibility(app: &mut MainWindow) {
     }
 }
 
+// the forwarded launches of the single instance mode come through the control socket too
+pub fn update_control_server(app: &mut MainWindow) {
+    let rewritable_config = config::get_main_rewritable_config(&app.app_config);
+    if rewritable_config.enable_control_socket || rewritable_config.single_instance {
+        match &app.control_server {
+            Some(control_server) => {
+                control_server.set_api_enabled(rewritable_config.enable_control_socket)
+            }
+            None => {
+                app.control_server = control_socket::ControlServer::start(
+                    &app.app_config.paths.config_path,
+                    rewritable_config.enable_control_socket,
+                );
+            }
+        }
+    } else {
+        app.control_server = None;
+    }
+}
+
 pub fn update_theme_icons(app: &mut MainWindow) {
     let icons = &mut app.visual_caches.icons;
     icons.themed = icons
@@ -1051,32 +1318,33 @@ pub fn start_new_execution_from_edited_scripts(app: &mut MainWindow) {
     start_new_execution_from_provided_execution_scripts(app, scripts_to_execute);
 }
 
-pub fn start_new_execution_from_provided_scripts(
+pub fn start_new_execution_from_provided_execution_scripts(
     app: &mut MainWindow,
-    scripts: Vec<config::OriginalScriptDefinition>,
-) {
-    start_new_execution_from_provided_execution_scripts(
-        app,
-        scripts
-            .into_iter()
-            .map(|script| execution_thread::ExecutionScript::from_original(script))
-            .collect(),
-    );
+    scripts: Vec<execution_thread::ExecutionScript>,
+) -> Option<parallel_execution_manager::ExecutionId> {
+    let new_execution_id = start_new_execution_in_background(app, scripts)?;
+
+    clear_script_selection(&mut app.window_state.selected_scripts);
+    app.visual_caches.selected_execution_log = Some(new_execution_id);
+    cancel_all_drag_and_drop_operations(app);
+
+    Some(new_execution_id)
 }
 
-pub fn start_new_execution_from_provided_execution_scripts(
+// for the executions that are started not by the user (e.g. by a schedule), so the selection,
+// the shown log and the drag and drop that the user is in the middle of are kept
+pub fn start_new_execution_in_background(
     app: &mut MainWindow,
     scripts: Vec<execution_thread::ExecutionScript>,
-) {
+) -> Option<parallel_execution_manager::ExecutionId> {
     if scripts
         .iter()
         .any(|script| is_original_script_missing_arguments(&script.original))
     {
         eprintln!("Some scripts are missing arguments");
-        return;
+        return None;
     }
 
-    clear_script_selection(&mut app.window_state.selected_scripts);
     let new_execution_id = app
         .execution_manager
         .start_new_execution(&app.app_config, scripts);
@@ -1086,10 +1354,361 @@ pub fn start_new_execution_from_provided_execution_scripts(
         .running_executions
         .push(DropArea::new());
 
-    app.visual_caches.selected_execution_log = Some(new_execution_id);
+    if app.visual_caches.selected_execution_log.is_none() {
+        app.visual_caches.selected_execution_log = Some(new_execution_id);
+    }
     update_button_key_hint_caches(app);
     update_drag_and_drop_area_bounds(app);
-    cancel_all_drag_and_drop_operations(app);
+
+    Some(new_execution_id)
+}
+
+pub fn run_scheduled_scripts(app: &mut MainWindow) {
+    let now = chrono::Local::now();
+    app.scheduler.update_schedules(
+        &config::get_main_rewritable_config(&app.app_config).schedules,
+        now,
+    );
+
+    // nothing is started while the config is being edited, such runs count as missed
+    if app.edit_data.window_edit_data.is_none() {
+        let execution_manager = &app.execution_manager;
+        let due_runs = app.scheduler.take_due_runs(now, |execution_id| {
+            execution_manager
+                .get_started_executions()
+                .get(execution_id)
+                .is_some_and(|execution| !execution.has_finished_execution())
+        });
+
+        for (schedule_idx, script_uid) in due_runs {
+            let scripts = get_resulting_scripts_from_guid(&app.app_config, script_uid);
+            if !can_start_scripts(&scripts) {
+                continue;
+            }
+            if let Some(execution_id) = start_new_execution_in_background(app, scripts) {
+                app.scheduler.on_run_started(schedule_idx, execution_id);
+            }
+        }
+    }
+
+    app.visual_caches.next_scheduled_runs = app.scheduler.get_next_run_times();
+}
+
+pub fn run_scripts_triggered_by_file_changes(app: &mut MainWindow) {
+    app.file_watch_triggers.update_file_watches(
+        &config::get_main_rewritable_config(&app.app_config).file_watches,
+        &app.app_config.paths.work_path,
+    );
+
+    // same as with schedules, nothing is started while the config is being edited
+    if app.edit_data.window_edit_data.is_some() {
+        return;
+    }
+
+    let execution_manager = &app.execution_manager;
+    let triggered_runs =
+        app.file_watch_triggers
+            .take_triggered_runs(Instant::now(), |execution_id| {
+                execution_manager
+                    .get_started_executions()
+                    .get(execution_id)
+                    .is_some_and(|execution| !execution.has_finished_execution())
+            });
+
+    for execution_id in triggered_runs.executions_to_stop {
+        app.execution_manager.request_stop_execution(execution_id);
+    }
+
+    for (file_watch_idx, script_uid) in triggered_runs.runs_to_start {
+        let scripts = get_resulting_scripts_from_guid(&app.app_config, script_uid);
+        if !can_start_scripts(&scripts) {
+            continue;
+        }
+        if let Some(execution_id) = start_new_execution_in_background(app, scripts) {
+            app.file_watch_triggers
+                .on_run_started(file_watch_idx, execution_id);
+        }
+    }
+}
+
+pub fn process_control_requests(app: &mut MainWindow) -> Option<Task<WindowMessage>> {
+    let mut control_server = app.control_server.take()?;
+
+    let mut tasks = Vec::new();
+    while let Some(received_request) = control_server.try_receive_request() {
+        let response = handle_control_request(
+            app,
+            &mut control_server,
+            &received_request.request,
+            &mut tasks,
+        );
+        received_request.respond(response);
+    }
+
+    app.control_server = Some(control_server);
+
+    if tasks.is_empty() {
+        None
+    } else {
+        Some(Task::batch(tasks))
+    }
+}
+
+fn handle_control_request(
+    app: &mut MainWindow,
+    control_server: &mut control_socket::ControlServer,
+    request: &control_socket::ControlRequest,
+    tasks: &mut Vec<Task<WindowMessage>>,
+) -> serde_json::Value {
+    match request {
+        control_socket::ControlRequest::ListScripts => {
+            let script_definitions = match &app.app_config.local_config_body {
+                Some(local_config) => &local_config.script_definitions,
+                None => &app.app_config.script_definitions,
+            };
+            let scripts: Vec<serde_json::Value> = script_definitions
+                .iter()
+                .filter_map(|script_definition| {
+                    let script_definition = match script_definition {
+                        config::ScriptDefinition::ReferenceToShared(reference) => {
+                            if reference.is_hidden {
+                                return None;
+                            }
+                            config::get_original_script_definition_by_uid(
+                                &app.app_config,
+                                &reference.uid,
+                            )?
+                            .0
+                        }
+                        _ => script_definition,
+                    };
+                    match script_definition {
+                        config::ScriptDefinition::Original(script) => Some(serde_json::json!({
+                            "uid": script.uid,
+                            "name": script.name,
+                            "type": "script",
+                        })),
+                        config::ScriptDefinition::Preset(preset) => Some(serde_json::json!({
+                            "uid": preset.uid,
+                            "name": preset.name,
+                            "type": "preset",
+                        })),
+                        config::ScriptDefinition::ReferenceToShared(_) => None,
+                    }
+                })
+                .collect();
+            serde_json::json!({ "ok": true, "scripts": scripts })
+        }
+        control_socket::ControlRequest::Run {
+            uid,
+            arguments,
+            placeholders,
+            only_schedule,
+        } => {
+            let execution = scenario::Execution {
+                scripts: vec![scenario::Script {
+                    uid: uid.clone(),
+                    name: None,
+                    arguments: arguments.clone(),
+                    placeholders: placeholders.clone(),
+                    timeout_sec: None,
+                }],
+                only_schedule: *only_schedule,
+            };
+            let (scripts, missing_scripts) =
+                get_scripts_from_scenario_execution(&app.app_config, &execution);
+            if !missing_scripts.is_empty() {
+                return control_socket::error_response(format!(
+                    "Script or preset '{}' is not found",
+                    uid.to_string()
+                ));
+            }
+
+            if *only_schedule == Some(true)
+                || scripts
+                    .iter()
+                    .any(|script| is_original_script_missing_arguments(&script.original))
+            {
+                app.execution_manager.add_scripts_to_edited_list(scripts);
+                update_edited_execution_list_script_number(app);
+                update_drag_and_drop_area_bounds(app);
+                return serde_json::json!({ "ok": true, "scheduled": true });
+            }
+
+            if app.edit_data.window_edit_data.is_some() {
+                return control_socket::error_response(
+                    "Can't start scripts while the config is being edited",
+                );
+            }
+            if !can_start_scripts(&scripts) {
+                return control_socket::error_response("The scripts can't be started");
+            }
+            match start_new_execution_in_background(app, scripts) {
+                Some(execution_id) => serde_json::json!({
+                    "ok": true,
+                    "execution_id": control_server.get_external_execution_id(execution_id),
+                }),
+                None => control_socket::error_response("The execution couldn't be started"),
+            }
+        }
+        control_socket::ControlRequest::Stop { execution_id } => {
+            match find_started_execution_id(app, control_server, *execution_id) {
+                Some(execution_id) => {
+                    app.execution_manager.request_stop_execution(execution_id);
+                    serde_json::json!({ "ok": true })
+                }
+                None => control_socket::error_response(format!(
+                    "Execution {} is not found",
+                    execution_id
+                )),
+            }
+        }
+        control_socket::ControlRequest::Status => {
+            let executions: Vec<serde_json::Value> = app
+                .execution_manager
+                .get_started_executions()
+                .key_values()
+                .map(|(execution_id, execution)| {
+                    let scripts: Vec<serde_json::Value> = execution
+                        .get_scheduled_scripts_cache()
+                        .iter()
+                        .map(|record| {
+                            serde_json::json!({
+                                "name": record.script.original.name,
+                                "status": get_script_status_name(&record.status),
+                                "exit_code": record.status.exit_statuses.last().and_then(|exit_status| exit_status.exit_code),
+                                "signal": record.status.exit_statuses.last().and_then(|exit_status| exit_status.signal),
+                            })
+                        })
+                        .collect();
+                    serde_json::json!({
+                        "execution_id": control_server.get_external_execution_id(execution_id),
+                        "name": execution.get_name(),
+                        "has_finished": execution.has_finished_execution(),
+                        "has_failed_scripts": execution.has_failed_scripts(),
+                        "log_folder": execution.get_log_folder_path(),
+                        "scripts": scripts,
+                    })
+                })
+                .collect();
+            serde_json::json!({ "ok": true, "executions": executions })
+        }
+        control_socket::ControlRequest::TailLogs { execution_id } => {
+            let execution = find_started_execution_id(app, control_server, *execution_id)
+                .and_then(|id| app.execution_manager.get_started_executions().get(id));
+            let Some(execution) = execution else {
+                return control_socket::error_response(format!(
+                    "Execution {} is not found",
+                    execution_id
+                ));
+            };
+
+            // only the recent lines are kept in memory, the full logs are in the log folder
+            let lines: Vec<serde_json::Value> = match execution.get_recent_logs().lock() {
+                Ok(recent_logs) => recent_logs
+                    .iter()
+                    .map(|line| {
+                        serde_json::json!({
+                            "time": line.timestamp.format("%H:%M:%S").to_string(),
+                            "type": match line.output_type {
+                                execution_thread::OutputType::StdOut => "stdout",
+                                execution_thread::OutputType::StdErr => "stderr",
+                                execution_thread::OutputType::Error => "error",
+                                execution_thread::OutputType::Event => "event",
+                            },
+                            "text": line.text,
+                        })
+                    })
+                    .collect(),
+                Err(_) => Vec::new(),
+            };
+            serde_json::json!({
+                "ok": true,
+                "has_finished": execution.has_finished_execution(),
+                "log_folder": execution.get_log_folder_path(),
+                "lines": lines,
+            })
+        }
+        control_socket::ControlRequest::Launch { scenario, env_vars } => {
+            if scenario.is_some() && app.edit_data.window_edit_data.is_some() {
+                return control_socket::error_response(
+                    "Can't start scripts while the config is being edited",
+                );
+            }
+
+            let launch_env_vars = env_vars
+                .iter()
+                .map(|(name, value)| {
+                    (
+                        std::ffi::OsString::from(name),
+                        std::ffi::OsString::from(value),
+                    )
+                })
+                .collect::<Vec<_>>();
+            if let Some(task) = init_from_scenario(app, scenario.clone(), &launch_env_vars) {
+                tasks.push(task);
+            }
+            tasks.push(window::get_oldest().and_then(|window_id| {
+                window::minimize(window_id, false).chain(window::gain_focus(window_id))
+            }));
+            serde_json::json!({ "ok": true })
+        }
+    }
+}
+
+fn find_started_execution_id(
+    app: &MainWindow,
+    control_server: &control_socket::ControlServer,
+    external_execution_id: u64,
+) -> Option<parallel_execution_manager::ExecutionId> {
+    control_server
+        .find_execution_id(external_execution_id)
+        .filter(|execution_id| {
+            app.execution_manager
+                .get_started_executions()
+                .contains(*execution_id)
+        })
+}
+
+fn get_script_status_name(status: &execution_thread::ScriptExecutionStatus) -> &'static str {
+    if status.has_script_been_disconnected() {
+        "disconnected"
+    } else if !status.has_script_started() {
+        "pending"
+    } else if !status.has_script_finished() {
+        "running"
+    } else if status.has_script_failed() {
+        "failed"
+    } else if status.has_script_been_skipped() {
+        "skipped"
+    } else if status.has_script_finished_with_warning() {
+        "warning"
+    } else {
+        "success"
+    }
+}
+
+pub fn format_next_run_time(next_run_time: chrono::DateTime<chrono::Local>) -> String {
+    let days_left = (next_run_time.date_naive() - chrono::Local::now().date_naive()).num_days();
+    match days_left {
+        0 => next_run_time.format("%H:%M").to_string(),
+        1..=6 => next_run_time.format("%a %H:%M").to_string(),
+        _ => next_run_time.format("%Y-%m-%d").to_string(),
+    }
+}
+
+pub fn apply_schedule_edit(
+    app: &mut MainWindow,
+    schedule_idx: usize,
+    edit_fn: impl FnOnce(&mut config::ScriptSchedule),
+) {
+    if let Some(schedule) = config::get_main_rewritable_config_mut(&mut app.app_config)
+        .schedules
+        .get_mut(schedule_idx)
+    {
+        edit_fn(schedule);
+        app.edit_data.is_dirty = true;
+    }
 }
 
 pub fn can_start_scripts(scripts: &Vec<execution_thread::ExecutionScript>) -> bool {
@@ -1132,9 +1751,7 @@ pub fn add_script_to_edited_execution(
         return false;
     }
 
-    for script in scripts {
-        app.execution_manager.add_script_to_edited_list(script);
-    }
+    app.execution_manager.add_scripts_to_edited_list(scripts);
 
     if should_focus {
         let script_idx = app.execution_manager.get_edited_scripts().len() - 1;
@@ -1314,6 +1931,38 @@ pub fn remove_config_script(app: &mut MainWindow, config_script_id: ConfigScript
     update_config_cache(app);
     clear_script_selection(&mut app.window_state.selected_scripts);
     keybind_editing::prune_unused_keybinds(app);
+    prune_unused_triggers(app);
+}
+
+pub fn apply_file_watch_edit(
+    app: &mut MainWindow,
+    file_watch_idx: usize,
+    edit_fn: impl FnOnce(&mut config::ScriptFileWatch),
+) {
+    if let Some(file_watch) = config::get_main_rewritable_config_mut(&mut app.app_config)
+        .file_watches
+        .get_mut(file_watch_idx)
+    {
+        edit_fn(file_watch);
+        app.edit_data.is_dirty = true;
+    }
+}
+
+fn prune_unused_triggers(app: &mut MainWindow) {
+    let app_config = &mut app.app_config;
+    let mut schedules =
+        std::mem::take(&mut config::get_main_rewritable_config_mut(app_config).schedules);
+    schedules.retain(|schedule| {
+        config::get_original_script_definition_by_uid(app_config, &schedule.script_uid).is_some()
+    });
+    config::get_main_rewritable_config_mut(app_config).schedules = schedules;
+
+    let mut file_watches =
+        std::mem::take(&mut config::get_main_rewritable_config_mut(app_config).file_watches);
+    file_watches.retain(|file_watch| {
+        config::get_original_script_definition_by_uid(app_config, &file_watch.script_uid).is_some()
+    });
+    config::get_main_rewritable_config_mut(app_config).file_watches = file_watches;
 }
 
 pub fn remove_execution_list_scripts(app: &mut MainWindow, indexes: SortedVec<usize>) {
@@ -1376,6 +2025,7 @@ pub fn select_edited_script(app: &mut MainWindow, config_script_id: ConfigScript
             config::ScriptDefinition::Original(script) => {
                 app.visual_caches.autorerun_count = script.autorerun_count.to_string();
                 app.visual_caches.autorerun_delay_sec = script.autorerun_delay_sec.to_string();
+                app.visual_caches.timeout_sec = script.timeout_sec.to_string();
             }
             config::ScriptDefinition::ReferenceToShared(reference) => {
                 let Some((script, _idx)) =
@@ -1383,6 +2033,7 @@ pub fn select_edited_script(app: &mut MainWindow, config_script_id: ConfigScript
                 else {
                     app.visual_caches.autorerun_count = "Error 1".to_string();
                     app.visual_caches.autorerun_delay_sec = "Error 1".to_string();
+                    app.visual_caches.timeout_sec = "Error 1".to_string();
                     return;
                 };
 
@@ -1391,20 +2042,24 @@ pub fn select_edited_script(app: &mut MainWindow, config_script_id: ConfigScript
                         app.visual_caches.autorerun_count = script.autorerun_count.to_string();
                         app.visual_caches.autorerun_delay_sec =
                             script.autorerun_delay_sec.to_string();
+                        app.visual_caches.timeout_sec = script.timeout_sec.to_string();
                     }
                     config::ScriptDefinition::ReferenceToShared(_) => {
                         app.visual_caches.autorerun_count = "Error 2".to_string();
                         app.visual_caches.autorerun_delay_sec = "Error 2".to_string();
+                        app.visual_caches.timeout_sec = "Error 2".to_string();
                     }
                     config::ScriptDefinition::Preset(_) => {
                         app.visual_caches.autorerun_count = "Error 3".to_string();
                         app.visual_caches.autorerun_delay_sec = "Error 3".to_string();
+                        app.visual_caches.timeout_sec = "Error 3".to_string();
                     }
                 }
             }
             config::ScriptDefinition::Preset(_) => {
                 app.visual_caches.autorerun_count = "Error 4".to_string();
                 app.visual_caches.autorerun_delay_sec = "Error 4".to_string();
+                app.visual_caches.timeout_sec = "Error 4".to_string();
             }
         }
     }
@@ -1424,6 +2079,7 @@ pub fn select_execution_script(app: &mut MainWindow, script_idx: usize) {
     if let Some(script) = &app.execution_manager.get_edited_scripts().get(script_idx) {
         app.visual_caches.autorerun_count = script.original.autorerun_count.to_string();
         app.visual_caches.autorerun_delay_sec = script.original.autorerun_delay_sec.to_string();
+        app.visual_caches.timeout_sec = script.original.timeout_sec.to_string();
     }
 }
 
@@ -1810,6 +2466,62 @@ pub fn apply_config_script_edit(
     }
 }
 
+pub fn open_in_editor(
+    editor_command: &str,
+    file_path: &std::path::Path,
+    line: u32,
+    column: Option<u32>,
+) -> std::io::Result<()> {
+    let command = file_references::get_editor_command(editor_command, file_path, line, column);
+    let Some((program, arguments)) = command.split_first() else {
+        return Err(std::io::Error::other("the command is empty"));
+    };
+
+    let mut child = std::process::Command::new(program)
+        .args(arguments)
+        .spawn()?;
+    // the editor can keep running, wait for it without blocking the UI
+    std::thread::spawn(move || {
+        let _ = child.wait();
+    });
+    Ok(())
+}
+
+pub fn apply_log_pattern_rule_edit(
+    rules: &mut Vec<config::LogPatternRule>,
+    edit: main_window_widgets::LogPatternRuleEdit,
+) {
+    match edit {
+        main_window_widgets::LogPatternRuleEdit::Add => {
+            rules.push(config::LogPatternRule {
+                pattern: String::new(),
+                severity: config::LogPatternSeverity::Error,
+                fails_script: false,
+            });
+        }
+        main_window_widgets::LogPatternRuleEdit::Remove(index) => {
+            if index < rules.len() {
+                rules.remove(index);
+            }
+        }
+        main_window_widgets::LogPatternRuleEdit::EditPattern(index, pattern) => {
+            if let Some(rule) = rules.get_mut(index) {
+                rule.pattern = pattern;
+            }
+        }
+        main_window_widgets::LogPatternRuleEdit::EditSeverity(index, severity) => {
+            if let Some(rule) = rules.get_mut(index) {
+                rule.severity = severity;
+            }
+        }
+        main_window_widgets::LogPatternRuleEdit::ToggleFailsScript(index, fails_script) => {
+            if let Some(rule) = rules.get_mut(index) {
+                rule.fails_script = fails_script;
+            }
+        }
+    }
+}
+
 pub fn apply_config_preset_edit(
     app: &mut MainWindow,
     config_script_id: ConfigScriptId,
@@ -1915,6 +2627,7 @@ pub fn exit_window_edit_mode(app: &mut MainWindow) {
     keybind_editing::update_keybind_visual_caches(app, config::get_main_edit_mode(&app.app_config));
     update_config_cache(app);
     update_git_branch_visibility(app);
+    update_control_server(app);
     update_drag_and_drop_area_bounds(app);
     cancel_all_drag_and_drop_operations(app);
 }
@@ -1992,6 +2705,49 @@ pub fn should_autoclean_on_success(
     false
 }
 
+pub fn record_execution_to_history(
+    app: &mut MainWindow,
+    execution_id: parallel_execution_manager::ExecutionId,
+) {
+    let Some(execution) = app
+        .execution_manager
+        .get_started_executions()
+        .get(execution_id)
+    else {
+        return;
+    };
+
+    let record = execution_history::make_record(execution);
+    execution_history::append_record(&app.app_config.paths.logs_path, &record);
+    execution_report::write_execution_report(&app.app_config, execution.get_name(), &record);
+    execution_history::add_record_to_loaded(&mut app.execution_history, record);
+
+    start_log_cleanup(app);
+}
+
+pub fn start_log_cleanup(app: &mut MainWindow) {
+    let executions = app.execution_manager.get_started_executions();
+    // the executions that are still open can be looked at, so their logs are kept
+    let protected_directories = executions
+        .values()
+        .map(|execution| execution.get_log_folder_path().clone())
+        .collect();
+    let running_directories = executions
+        .values()
+        .filter(|execution| {
+            !execution.has_finished_execution() || execution.is_waiting_execution_to_finish()
+        })
+        .map(|execution| execution.get_log_folder_path().clone())
+        .collect();
+    let request = log_retention::LogCleanupRequest::new(
+        &app.app_config,
+        protected_directories,
+        running_directories,
+        &app.execution_history,
+    );
+    app.visual_caches.log_cleaner.request_cleanup(request);
+}
+
 pub(crate) fn get_script_list_script_offset(index: usize) -> f32 {
     index as f32 * ONE_SCRIPT_LIST_ELEMENT_HEIGHT
 }
@@ -2314,6 +3070,23 @@ mod tests {
                             executor: vec!["python".to_string()],
                             executor_arguments: vec![],
                         }],
+                        execution_report_format: config::ExecutionReportFormat::None,
+                        execution_report_folder: config::PathConfig::default(),
+                        keep_escape_sequences_in_logs: false,
+                        log_file_format: config::LogFileFormat::Plain,
+                        parallel_scripts_limit: 0,
+                        schedules: Vec::new(),
+                        file_watches: Vec::new(),
+                        single_instance: false,
+                        enable_control_socket: false,
+                        slow_script_threshold_percent: 200,
+                        log_retention: config::LogRetention::default(),
+                        log_compression: config::LogCompression {
+                            mode: config::LogCompressionMode::Disabled,
+                            after_days: 7,
+                        },
+                        log_pattern_rules: Vec::new(),
+                        editor_command: String::new(),
                     },
                     script_definitions: vec![
                         config::ScriptDefinition::Original(config::OriginalScriptDefinition {
@@ -2326,6 +3099,7 @@ mod tests {
                             argument_placeholders: Vec::new(),
                             autorerun_count: 0,
                             autorerun_delay_sec: 0.0,
+                            timeout_sec: 0.0,
                             reaction_to_previous_failures:
                                 config::ReactionToPreviousFailures::SkipOnFailure,
                             arguments_requirement: config::ArgumentRequirement::Optional,
@@ -2335,6 +3109,13 @@ mod tests {
                             is_hidden: false,
                             autoclean_on_success: false,
                             ignore_output: false,
+                            keep_child_processes: false,
+                            accept_input: false,
+                            env_vars: Vec::new(),
+                            env_file: config::PathConfig::default(),
+                            exit_codes: Vec::new(),
+                            run_conditions: Vec::new(),
+                            log_pattern_rules: Vec::new(),
                         }),
                         config::ScriptDefinition::Original(config::OriginalScriptDefinition {
                             uid: test_script_guid_2.clone(),
@@ -2346,6 +3127,7 @@ mod tests {
                             argument_placeholders: Vec::new(),
                             autorerun_count: 0,
                             autorerun_delay_sec: 0.0,
+                            timeout_sec: 0.0,
                             reaction_to_previous_failures:
                                 config::ReactionToPreviousFailures::SkipOnFailure,
                             arguments_requirement: config::ArgumentRequirement::Optional,
@@ -2355,6 +3137,13 @@ mod tests {
                             is_hidden: false,
                             autoclean_on_success: false,
                             ignore_output: false,
+                            keep_child_processes: false,
+                            accept_input: false,
+                            env_vars: Vec::new(),
+                            env_file: config::PathConfig::default(),
+                            exit_codes: Vec::new(),
+                            run_conditions: Vec::new(),
+                            log_pattern_rules: Vec::new(),
                         }),
                         config::ScriptDefinition::Preset(config::ScriptPreset {
                             uid: test_script_guid_3.clone(),
@@ -2369,8 +3158,13 @@ mod tests {
                                     overridden_placeholder_values: std::collections::HashMap::new(),
                                     autorerun_count: None,
                                     autorerun_delay_sec: None,
+                                    timeout_sec: None,
                                     reaction_to_previous_failures: None,
                                     autoclean_on_success: None,
+                                    env_vars: Vec::new(),
+                                    exit_codes: Vec::new(),
+                                    run_conditions: Vec::new(),
+                                    dependencies: None,
                                 },
                                 config::PresetItem {
                                     uid: test_script_guid_2.clone(),
@@ -2380,8 +3174,13 @@ mod tests {
                                     overridden_placeholder_values: std::collections::HashMap::new(),
                                     autorerun_count: None,
                                     autorerun_delay_sec: None,
+                                    timeout_sec: None,
                                     reaction_to_previous_failures: None,
                                     autoclean_on_success: None,
+                                    env_vars: Vec::new(),
+                                    exit_codes: Vec::new(),
+                                    run_conditions: Vec::new(),
+                                    dependencies: None,
                                 },
                             ],
                         }),
@@ -2395,6 +3194,8 @@ mod tests {
                     },
                     env_vars: Vec::new(),
                     custom_title: None,
+                    is_headless: false,
+                    execution_report_override: None,
                     config_read_error: None,
                     local_config_path: config::PathConfig::default(),
                     arguments_read_error: None,
@@ -2417,6 +3218,23 @@ mod tests {
                                 executor: vec!["python".to_string()],
                                 executor_arguments: vec![],
                             }],
+                            execution_report_format: config::ExecutionReportFormat::None,
+                            execution_report_folder: config::PathConfig::default(),
+                            keep_escape_sequences_in_logs: false,
+                            log_file_format: config::LogFileFormat::Plain,
+                            parallel_scripts_limit: 0,
+                            schedules: Vec::new(),
+                            file_watches: Vec::new(),
+                            single_instance: false,
+                            enable_control_socket: false,
+                            slow_script_threshold_percent: 200,
+                            log_retention: config::LogRetention::default(),
+                            log_compression: config::LogCompression {
+                                mode: config::LogCompressionMode::Disabled,
+                                after_days: 7,
+                            },
+                            log_pattern_rules: Vec::new(),
+                            editor_command: String::new(),
                         },
                         script_definitions: vec![
                             config::ScriptDefinition::ReferenceToShared(
@@ -2447,6 +3265,7 @@ mod tests {
                                 argument_placeholders: Vec::new(),
                                 autorerun_count: 0,
                                 autorerun_delay_sec: 0.0,
+                                timeout_sec: 0.0,
                                 reaction_to_previous_failures:
                                     config::ReactionToPreviousFailures::SkipOnFailure,
                                 arguments_requirement: config::ArgumentRequirement::Optional,
@@ -2456,6 +3275,13 @@ mod tests {
                                 is_hidden: false,
                                 autoclean_on_success: false,
                                 ignore_output: false,
+                                keep_child_processes: false,
+                                accept_input: false,
+                                env_vars: Vec::new(),
+                                env_file: config::PathConfig::default(),
+                                exit_codes: Vec::new(),
+                                run_conditions: Vec::new(),
+                                log_pattern_rules: Vec::new(),
                             }),
                         ],
                     })),
@@ -2504,6 +3330,68 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_given_preset_with_nested_presets_when_get_resulting_scripts_from_guid_then_expands_them_once(
+    ) {
+        let (mut app_config, all_guids) = APP_CONFIG_WITH_DIFFERENT_SCRIPTS();
+        let make_preset_item = |uid: &config::Guid| config::PresetItem {
+            uid: uid.clone(),
+            name: None,
+            arguments_line: None,
+            executor_arguments: None,
+            overridden_placeholder_values: std::collections::HashMap::new(),
+            autorerun_count: None,
+            autorerun_delay_sec: None,
+            timeout_sec: None,
+            reaction_to_previous_failures: None,
+            autoclean_on_success: None,
+            env_vars: Vec::new(),
+            exit_codes: Vec::new(),
+            run_conditions: Vec::new(),
+            dependencies: None,
+        };
+        let nightly_preset_guid = config::Guid::new();
+        app_config
+            .script_definitions
+            .push(config::ScriptDefinition::Preset(config::ScriptPreset {
+                uid: nightly_preset_guid.clone(),
+                name: "Nightly".to_string(),
+                icon: config::PathConfig::default(),
+                items: vec![
+                    config::PresetItem {
+                        name: Some("Not applied to nested scripts".to_string()),
+                        timeout_sec: Some(5.0),
+                        ..make_preset_item(&all_guids[2])
+                    },
+                    config::PresetItem {
+                        dependencies: Some(Vec::new()),
+                        ..make_preset_item(&all_guids[0])
+                    },
+                ],
+            }));
+        // make a cycle: the nested preset refers back to the nightly preset
+        if let config::ScriptDefinition::Preset(preset) = &mut app_config.script_definitions[2] {
+            preset.items.push(make_preset_item(&nightly_preset_guid));
+        }
+
+        let scripts = get_resulting_scripts_from_guid(&app_config, nightly_preset_guid);
+
+        assert_eq!(scripts.len(), 3);
+        assert_eq!(scripts[0].original.name, "Shared script 1");
+        assert_eq!(scripts[1].original.name, "Original script 2");
+        assert_eq!(scripts[0].original.timeout_sec, 5.0);
+        assert_eq!(scripts[1].original.timeout_sec, 5.0);
+        assert_eq!(scripts[2].original.timeout_sec, 0.0);
+        assert_eq!(
+            execution_thread::resolve_dependencies(&scripts),
+            vec![vec![], vec![0], vec![]]
+        );
+        assert_eq!(
+            get_resulting_scripts_from_guid(&app_config, all_guids[2].clone()).len(),
+            3
+        );
+    }
+
     #[test]
     fn get_pretty_name_test() {
         assert_eq!(get_pretty_name("test.sh".to_string()), "Test".to_string());
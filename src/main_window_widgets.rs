@@ -3,8 +3,10 @@
 
 use crate::config;
 use crate::keybind_editing;
+use crate::log_patterns;
 use crate::main_window::*;
 use crate::main_window_utils::*;
+use crate::schedules;
 use crate::style;
 use iced::advanced::image::Handle;
 use iced::widget::text::LineHeight;
@@ -23,8 +25,29 @@ pub(crate) const RETRY_COUNT_HELP_TEXT: &str =
     "If the script fails, it will be retried this many times before failing the whole execution.";
 pub(crate) const RETRY_DELAY_HELP_TEXT: &str =
     "How many seconds to wait before retrying (can be fractional).";
+pub(crate) const EXECUTION_REPORT_HELP_TEXT: &str = "Write a machine-readable report when an execution finishes. By default it is written to the log folder of the execution, if a report folder is set, reports from all executions are written there. Can be overridden with '--report' argument.";
+pub(crate) const KEEP_ESCAPE_SEQUENCES_HELP_TEXT: &str = "Scripts can print ANSI escape sequences to color their output. They are always shown as colors in scripter, but by default are removed from the log files so they are readable in any text editor. Enable this if you view the logs with tools that understand colors (e.g. 'less -R').";
+pub(crate) const TIMEOUT_HELP_TEXT: &str = "Kill the script if one attempt runs longer than this number of seconds (can be fractional). The attempt is counted as failed and can be retried. 0 means no timeout.";
 pub(crate) const CUSTOM_EXECUTOR_HELP_TEXT: &str = "By default, the script will be run using the shell of the operating system (e.g. cmd.exe on Windows, sh on Linux), this option allows to override this behavior to run the script using python, powershell, etc.";
+pub(crate) const ACCEPT_INPUT_HELP_TEXT: &str = "Connect stdin of the script, so when it is running you can type answers to its prompts (e.g. confirmations) in the log pane. Without this, the script gets no input and prompts fail right away.";
+pub(crate) const SCRIPT_DEPENDENCIES_HELP_TEXT: &str = "By default scripts run one after another. Uncheck this to choose which scripts this one waits for. Scripts that don't wait for each other run at the same time (up to the limit set in the settings).\n\"Reaction to previous failures\" then only looks at the scripts it waits for. \"Save as preset\" keeps these links.";
+pub(crate) const PARALLEL_SCRIPTS_LIMIT_HELP_TEXT: &str = "Scripts that don't wait for each other (see \"Wait for all previous scripts\" when editing a scheduled script) can run at the same time. This limits how many of them run at once in one execution. Leave empty for no limit.";
+pub(crate) const SCHEDULE_HELP_TEXT: &str = "Start this script automatically while scripter is open.\nA cron expression has five fields in local time: minute, hour, day of month, month and day of week, e.g. \"0 18 * * mon-fri\" runs at 18:00 on workdays.\nA run is missed if the computer was asleep, the config was being edited, or the previous run of this schedule is still going. Missed runs are either skipped, or replaced with one run that starts as soon as possible.";
+pub(crate) const FILE_WATCH_HELP_TEXT: &str = "Start this script when the watched files change while scripter is open. Paths are relative to the working directory and can use wildcards: \"*\" and \"?\" inside a name, \"**\" for any number of folders (e.g. \"src/**/*.rs\"). A path without wildcards watches everything inside it.\nThe script starts when nothing has changed for the debounce time. The changes of the ignored paths (e.g. \"target\" or \"**/*.log\") don't start the script.\nIf the script writes to the watched files itself, ignore those files, or choose to ignore the changes made while the script is running, otherwise it will keep starting itself.";
+pub(crate) const SINGLE_INSTANCE_HELP_TEXT: &str = "When scripter is started again with the same config (e.g. with --scenario or --run), the scripts are sent to the already open window instead of opening a new one.\nEnvironment variables from --env are set only for the scripts of that launch, on top of the ones of the open window.";
+pub(crate) const CONTROL_SOCKET_HELP_TEXT: &str = "Let other programs started by you control this window through a local socket, e.g. \"scripter --send run <uid>\" from editor tasks or shell aliases. Other users of the computer can't connect to it.\nThe same socket is used by \"Reuse the open window for new launches\", so enabling that one also allows the control.";
+pub(crate) const ENV_VARS_HELP_TEXT: &str = "Environment variables that are set only for this script. The values can use argument placeholders.\nThe env file is read before each run, it has one \"NAME=value\" per line. The variables set here override the ones from the file, and both override the ones from --env.";
+pub(crate) const EXIT_CODES_HELP_TEXT: &str = "By default exit code 0 is a success and any other code is a failure.\nHere you can make specific exit codes count as a success, a warning (shown with its own icon, doesn't fail the execution) or skip the script.";
+pub(crate) const RUN_CONDITIONS_HELP_TEXT: &str = "The script runs only if all the conditions are true, otherwise it is skipped.\nThe script conditions take the name of a script from the same execution, the script waits for it to finish. If there is no script with this name, the script fails.\nExpressions can compare values, e.g. \"{{outputs.branch}} != main\" or \"{{env.CI}} == true\", or check that a value is not empty.";
+pub(crate) const SLOW_SCRIPT_THRESHOLD_HELP_TEXT: &str = "Scripter remembers how long the latest successful runs of each script took (separately for different arguments) and shows the estimated time left for the execution.\nA running script is highlighted when it takes longer than this percentage of its usual duration, e.g. 200 highlights the scripts that run twice as long as usual. Leave empty to disable the highlighting.";
+pub(crate) const LOG_RETENTION_HELP_TEXT: &str = "Every execution writes its logs to a new folder in the logs folder. Old folders are removed when scripter starts and after each execution, leave all the fields empty to keep everything.\nThe oldest runs are removed first. Failed runs can be kept twice as long, and are removed last when there are too many runs or they take too much space. The logs of the executions that are still open are never removed.";
+pub(crate) const LOG_COMPRESSION_HELP_TEXT: &str = "Compress the log files of the finished executions to save disk space. The buttons to open the logs and the full log view keep working, the files are decompressed to a temporary folder when needed.";
+pub(crate) const LOG_FILE_FORMAT_HELP_TEXT: &str = "How the output of the scripts is written to the log files.\n\"Text with timestamps\" starts every line with the time and the stream it came from, e.g. \"[2025-01-31 23:59:59.123] [stderr] \".\n\"JSON lines\" writes one JSON object per line with \"timestamp\", \"stream\" (stdout, stderr, error or event), \"retry\" and \"text\" fields, for processing the logs with other tools.";
+pub(crate) const LOG_PATTERNS_HELP_TEXT: &str = "Lines of the script output that match a pattern are highlighted in the log, and the first of them are listed under the script in the execution list. A pattern can also fail the script even if it exited with a successful exit code.\nThe patterns are regular expressions (e.g. \"^error(\\[E\\d+\\])?:\" or \"(?i)\\bwarning\\b\"). The rules of the script are checked before the global ones from the settings, the first matching rule is used.";
+pub(crate) const GLOBAL_LOG_PATTERNS_HELP_TEXT: &str = "Patterns that are checked for the output of all the scripts, after the patterns of the script itself. Matching lines are highlighted in the log and listed under the script in the execution list, a pattern can also fail the script even if it exited with a successful exit code.\nThe patterns are regular expressions with the common syntax: \".\", \"[a-z]\", \"\\d\", \"\\w\", \"\\s\", \"\\b\", \"^\", \"$\", \"(a|b)\", \"*\", \"+\", \"?\", \"{n,m}\". Start a pattern with \"(?i)\" to ignore the case.";
+pub(crate) const EDITOR_COMMAND_HELP_TEXT: &str = "File references in the script output like \"src/main.rs:42:7\" can be clicked in the log to open the file. The paths are relative to the working directory of the script.\nThe command gets {file}, {line} and {column} replaced, e.g. \"code -g {file}:{line}:{column}\" or \"subl {file}:{line}\". Put the parts with spaces in double quotes. When it is empty, the file is opened with the default application.";
 pub(crate) const IGNORE_OUTPUT_HELP_TEXT: &str = "Avoid attaching to stdout/stderr of the script which is used for reading the logs. Useful in cases when you want to run a script that detaches its child when you don't want it to be blocked by io redirections.";
+pub(crate) const KEEP_CHILD_PROCESSES_HELP_TEXT: &str = "By default, stopping the script or its timeout stops all the processes that it started, including the ones running in the background. Enable this to stop only the script itself and leave the rest running, e.g. for a script that starts a server that should outlive it.\nIf such processes keep writing to the output of the script, also enable \"Ignore output\", otherwise the script is finished only when they exit.";
 pub(crate) const WINDOW_STATUS_REACTIONS_HELP_TEXT: &str =
     "Allow blinking the icon in the task bar when the execution is finished.";
 pub(crate) const KEEP_WINDOW_SIZE_HELP_TEXT: &str = "Disallow changing the size of the window when entering the Focus mode.\nCan be useful when used with tiled window managers.";
@@ -80,6 +103,98 @@ pub(crate) const CONFIG_REACTION_TO_PREVIOUS_FAILURES_PICK_LIST:
     config::ReactionToPreviousFailures::SkipOnSuccessExecuteOnFailureTurnToSuccess,
 ];
 
+pub(crate) const EXIT_CODE_RESULT_PICK_LIST: &[config::ExitCodeResult] = &[
+    config::ExitCodeResult::Success,
+    config::ExitCodeResult::Warning,
+    config::ExitCodeResult::Skipped,
+    config::ExitCodeResult::Failure,
+];
+
+pub(crate) const RUN_CONDITION_TYPE_PICK_LIST: &[config::RunConditionType] = &[
+    config::RunConditionType::ScriptSucceeded,
+    config::RunConditionType::ScriptFailed,
+    config::RunConditionType::Expression,
+];
+
+pub(crate) const LOG_PATTERN_SEVERITY_PICK_LIST: &[config::LogPatternSeverity] = &[
+    config::LogPatternSeverity::Error,
+    config::LogPatternSeverity::Warning,
+    config::LogPatternSeverity::Info,
+];
+
+// the same editing is used for the rules of a script and the global rules
+#[derive(Debug, Clone)]
+pub(crate) enum LogPatternRuleEdit {
+    Add,
+    Remove(usize),
+    EditPattern(usize, String),
+    EditSeverity(usize, config::LogPatternSeverity),
+    ToggleFailsScript(usize, bool),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ScheduleTriggerKind {
+    Interval,
+    Cron,
+}
+
+pub(crate) const SCHEDULE_TRIGGER_KIND_PICK_LIST: &[ScheduleTriggerKind] =
+    &[ScheduleTriggerKind::Interval, ScheduleTriggerKind::Cron];
+
+impl std::fmt::Display for ScheduleTriggerKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                ScheduleTriggerKind::Interval => "Every N seconds",
+                ScheduleTriggerKind::Cron => "Cron expression",
+            }
+        )
+    }
+}
+
+pub(crate) const MISSED_RUN_POLICY_PICK_LIST: &[config::MissedRunPolicy] = &[
+    config::MissedRunPolicy::Skip,
+    config::MissedRunPolicy::RunOnce,
+];
+
+impl std::fmt::Display for config::MissedRunPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                config::MissedRunPolicy::Skip => "Skip missed runs",
+                config::MissedRunPolicy::RunOnce => "Run once after missed runs",
+            }
+        )
+    }
+}
+
+pub(crate) const FILE_WATCH_RUNNING_SCRIPT_POLICY_PICK_LIST:
+    &[config::FileWatchRunningScriptPolicy] = &[
+    config::FileWatchRunningScriptPolicy::Restart,
+    config::FileWatchRunningScriptPolicy::Queue,
+    config::FileWatchRunningScriptPolicy::Ignore,
+];
+
+impl std::fmt::Display for config::FileWatchRunningScriptPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                config::FileWatchRunningScriptPolicy::Restart =>
+                    "Stop the running script and restart",
+                config::FileWatchRunningScriptPolicy::Queue => "Run again after the running script",
+                config::FileWatchRunningScriptPolicy::Ignore =>
+                    "Ignore the changes, the script makes them itself",
+            }
+        )
+    }
+}
+
 impl std::fmt::Display for config::ReactionToPreviousFailures {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -100,6 +215,49 @@ impl std::fmt::Display for config::ReactionToPreviousFailures {
     }
 }
 
+impl std::fmt::Display for config::ExitCodeResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                config::ExitCodeResult::Success => "Success",
+                config::ExitCodeResult::Warning => "Warning",
+                config::ExitCodeResult::Skipped => "Skip",
+                config::ExitCodeResult::Failure => "Failure",
+            }
+        )
+    }
+}
+
+impl std::fmt::Display for config::LogPatternSeverity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                config::LogPatternSeverity::Error => "Error",
+                config::LogPatternSeverity::Warning => "Warning",
+                config::LogPatternSeverity::Info => "Info",
+            }
+        )
+    }
+}
+
+impl std::fmt::Display for config::RunConditionType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                config::RunConditionType::ScriptSucceeded => "Script succeeded",
+                config::RunConditionType::ScriptFailed => "Script failed",
+                config::RunConditionType::Expression => "Expression is true",
+            }
+        )
+    }
+}
+
 pub fn edit_button(label: &str, message: WindowMessage) -> Button<'_, WindowMessage> {
     button(text(label).align_y(alignment::Vertical::Center).size(16))
         .padding(4)
@@ -131,6 +289,176 @@ pub fn populate_quick_launch_edit_button<'a>(
     }
 }
 
+pub fn populate_schedule_editing_content(
+    content: &mut Vec<Element<'_, WindowMessage, Theme, iced::Renderer>>,
+    app_config: &config::AppConfig,
+    visual_caches: &VisualCaches,
+    script_uid: &config::Guid,
+    theme: &Theme,
+) {
+    content.push(horizontal_rule(SEPARATOR_HEIGHT).into());
+    content.push(
+        row![
+            text("Schedules:"),
+            Space::with_width(4),
+            help_icon(SCHEDULE_HELP_TEXT, visual_caches, theme),
+        ]
+        .into(),
+    );
+
+    let schedules = &config::get_main_rewritable_config(app_config).schedules;
+    for (schedule_idx, schedule) in schedules
+        .iter()
+        .enumerate()
+        .filter(|(_, schedule)| schedule.script_uid == *script_uid)
+    {
+        let (trigger_kind, trigger_input) = match &schedule.trigger {
+            config::ScheduleTrigger::Interval { interval_sec } => (
+                ScheduleTriggerKind::Interval,
+                text_input(
+                    "seconds",
+                    &if *interval_sec > 0 {
+                        interval_sec.to_string()
+                    } else {
+                        String::new()
+                    },
+                )
+                .on_input(move |new_value| {
+                    WindowMessage::EditScheduleInterval(schedule_idx, new_value)
+                }),
+            ),
+            config::ScheduleTrigger::Cron { expression } => (
+                ScheduleTriggerKind::Cron,
+                text_input("0 18 * * mon-fri", expression).on_input(move |new_value| {
+                    WindowMessage::EditScheduleCronExpression(schedule_idx, new_value)
+                }),
+            ),
+        };
+
+        content.push(
+            pick_list(
+                SCHEDULE_TRIGGER_KIND_PICK_LIST,
+                Some(trigger_kind),
+                move |value| WindowMessage::EditScheduleTriggerKind(schedule_idx, value),
+            )
+            .into(),
+        );
+        content.push(trigger_input.padding(5).into());
+        if let config::ScheduleTrigger::Cron { expression } = &schedule.trigger {
+            if let Err(error) = schedules::parse_cron_expression(expression) {
+                content.push(
+                    text(error)
+                        .color(theme.extended_palette().danger.base.color)
+                        .into(),
+                );
+            }
+        }
+        content.push(
+            pick_list(
+                MISSED_RUN_POLICY_PICK_LIST,
+                Some(schedule.missed_run_policy),
+                move |value| WindowMessage::EditScheduleMissedRunPolicy(schedule_idx, value),
+            )
+            .into(),
+        );
+        content.push(
+            edit_button(
+                "Remove schedule",
+                WindowMessage::RemoveScriptSchedule(schedule_idx),
+            )
+            .into(),
+        );
+    }
+
+    if let Some(next_run_time) = visual_caches.next_scheduled_runs.get(script_uid) {
+        content.push(
+            text(format!(
+                "Next run: {}",
+                next_run_time.format("%Y-%m-%d %H:%M:%S")
+            ))
+            .into(),
+        );
+    }
+    content.push(
+        edit_button(
+            "Add schedule",
+            WindowMessage::AddScriptSchedule(script_uid.clone()),
+        )
+        .into(),
+    );
+}
+
+pub fn populate_file_watch_editing_content(
+    content: &mut Vec<Element<'_, WindowMessage, Theme, iced::Renderer>>,
+    app_config: &config::AppConfig,
+    visual_caches: &VisualCaches,
+    script_uid: &config::Guid,
+    theme: &Theme,
+) {
+    content.push(horizontal_rule(SEPARATOR_HEIGHT).into());
+    content.push(
+        row![
+            text("Run on file changes:"),
+            Space::with_width(4),
+            help_icon(FILE_WATCH_HELP_TEXT, visual_caches, theme),
+        ]
+        .into(),
+    );
+
+    let file_watches = &config::get_main_rewritable_config(app_config).file_watches;
+    for (file_watch_idx, file_watch) in file_watches
+        .iter()
+        .enumerate()
+        .filter(|(_, file_watch)| file_watch.script_uid == *script_uid)
+    {
+        content.push(text("Watched paths:").into());
+        populate_string_vec_edit_content(content, &file_watch.paths, move |idx, new_value| {
+            WindowMessage::EditFileWatchPath(file_watch_idx, new_value, idx)
+        });
+        content.push(text("Ignored paths:").into());
+        populate_string_vec_edit_content(
+            content,
+            &file_watch.ignored_paths,
+            move |idx, new_value| {
+                WindowMessage::EditFileWatchIgnoredPath(file_watch_idx, new_value, idx)
+            },
+        );
+        content.push(text("Debounce (milliseconds):").into());
+        content.push(
+            text_input("0", &file_watch.debounce_ms.to_string())
+                .on_input(move |new_value| {
+                    WindowMessage::EditFileWatchDebounce(file_watch_idx, new_value)
+                })
+                .padding(5)
+                .into(),
+        );
+        content.push(text("If the script is still running:").into());
+        content.push(
+            pick_list(
+                FILE_WATCH_RUNNING_SCRIPT_POLICY_PICK_LIST,
+                Some(file_watch.running_script_policy),
+                move |value| WindowMessage::EditFileWatchRunningScriptPolicy(file_watch_idx, value),
+            )
+            .into(),
+        );
+        content.push(
+            edit_button(
+                "Stop watching",
+                WindowMessage::RemoveScriptFileWatch(file_watch_idx),
+            )
+            .into(),
+        );
+    }
+
+    content.push(
+        edit_button(
+            "Add watched files",
+            WindowMessage::AddScriptFileWatch(script_uid.clone()),
+        )
+        .into(),
+    );
+}
+
 pub fn populate_path_editing_content(
     hint: &str,
     path: &config::PathConfig,
@@ -286,6 +614,7 @@ pub fn get_pane_name_from_variant(variant: &PaneVariant) -> &str {
         PaneVariant::ExecutionList => "Execution",
         PaneVariant::LogOutput => "Log preview",
         PaneVariant::Parameters => "Parameters",
+        PaneVariant::History => "History",
     }
 }
 
@@ -552,6 +881,149 @@ pub fn populate_argument_placeholders_config_content<'a>(
     );
 }
 
+pub fn populate_env_vars_config_content<'a>(
+    content: &mut Vec<Element<'a, WindowMessage, Theme, iced::Renderer>>,
+    env_vars: &[config::EnvironmentVariable],
+    config_script_id: ConfigScriptId,
+) {
+    for (i, env_var) in env_vars.iter().enumerate() {
+        content.push(
+            row![
+                text_input("NAME", &env_var.name)
+                    .on_input(move |new_value| {
+                        WindowMessage::EditScriptEnvVarName(config_script_id, i, new_value)
+                    })
+                    .padding(5),
+                text_input("value", &env_var.value)
+                    .on_input(move |new_value| {
+                        WindowMessage::EditScriptEnvVarValue(config_script_id, i, new_value)
+                    })
+                    .padding(5),
+                button("x").on_press(WindowMessage::RemoveScriptEnvVar(config_script_id, i)),
+            ]
+            .into(),
+        );
+    }
+
+    content.push(
+        button("+")
+            .on_press(WindowMessage::AddScriptEnvVar(config_script_id))
+            .into(),
+    );
+}
+
+pub fn populate_exit_codes_config_content<'a>(
+    content: &mut Vec<Element<'a, WindowMessage, Theme, iced::Renderer>>,
+    exit_codes: &[config::ExitCodeReaction],
+    config_script_id: ConfigScriptId,
+) {
+    for (i, exit_code) in exit_codes.iter().enumerate() {
+        content.push(
+            row![
+                text_input("exit code", &exit_code.exit_code.to_string())
+                    .on_input(move |new_value| {
+                        WindowMessage::EditScriptExitCode(config_script_id, i, new_value)
+                    })
+                    .padding(5),
+                pick_list(
+                    EXIT_CODE_RESULT_PICK_LIST,
+                    Some(exit_code.result),
+                    move |val| {
+                        WindowMessage::EditScriptExitCodeResult(config_script_id, i, val)
+                    }
+                ),
+                button("x").on_press(WindowMessage::RemoveScriptExitCode(config_script_id, i)),
+            ]
+            .into(),
+        );
+    }
+
+    content.push(
+        button("+")
+            .on_press(WindowMessage::AddScriptExitCode(config_script_id))
+            .into(),
+    );
+}
+
+pub fn populate_run_conditions_config_content<'a>(
+    content: &mut Vec<Element<'a, WindowMessage, Theme, iced::Renderer>>,
+    run_conditions: &[config::RunCondition],
+    config_script_id: ConfigScriptId,
+) {
+    for (i, run_condition) in run_conditions.iter().enumerate() {
+        let placeholder = match run_condition.condition_type {
+            config::RunConditionType::ScriptSucceeded | config::RunConditionType::ScriptFailed => {
+                "script name"
+            }
+            config::RunConditionType::Expression => "{{outputs.NAME}} == value",
+        };
+        content.push(
+            row![
+                pick_list(
+                    RUN_CONDITION_TYPE_PICK_LIST,
+                    Some(run_condition.condition_type),
+                    move |val| WindowMessage::EditScriptRunConditionType(config_script_id, i, val)
+                ),
+                text_input(placeholder, &run_condition.value)
+                    .on_input(move |new_value| {
+                        WindowMessage::EditScriptRunConditionValue(config_script_id, i, new_value)
+                    })
+                    .padding(5),
+                button("x").on_press(WindowMessage::RemoveScriptRunCondition(config_script_id, i)),
+            ]
+            .into(),
+        );
+    }
+
+    content.push(
+        button("+")
+            .on_press(WindowMessage::AddScriptRunCondition(config_script_id))
+            .into(),
+    );
+}
+
+pub fn populate_log_pattern_rules_content<'a>(
+    content: &mut Vec<Element<'a, WindowMessage, Theme, iced::Renderer>>,
+    rules: &[config::LogPatternRule],
+    edit_fn: impl Fn(LogPatternRuleEdit) -> WindowMessage + 'static + Copy,
+) {
+    for (i, rule) in rules.iter().enumerate() {
+        let is_pattern_valid =
+            rule.pattern.is_empty() || log_patterns::compile_pattern(&rule.pattern).is_ok();
+        content.push(
+            row![
+                text_input("pattern", &rule.pattern)
+                    .on_input(move |new_value| {
+                        edit_fn(LogPatternRuleEdit::EditPattern(i, new_value))
+                    })
+                    .padding(5)
+                    .style(if is_pattern_valid {
+                        text_input::default
+                    } else {
+                        style::invalid_text_input_style
+                    }),
+                pick_list(
+                    LOG_PATTERN_SEVERITY_PICK_LIST,
+                    Some(rule.severity),
+                    move |val| { edit_fn(LogPatternRuleEdit::EditSeverity(i, val)) }
+                ),
+                checkbox("Fails", rule.fails_script)
+                    .on_toggle(move |val| edit_fn(LogPatternRuleEdit::ToggleFailsScript(i, val))),
+                button("x").on_press(edit_fn(LogPatternRuleEdit::Remove(i))),
+            ]
+            .spacing(4)
+            .align_y(Alignment::Center)
+            .into(),
+        );
+    }
+
+    content.push(
+        button("+")
+            .on_press(edit_fn(LogPatternRuleEdit::Add))
+            .into(),
+    );
+}
+
 pub fn populate_argument_placeholders_content<'a>(
     content: &mut Vec<Element<'a, WindowMessage, Theme, iced::Renderer>>,
     argument_placeholders: &Vec<config::ArgumentPlaceholder>,
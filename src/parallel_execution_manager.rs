@@ -3,10 +3,12 @@
 
 use chrono;
 use sparse_set_container::{SparseKey, SparseSet};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use crate::config;
+use crate::duration_history;
 use crate::execution_thread;
 use crate::file_utils;
 use crate::ring_buffer;
@@ -42,8 +44,10 @@ pub struct Execution {
     has_failed_scripts: bool,
     has_non_skipped_scripts: bool,
 
+    start_time: chrono::DateTime<chrono::Local>,
     log_directory: PathBuf,
     recent_logs: Arc<Mutex<execution_thread::LogBuffer>>,
+    script_outputs: Arc<Mutex<execution_thread::ScriptOutputs>>,
     currently_outputting_script: isize,
 }
 
@@ -57,12 +61,21 @@ pub struct Execution {
 pub struct ParallelExecutionManager {
     started_executions: SparseSet<Execution>,
     edited_scripts: Vec<execution_thread::ExecutionScript>,
+    duration_history: duration_history::DurationHistory,
+}
+
+pub struct ExecutionProgressEstimate {
+    pub time_left: Duration,
+    // from 0.0 to 1.0
+    pub completed_fraction: f32,
 }
 
 pub struct ScheduledScriptCacheRecord {
     pub script: execution_thread::ExecutionScript,
     pub tooltip: String,
     pub status: execution_thread::ScriptExecutionStatus,
+    // indexes of the scripts in the cache that this script waits for
+    pub dependencies: Vec<usize>,
 }
 
 impl Execution {
@@ -75,8 +88,10 @@ impl Execution {
             scheduled_scripts_cache: Vec::new(),
             has_failed_scripts: false,
             has_non_skipped_scripts: false,
+            start_time: chrono::Local::now(),
             log_directory: PathBuf::new(),
             recent_logs: Arc::new(Mutex::new(ring_buffer::RingBuffer::new(Default::default()))),
+            script_outputs: Arc::new(Mutex::new(execution_thread::ScriptOutputs::new())),
             currently_outputting_script: -1,
         }
     }
@@ -102,12 +117,15 @@ impl Execution {
         let first_cache_index = self.scheduled_scripts_cache.len();
         let is_already_started = !self.execution_lists.is_empty();
 
+        let dependencies = execution_thread::resolve_dependencies(&scripts_to_run);
+
         // we keep the cache to be able to display the list of scripts in the UI
         self.scheduled_scripts_cache
             .extend(
                 scripts_to_run
                     .iter()
-                    .map(|script| ScheduledScriptCacheRecord {
+                    .zip(dependencies)
+                    .map(|(script, dependencies)| ScheduledScriptCacheRecord {
                         script: script.clone(),
                         tooltip: get_tooltip_for_script(&script.original),
                         status: execution_thread::ScriptExecutionStatus {
@@ -115,7 +133,14 @@ impl Execution {
                             finish_time: None,
                             result: execution_thread::ScriptResultStatus::Success,
                             retry_count: 0,
+                            exit_statuses: Vec::new(),
+                            resource_usages: Vec::new(),
+                            problems: Vec::new(),
                         },
+                        dependencies: dependencies
+                            .into_iter()
+                            .map(|script_idx| first_cache_index + script_idx)
+                            .collect(),
                     }),
             );
         self.execution_lists.push(ExecutionList {
@@ -127,10 +152,9 @@ impl Execution {
         });
 
         if !is_already_started {
-            self.log_directory = file_utils::get_script_log_directory(
-                &app_config.paths.logs_path,
-                &chrono::Local::now(),
-            );
+            self.start_time = chrono::Local::now();
+            self.log_directory =
+                file_utils::get_script_log_directory(&app_config.paths.logs_path, &self.start_time);
 
             self.run_execution_list(app_config);
         }
@@ -158,6 +182,38 @@ impl Execution {
         }
     }
 
+    // the input goes to the same script that get_running_script_accepting_input returns
+    pub fn send_input_to_running_script(&self, input: execution_thread::ScriptInput) {
+        let Some(script_cache_idx) = self.find_running_script_accepting_input() else {
+            return;
+        };
+        let Some((list_idx, script_idx)) =
+            self.get_script_idx_from_script_cache_idx(script_cache_idx)
+        else {
+            return;
+        };
+        if let Some(input_sender) = self.execution_lists[list_idx]
+            .execution_data
+            .input_senders
+            .get(script_idx)
+        {
+            let _ = input_sender.send(input);
+        }
+    }
+
+    pub fn get_running_script_accepting_input(&self) -> Option<&ScheduledScriptCacheRecord> {
+        self.find_running_script_accepting_input()
+            .map(|script_cache_idx| &self.scheduled_scripts_cache[script_cache_idx])
+    }
+
+    fn find_running_script_accepting_input(&self) -> Option<usize> {
+        self.scheduled_scripts_cache.iter().position(|record| {
+            record.script.original.accept_input
+                && record.status.has_script_started()
+                && !record.status.has_script_finished()
+        })
+    }
+
     pub fn is_waiting_execution_to_finish(&self) -> bool {
         if self.execution_lists.is_empty() {
             return false;
@@ -174,10 +230,12 @@ impl Execution {
     }
 
     pub fn has_finished_execution(&self) -> bool {
-        if let Some(scheduled_script) = self.scheduled_scripts_cache.last() {
-            return scheduled_script.status.has_script_finished();
-        }
-        false
+        // with dependencies, the last script is not necessarily the last one to finish
+        !self.scheduled_scripts_cache.is_empty()
+            && self
+                .scheduled_scripts_cache
+                .iter()
+                .all(|record| record.status.has_script_finished())
     }
 
     pub fn has_potentially_editable_scripts(&self) -> bool {
@@ -185,13 +243,18 @@ impl Execution {
             return true;
         }
 
-        let Some(execution_list) = self.execution_lists.get(self.current_execution_list_index)
-        else {
+        if self.current_execution_list_index >= self.execution_lists.len() {
             return false;
-        };
+        }
 
-        execution_list.first_cache_index as isize + self.currently_outputting_script + 1
-            < self.scheduled_scripts_cache.len() as isize
+        // only the scripts after the last started one can be taken back for editing
+        self.scheduled_scripts_cache
+            .last()
+            .is_some_and(|record| !record.status.has_script_started())
+    }
+
+    pub fn get_start_time(&self) -> &chrono::DateTime<chrono::Local> {
+        &self.start_time
     }
 
     pub fn get_log_folder_path(&self) -> &PathBuf {
@@ -236,6 +299,72 @@ impl Execution {
         self.has_non_skipped_scripts
     }
 
+    // based on the usual durations of the scripts, None if some of the remaining scripts never succeeded before
+    pub fn get_progress_estimate(
+        &self,
+        duration_history: &duration_history::DurationHistory,
+    ) -> Option<ExecutionProgressEstimate> {
+        let now = Instant::now();
+        // when each script is expected to finish counting from now, following the longest chain of scripts
+        // that wait for each other (the limit of parallel scripts is not taken into account)
+        let mut finish_estimates = vec![Duration::ZERO; self.scheduled_scripts_cache.len()];
+        for execution_list in &self.execution_lists {
+            let list_start_estimate = finish_estimates
+                .iter()
+                .take(execution_list.first_cache_index)
+                .max()
+                .copied()
+                .unwrap_or_default();
+
+            let list_end = execution_list.first_cache_index
+                + execution_list.execution_data.scripts_to_run.len();
+            for cache_idx in execution_list.first_cache_index..list_end {
+                let Some(record) = self.scheduled_scripts_cache.get(cache_idx) else {
+                    break;
+                };
+                if record.status.has_script_finished() {
+                    continue;
+                }
+
+                let typical_duration =
+                    duration_history.get_typical_duration(&record.script.original)?;
+                finish_estimates[cache_idx] = match record.status.start_time {
+                    Some(start_time) => typical_duration.saturating_sub(now - start_time),
+                    None => {
+                        record
+                            .dependencies
+                            .iter()
+                            .filter_map(|dependency_idx| finish_estimates.get(*dependency_idx))
+                            .max()
+                            .copied()
+                            .unwrap_or_default()
+                            .max(list_start_estimate)
+                            + typical_duration
+                    }
+                };
+            }
+        }
+
+        let time_left = finish_estimates.into_iter().max().unwrap_or_default();
+        let elapsed_time = self
+            .scheduled_scripts_cache
+            .iter()
+            .filter_map(|record| record.status.start_time)
+            .min()
+            .map(|start_time| now - start_time)
+            .unwrap_or_default();
+        let total_time = elapsed_time + time_left;
+
+        Some(ExecutionProgressEstimate {
+            time_left,
+            completed_fraction: if total_time.is_zero() {
+                0.0
+            } else {
+                elapsed_time.as_secs_f32() / total_time.as_secs_f32()
+            },
+        })
+    }
+
     pub fn get_scheduled_scripts_cache(&self) -> &Vec<ScheduledScriptCacheRecord> {
         &self.scheduled_scripts_cache
     }
@@ -244,78 +373,97 @@ impl Execution {
         &mut self.scheduled_scripts_cache
     }
 
-    pub fn tick(&mut self, app_config: &config::AppConfig) -> ExecutionTickStatus {
-        let current_execution_list = &mut self.execution_lists[self.current_execution_list_index];
-        if let Some(rx) = &current_execution_list.execution_data.progress_receiver {
-            if let Ok(progress) = rx.try_recv() {
-                let script_local_idx = progress.0;
-                let script_status = progress.1;
+    pub fn tick(
+        &mut self,
+        app_config: &config::AppConfig,
+        duration_history: &mut duration_history::DurationHistory,
+    ) -> ExecutionTickStatus {
+        // scripts running in parallel can report several changes between the ticks
+        loop {
+            let current_execution_list =
+                &mut self.execution_lists[self.current_execution_list_index];
+            let Some(rx) = &current_execution_list.execution_data.progress_receiver else {
+                self.try_join_previous_execution_list_item_thread_and_start_the_next(app_config);
+                return ExecutionTickStatus::Continue;
+            };
+            let Ok(progress) = rx.try_recv() else {
+                return ExecutionTickStatus::Continue;
+            };
 
-                if !script_status.has_script_been_skipped() {
-                    self.has_non_skipped_scripts = true;
-                }
+            let script_local_idx = progress.0;
+            let script_status = progress.1;
 
-                if script_status.has_script_failed() {
-                    self.has_failed_scripts = true;
-                } else {
-                    if let Some(script) = current_execution_list
-                        .execution_data
-                        .scripts_to_run
-                        .get_mut(script_local_idx)
-                    {
-                        if execution_thread::should_turn_failure_to_success(
-                            script.original.reaction_to_previous_failures,
-                        ) {
-                            self.has_failed_scripts = false;
-                        }
-                    }
-                }
+            if !script_status.has_script_been_skipped() {
+                self.has_non_skipped_scripts = true;
+            }
 
-                let mut no_execution_progress_change = false;
-                // some script was disconnected to become editable
-                if script_status.has_script_been_disconnected() {
-                    // we only mark future scripts, no actual changes in the execution progress
-                    no_execution_progress_change = true;
-                }
+            let mut no_execution_progress_change = false;
+            // some script was disconnected to become editable
+            if script_status.has_script_been_disconnected() {
+                // we only mark future scripts, no actual changes in the execution progress
+                no_execution_progress_change = true;
+            }
 
-                let script_cache_idx = current_execution_list.first_cache_index + script_local_idx;
-
-                // some script was disconnected to become editable
-                if no_execution_progress_change {
-                    return if script_local_idx
-                        == current_execution_list.execution_data.scripts_to_run.len()
-                    {
-                        ExecutionTickStatus::DisconnectFinished
-                    } else {
-                        self.scheduled_scripts_cache[script_cache_idx].status = script_status;
-                        ExecutionTickStatus::Continue
-                    };
-                }
+            let script_cache_idx = current_execution_list.first_cache_index + script_local_idx;
+            let list_cache_range = current_execution_list.first_cache_index
+                ..current_execution_list.first_cache_index
+                    + current_execution_list.execution_data.scripts_to_run.len();
 
+            // some script was disconnected to become editable
+            if no_execution_progress_change {
+                if script_local_idx == current_execution_list.execution_data.scripts_to_run.len() {
+                    return ExecutionTickStatus::DisconnectFinished;
+                }
                 self.scheduled_scripts_cache[script_cache_idx].status = script_status;
+                continue;
+            }
 
-                self.currently_outputting_script = progress.0 as isize;
-
-                if self.scheduled_scripts_cache[script_cache_idx]
-                    .status
-                    .has_script_finished()
-                    && current_execution_list.execution_data.scripts_to_run.len()
-                        == script_local_idx + 1
+            // only the clean runs are used to estimate how long the script usually takes
+            if script_status.has_script_finished()
+                && script_status.retry_count == 0
+                && (script_status.result == execution_thread::ScriptResultStatus::Success
+                    || script_status.result == execution_thread::ScriptResultStatus::Warning)
+            {
+                if let (Some(start_time), Some(finish_time)) =
+                    (script_status.start_time, script_status.finish_time)
                 {
-                    self.current_execution_list_index += 1;
-                    self.try_join_previous_execution_list_item_thread_and_start_the_next(
-                        app_config,
+                    duration_history.add_duration(
+                        &self.scheduled_scripts_cache[script_cache_idx]
+                            .script
+                            .original,
+                        finish_time.duration_since(start_time),
                     );
                 }
+            }
 
-                if self.has_finished_execution() {
-                    return ExecutionTickStatus::ExecutionFinished;
-                }
+            self.scheduled_scripts_cache[script_cache_idx].status = script_status;
+            self.has_failed_scripts = has_unhandled_failures(
+                &self.scheduled_scripts_cache,
+                &self
+                    .execution_lists
+                    .iter()
+                    .map(|execution_list| execution_list.first_cache_index)
+                    .collect::<Vec<_>>(),
+            );
+
+            self.currently_outputting_script = progress.0 as isize;
+
+            // scripts of the list can finish in any order, so wait for all of them
+            if self.scheduled_scripts_cache[script_cache_idx]
+                .status
+                .has_script_finished()
+                && self.scheduled_scripts_cache[list_cache_range]
+                    .iter()
+                    .all(|record| record.status.has_script_finished())
+            {
+                self.current_execution_list_index += 1;
+                self.try_join_previous_execution_list_item_thread_and_start_the_next(app_config);
+            }
+
+            if self.has_finished_execution() {
+                return ExecutionTickStatus::ExecutionFinished;
             }
-        } else {
-            self.try_join_previous_execution_list_item_thread_and_start_the_next(app_config);
         }
-        ExecutionTickStatus::Continue
     }
 
     fn try_join_previous_execution_list_item_thread_and_start_the_next(
@@ -350,6 +498,7 @@ impl Execution {
             had_failures_before,
             &app_config,
             self.recent_logs.clone(),
+            self.script_outputs.clone(),
             execution_list.first_cache_index,
         );
     }
@@ -480,16 +629,16 @@ pub struct TickResult {
 }
 
 impl ParallelExecutionManager {
-    pub fn new() -> Self {
+    pub fn new(logs_path: &Path) -> Self {
         Self {
             started_executions: SparseSet::new(),
             edited_scripts: Vec::new(),
+            duration_history: duration_history::DurationHistory::load(logs_path),
         }
     }
 
-    pub fn add_script_to_edited_list(&mut self, script: config::OriginalScriptDefinition) {
-        self.get_edited_scripts_mut()
-            .push(execution_thread::ExecutionScript::from_original(script));
+    pub fn add_scripts_to_edited_list(&mut self, scripts: Vec<execution_thread::ExecutionScript>) {
+        execution_thread::append_scripts_to_list(self.get_edited_scripts_mut(), scripts);
     }
 
     pub fn add_execution_script_to_edited_list(
@@ -525,6 +674,10 @@ impl ParallelExecutionManager {
         &self.started_executions
     }
 
+    pub fn get_duration_history(&self) -> &duration_history::DurationHistory {
+        &self.duration_history
+    }
+
     pub fn start_new_execution(
         &mut self,
         app_config: &config::AppConfig,
@@ -547,23 +700,6 @@ impl ParallelExecutionManager {
         execution
     }
 
-    pub fn add_scripts_to_running_execution(
-        &mut self,
-        app_config: &config::AppConfig,
-        execution_index: ExecutionId,
-        scripts_to_add: Vec<config::OriginalScriptDefinition>,
-    ) {
-        if let Some(execution) = self.started_executions.get_mut(execution_index) {
-            execution.execute_scripts(
-                app_config,
-                scripts_to_add
-                    .into_iter()
-                    .map(|script| execution_thread::ExecutionScript::from_original(script))
-                    .collect(),
-            );
-        }
-    }
-
     pub fn add_execution_scripts_to_running_execution(
         &mut self,
         app_config: &config::AppConfig,
@@ -584,7 +720,7 @@ impl ParallelExecutionManager {
                 continue;
             }
 
-            let execution_tick_status = execution.tick(app_config);
+            let execution_tick_status = execution.tick(app_config, &mut self.duration_history);
             match execution_tick_status {
                 ExecutionTickStatus::Continue => {}
                 ExecutionTickStatus::ExecutionFinished => {
@@ -596,6 +732,10 @@ impl ParallelExecutionManager {
             }
         }
 
+        if !just_finished_executions.is_empty() {
+            self.duration_history.save();
+        }
+
         let has_just_disconnected_executions = !just_disconnected_executions.is_empty();
         for execution_id in just_disconnected_executions {
             self.move_not_started_execution_lists_to_edited_list(execution_id);
@@ -667,3 +807,126 @@ impl ParallelExecutionManager {
 fn get_tooltip_for_script(script: &config::OriginalScriptDefinition) -> String {
     execution_thread::get_script_to_execute_description(script)
 }
+
+// a failure is handled when a script that turns failures to success succeeded after it,
+// the failures are followed through the dependencies the same way the scripts see them,
+// so a script on an independent branch doesn't hide them
+fn has_unhandled_failures(
+    scheduled_scripts: &[ScheduledScriptCacheRecord],
+    list_first_cache_indexes: &[usize],
+) -> bool {
+    let mut has_failed = vec![false; scheduled_scripts.len()];
+    let mut had_failures_before = false;
+    for (list_idx, list_start) in list_first_cache_indexes.iter().enumerate() {
+        let list_end = list_first_cache_indexes
+            .get(list_idx + 1)
+            .copied()
+            .unwrap_or(scheduled_scripts.len())
+            .min(scheduled_scripts.len());
+        if *list_start >= list_end {
+            break;
+        }
+
+        // the failures that a finished script of the list waited for
+        let mut is_waited_for = vec![false; scheduled_scripts.len()];
+        let mut are_failures_before_waited_for = false;
+        for script_idx in *list_start..list_end {
+            let record = &scheduled_scripts[script_idx];
+            if !record.status.has_script_finished() {
+                continue;
+            }
+
+            let has_previous_script_failed = if record.dependencies.is_empty() {
+                are_failures_before_waited_for = true;
+                had_failures_before
+            } else {
+                record.dependencies.iter().any(|dependency_idx| {
+                    is_waited_for[*dependency_idx] = true;
+                    has_failed[*dependency_idx]
+                })
+            };
+            has_failed[script_idx] = match record.status.result {
+                execution_thread::ScriptResultStatus::Failed => true,
+                execution_thread::ScriptResultStatus::Success
+                | execution_thread::ScriptResultStatus::Warning
+                    if execution_thread::should_turn_failure_to_success(
+                        record.script.original.reaction_to_previous_failures,
+                    ) =>
+                {
+                    false
+                }
+                _ => has_previous_script_failed,
+            };
+        }
+
+        had_failures_before = (had_failures_before && !are_failures_before_waited_for)
+            || (*list_start..list_end)
+                .any(|script_idx| has_failed[script_idx] && !is_waited_for[script_idx]);
+    }
+    had_failures_before
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_record(
+        result: execution_thread::ScriptResultStatus,
+        reaction_to_previous_failures: config::ReactionToPreviousFailures,
+        dependencies: Vec<usize>,
+    ) -> ScheduledScriptCacheRecord {
+        ScheduledScriptCacheRecord {
+            script: execution_thread::ExecutionScript::from_original(
+                config::OriginalScriptDefinition {
+                    reaction_to_previous_failures,
+                    ..Default::default()
+                },
+            ),
+            tooltip: String::new(),
+            status: execution_thread::ScriptExecutionStatus {
+                start_time: Some(Instant::now()),
+                finish_time: Some(Instant::now()),
+                result,
+                retry_count: 0,
+                exit_statuses: Vec::new(),
+                resource_usages: Vec::new(),
+                problems: Vec::new(),
+            },
+            dependencies,
+        }
+    }
+
+    #[test]
+    fn test_failures_are_handled_only_by_scripts_that_wait_for_them() {
+        use config::ReactionToPreviousFailures::*;
+        use execution_thread::ScriptResultStatus::*;
+
+        // a script on a parallel branch doesn't hide the failure
+        let scripts = [
+            make_record(Failed, SkipOnFailure, vec![]),
+            make_record(Success, ExecuteOnSuccessOrFailureTurnToSuccess, vec![]),
+        ];
+        assert!(has_unhandled_failures(&scripts, &[0]));
+
+        // the script that waited for the failure handles it, but not for the other branch
+        let scripts = [
+            make_record(Failed, SkipOnFailure, vec![]),
+            make_record(Success, ExecuteOnSuccessOrFailureTurnToSuccess, vec![0]),
+            make_record(Skipped, SkipOnFailure, vec![0]),
+        ];
+        assert!(!has_unhandled_failures(&scripts[..2], &[0]));
+        assert!(has_unhandled_failures(&scripts, &[0]));
+
+        // the first scripts of the next list wait for the previous lists
+        let scripts = [
+            make_record(Failed, SkipOnFailure, vec![]),
+            make_record(Success, ExecuteOnSuccessOrFailureTurnToSuccess, vec![]),
+        ];
+        assert!(!has_unhandled_failures(&scripts, &[0, 1]));
+        let scripts = [
+            make_record(Failed, SkipOnFailure, vec![]),
+            make_record(Success, ExecuteOnSuccessOrFailure, vec![]),
+        ];
+        assert!(has_unhandled_failures(&scripts, &[0, 1]));
+    }
+}
@@ -0,0 +1,447 @@
+// Copyright (C) Pavel Grebnev 2025
+// Distributed under the MIT License (license terms are at http://opensource.org/licenses/MIT).
+
+use std::time::{Duration, Instant};
+
+// the processes of a running script are not read more often than this
+const SAMPLING_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResourceUsage {
+    pub wall_time: Duration,
+    // None if it can't be measured on this platform
+    pub cpu_time: Option<Duration>,
+    // the highest total resident memory of all the processes of the script at the same time
+    pub peak_memory_bytes: Option<u64>,
+}
+
+// samples the processes of a running script, on Linux and macOS these are the processes of its
+// process group and the ones that live shorter than the sampling interval can be missed,
+// on Windows these are the processes of a job object that the script process is added to
+pub struct ResourceUsageSampler {
+    #[cfg(not(target_os = "windows"))]
+    process_group_id: u32,
+    #[cfg(target_os = "windows")]
+    job: Option<JobObject>,
+    start_time: Instant,
+    last_sample_time: Option<Instant>,
+    cpu_time: Option<Duration>,
+    peak_memory_bytes: Option<u64>,
+}
+
+#[cfg_attr(
+    not(any(target_os = "linux", target_os = "macos", target_os = "windows")),
+    allow(dead_code)
+)]
+struct ProcessGroupSample {
+    cpu_time: Duration,
+    memory_bytes: u64,
+}
+
+impl ResourceUsageSampler {
+    // the script process is expected to be the leader of its own process group
+    pub fn new(child: &std::process::Child) -> Self {
+        Self {
+            #[cfg(not(target_os = "windows"))]
+            process_group_id: child.id(),
+            #[cfg(target_os = "windows")]
+            job: JobObject::new(child),
+            start_time: Instant::now(),
+            last_sample_time: None,
+            cpu_time: None,
+            peak_memory_bytes: None,
+        }
+    }
+
+    // can be called as often as needed, it skips the samples that are too close to the previous one
+    pub fn sample(&mut self) {
+        let now = Instant::now();
+        if self
+            .last_sample_time
+            .is_some_and(|last_sample_time| now - last_sample_time < SAMPLING_INTERVAL)
+        {
+            return;
+        }
+        self.last_sample_time = Some(now);
+
+        #[cfg(not(target_os = "windows"))]
+        let sample = sample_process_group(self.process_group_id);
+        #[cfg(target_os = "windows")]
+        let sample = self.job.as_ref().and_then(JobObject::sample);
+
+        if let Some(sample) = sample {
+            // the time of the finished processes moves to their parents, so the total only grows
+            // until the processes start to exit without being waited for
+            self.cpu_time = Some(
+                self.cpu_time
+                    .map_or(sample.cpu_time, |cpu_time| cpu_time.max(sample.cpu_time)),
+            );
+            self.peak_memory_bytes = Some(
+                self.peak_memory_bytes
+                    .map_or(sample.memory_bytes, |peak| peak.max(sample.memory_bytes)),
+            );
+        }
+    }
+
+    pub fn get_usage(&self) -> ResourceUsage {
+        ResourceUsage {
+            wall_time: self.start_time.elapsed(),
+            cpu_time: self.cpu_time,
+            peak_memory_bytes: self.peak_memory_bytes,
+        }
+    }
+}
+
+impl std::fmt::Display for ResourceUsage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "wall time {:.1}s", self.wall_time.as_secs_f32())?;
+        if let Some(cpu_time) = self.cpu_time {
+            write!(f, ", CPU time {:.1}s", cpu_time.as_secs_f32())?;
+        }
+        if let Some(peak_memory_bytes) = self.peak_memory_bytes {
+            write!(f, ", peak memory {}", format_memory_size(peak_memory_bytes))?;
+        }
+        Ok(())
+    }
+}
+
+fn format_memory_size(bytes: u64) -> String {
+    const KIB: u64 = 1024;
+    const MIB: u64 = 1024 * KIB;
+    const GIB: u64 = 1024 * MIB;
+    if bytes >= GIB {
+        format!("{:.1} GiB", bytes as f64 / GIB as f64)
+    } else if bytes >= MIB {
+        format!("{:.1} MiB", bytes as f64 / MIB as f64)
+    } else {
+        format!("{} KiB", bytes / KIB)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn sample_process_group(process_group_id: u32) -> Option<ProcessGroupSample> {
+    extern "C" {
+        fn sysconf(name: i32) -> i64;
+    }
+    const SC_CLK_TCK: i32 = 2;
+    const SC_PAGESIZE: i32 = 30;
+
+    let ticks_per_second = unsafe { sysconf(SC_CLK_TCK) };
+    let page_size = unsafe { sysconf(SC_PAGESIZE) };
+    if ticks_per_second <= 0 || page_size <= 0 {
+        return None;
+    }
+
+    let mut cpu_ticks = 0;
+    let mut resident_pages = 0;
+    let mut has_found_processes = false;
+    for entry in std::fs::read_dir("/proc").ok()?.flatten() {
+        let file_name = entry.file_name();
+        let Some(pid) = file_name.to_str().and_then(|name| name.parse::<u32>().ok()) else {
+            continue;
+        };
+        // the process could have exited since we listed the directory
+        let Ok(content) = std::fs::read_to_string(format!("/proc/{}/stat", pid)) else {
+            continue;
+        };
+        let Some(process_stat) = parse_process_stat(&content) else {
+            continue;
+        };
+        if process_stat.process_group_id != process_group_id {
+            continue;
+        }
+        has_found_processes = true;
+        cpu_ticks += process_stat.cpu_ticks;
+        resident_pages += process_stat.resident_pages;
+    }
+
+    if !has_found_processes {
+        return None;
+    }
+
+    Some(ProcessGroupSample {
+        cpu_time: Duration::from_secs_f64(cpu_ticks as f64 / ticks_per_second as f64),
+        memory_bytes: resident_pages * page_size as u64,
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn sample_process_group(process_group_id: u32) -> Option<ProcessGroupSample> {
+    // struct rusage_info_v2 from <sys/resource.h>
+    #[repr(C)]
+    #[derive(Default)]
+    #[allow(dead_code)]
+    struct RusageInfoV2 {
+        uuid: [u8; 16],
+        user_time: u64,
+        system_time: u64,
+        pkg_idle_wkups: u64,
+        interrupt_wkups: u64,
+        pageins: u64,
+        wired_size: u64,
+        resident_size: u64,
+        phys_footprint: u64,
+        proc_start_abstime: u64,
+        proc_exit_abstime: u64,
+        child_user_time: u64,
+        child_system_time: u64,
+        child_pkg_idle_wkups: u64,
+        child_interrupt_wkups: u64,
+        child_pageins: u64,
+        child_elapsed_abstime: u64,
+        diskio_bytesread: u64,
+        diskio_byteswritten: u64,
+    }
+    #[repr(C)]
+    #[derive(Default)]
+    struct MachTimebaseInfo {
+        numer: u32,
+        denom: u32,
+    }
+    extern "C" {
+        fn proc_listpids(list_type: u32, type_info: u32, buffer: *mut i32, buffer_size: i32)
+            -> i32;
+        fn proc_pid_rusage(pid: i32, flavor: i32, buffer: *mut RusageInfoV2) -> i32;
+        fn mach_timebase_info(info: *mut MachTimebaseInfo) -> i32;
+    }
+    const PROC_PGRP_ONLY: u32 = 2;
+    const RUSAGE_INFO_V2: i32 = 2;
+
+    // the times are in mach absolute time units, which are not nanoseconds on Apple silicon
+    let mut timebase = MachTimebaseInfo::default();
+    let timebase_result = unsafe { mach_timebase_info(&mut timebase) };
+    if timebase_result != 0 || timebase.denom == 0 {
+        return None;
+    }
+
+    // the size of the list is checked first, with some room for the processes started meanwhile
+    let list_size =
+        unsafe { proc_listpids(PROC_PGRP_ONLY, process_group_id, std::ptr::null_mut(), 0) };
+    if list_size <= 0 {
+        return None;
+    }
+    let mut pids = vec![0i32; list_size as usize / std::mem::size_of::<i32>() + 16];
+    let list_size = unsafe {
+        proc_listpids(
+            PROC_PGRP_ONLY,
+            process_group_id,
+            pids.as_mut_ptr(),
+            (pids.len() * std::mem::size_of::<i32>()) as i32,
+        )
+    };
+    if list_size <= 0 {
+        return None;
+    }
+    pids.truncate(list_size as usize / std::mem::size_of::<i32>());
+
+    let mut cpu_time_units = 0u128;
+    let mut memory_bytes = 0;
+    let mut has_found_processes = false;
+    for pid in pids.into_iter().filter(|pid| *pid > 0) {
+        let mut usage = RusageInfoV2::default();
+        // the process could have exited since we listed the processes
+        if unsafe { proc_pid_rusage(pid, RUSAGE_INFO_V2, &mut usage) } != 0 {
+            continue;
+        }
+        has_found_processes = true;
+        // including the time of the finished children that the process waited for
+        cpu_time_units +=
+            (usage.user_time + usage.system_time + usage.child_user_time + usage.child_system_time)
+                as u128;
+        memory_bytes += usage.resident_size;
+    }
+
+    if !has_found_processes {
+        return None;
+    }
+
+    Some(ProcessGroupSample {
+        cpu_time: Duration::from_nanos(
+            (cpu_time_units * timebase.numer as u128 / timebase.denom as u128) as u64,
+        ),
+        memory_bytes,
+    })
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn sample_process_group(_process_group_id: u32) -> Option<ProcessGroupSample> {
+    // only the wall time is measured on the other platforms
+    None
+}
+
+#[cfg(target_os = "windows")]
+extern "system" {
+    fn CreateJobObjectW(
+        job_attributes: *mut std::ffi::c_void,
+        name: *const u16,
+    ) -> *mut std::ffi::c_void;
+    fn AssignProcessToJobObject(job: *mut std::ffi::c_void, process: *mut std::ffi::c_void) -> i32;
+    fn QueryInformationJobObject(
+        job: *mut std::ffi::c_void,
+        information_class: i32,
+        information: *mut std::ffi::c_void,
+        information_length: u32,
+        return_length: *mut u32,
+    ) -> i32;
+    fn CloseHandle(handle: *mut std::ffi::c_void) -> i32;
+}
+
+// JOBOBJECT_BASIC_ACCOUNTING_INFORMATION, the times are in 100 ns intervals
+#[cfg(target_os = "windows")]
+#[repr(C)]
+#[derive(Default)]
+#[allow(dead_code)]
+struct JobBasicAccountingInformation {
+    total_user_time: i64,
+    total_kernel_time: i64,
+    this_period_total_user_time: i64,
+    this_period_total_kernel_time: i64,
+    total_page_fault_count: u32,
+    total_processes: u32,
+    active_processes: u32,
+    total_terminated_processes: u32,
+}
+
+// JOBOBJECT_EXTENDED_LIMIT_INFORMATION
+#[cfg(target_os = "windows")]
+#[repr(C)]
+#[derive(Default)]
+#[allow(dead_code)]
+struct JobExtendedLimitInformation {
+    per_process_user_time_limit: i64,
+    per_job_user_time_limit: i64,
+    limit_flags: u32,
+    minimum_working_set_size: usize,
+    maximum_working_set_size: usize,
+    active_process_limit: u32,
+    affinity: usize,
+    priority_class: u32,
+    scheduling_class: u32,
+    io_counters: [u64; 6],
+    process_memory_limit: usize,
+    job_memory_limit: usize,
+    peak_process_memory_used: usize,
+    peak_job_memory_used: usize,
+}
+
+// the job keeps the totals of the processes that already exited, so nothing is missed between the
+// samples, except the processes that the script starts before it is added to the job
+#[cfg(target_os = "windows")]
+struct JobObject {
+    handle: *mut std::ffi::c_void,
+}
+
+#[cfg(target_os = "windows")]
+impl JobObject {
+    fn new(child: &std::process::Child) -> Option<Self> {
+        use std::os::windows::io::AsRawHandle;
+
+        let handle = unsafe { CreateJobObjectW(std::ptr::null_mut(), std::ptr::null()) };
+        if handle.is_null() {
+            return None;
+        }
+        let job = Self { handle };
+        // fails if the process has already exited
+        let assign_result = unsafe { AssignProcessToJobObject(job.handle, child.as_raw_handle()) };
+        (assign_result != 0).then_some(job)
+    }
+
+    fn sample(&self) -> Option<ProcessGroupSample> {
+        const JOB_OBJECT_BASIC_ACCOUNTING_INFORMATION: i32 = 1;
+        const JOB_OBJECT_EXTENDED_LIMIT_INFORMATION: i32 = 9;
+
+        let mut accounting_information = JobBasicAccountingInformation::default();
+        let mut limit_information = JobExtendedLimitInformation::default();
+        let has_queried_information = unsafe {
+            QueryInformationJobObject(
+                self.handle,
+                JOB_OBJECT_BASIC_ACCOUNTING_INFORMATION,
+                &mut accounting_information as *mut _ as *mut std::ffi::c_void,
+                std::mem::size_of::<JobBasicAccountingInformation>() as u32,
+                std::ptr::null_mut(),
+            ) != 0
+                && QueryInformationJobObject(
+                    self.handle,
+                    JOB_OBJECT_EXTENDED_LIMIT_INFORMATION,
+                    &mut limit_information as *mut _ as *mut std::ffi::c_void,
+                    std::mem::size_of::<JobExtendedLimitInformation>() as u32,
+                    std::ptr::null_mut(),
+                ) != 0
+        };
+        if !has_queried_information {
+            return None;
+        }
+
+        let cpu_time_intervals =
+            accounting_information.total_user_time + accounting_information.total_kernel_time;
+        Some(ProcessGroupSample {
+            cpu_time: Duration::from_nanos(cpu_time_intervals.max(0) as u64 * 100),
+            // this is the committed memory rather than the resident one, the peak is kept by the job
+            memory_bytes: limit_information.peak_job_memory_used as u64,
+        })
+    }
+}
+
+#[cfg(target_os = "windows")]
+impl Drop for JobObject {
+    fn drop(&mut self) {
+        // the processes keep running, the job doesn't have the kill on close limit
+        unsafe {
+            CloseHandle(self.handle);
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+struct ProcessStat {
+    process_group_id: u32,
+    // including the time of the finished children that the process waited for
+    cpu_ticks: u64,
+    resident_pages: u64,
+}
+
+// parses the content of /proc/<pid>/stat
+#[cfg(target_os = "linux")]
+fn parse_process_stat(content: &str) -> Option<ProcessStat> {
+    // the process name is in parentheses and can contain spaces and parentheses itself
+    let fields: Vec<&str> = content[content.rfind(')')? + 1..]
+        .split_whitespace()
+        .collect();
+    // the fields are counted from the state, which is the third field in the file
+    let get_field = |number: usize| fields.get(number - 3)?.parse::<u64>().ok();
+
+    Some(ProcessStat {
+        process_group_id: get_field(5)? as u32,
+        cpu_ticks: get_field(14)? + get_field(15)? + get_field(16)? + get_field(17)?,
+        resident_pages: get_field(24)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_process_stat_is_parsed_with_spaces_in_process_name() {
+        let content = "4242 (my (build) tool) S 4200 4242 4200 0 -1 4194560 1000 0 0 0 \
+            150 50 30 20 20 0 1 0 12345 104857600 2560 18446744073709551615 1 1 0 0 0 0 0 0 0 0 0 0 17 3 0 0 0 0 0";
+        let process_stat = parse_process_stat(content).unwrap();
+        assert_eq!(process_stat.process_group_id, 4242);
+        assert_eq!(process_stat.cpu_ticks, 250);
+        assert_eq!(process_stat.resident_pages, 2560);
+    }
+
+    #[test]
+    fn test_resource_usage_is_described_with_readable_units() {
+        let resource_usage = ResourceUsage {
+            wall_time: Duration::from_millis(12340),
+            cpu_time: Some(Duration::from_millis(8100)),
+            peak_memory_bytes: Some(1536 * 1024 * 1024),
+        };
+        assert_eq!(
+            resource_usage.to_string(),
+            "wall time 12.3s, CPU time 8.1s, peak memory 1.5 GiB"
+        );
+    }
+}
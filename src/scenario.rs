@@ -35,6 +35,7 @@ pub struct Script {
     pub name: Option<String>,
     pub arguments: Option<String>,
     pub placeholders: Option<HashMap<String, String>>,
+    pub timeout_sec: Option<f32>,
 }
 
 impl Script {
@@ -44,6 +45,7 @@ impl Script {
             name: None,
             arguments: None,
             placeholders: None,
+            timeout_sec: None,
         }
     }
 }
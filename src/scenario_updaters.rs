@@ -5,7 +5,7 @@ use crate::json_file_updater::{JsonFileUpdater, UpdateResult};
 use serde_json::Value as JsonValue;
 
 static FORMAT_VERSION_FIELD_NAME: &str = "format_version";
-pub static LATEST_SCENARIO_FORMAT_VERSION: &str = "5";
+pub static LATEST_SCENARIO_FORMAT_VERSION: &str = "6";
 
 pub fn update_scenario_to_the_latest_version(scenario_json: &mut JsonValue) -> UpdateResult {
     let version = scenario_json[FORMAT_VERSION_FIELD_NAME].as_str();
@@ -43,6 +43,11 @@ fn register_scenario_updaters() -> JsonFileUpdater {
         |_| {},
         v5_validate_no_start_focused_before,
     );
+    json_scenario_updater.add_update_function_with_validator(
+        "6",
+        |_| {},
+        v6_validate_no_timeout_before,
+    );
     // add update functions above this line
     // don't forget to update LATEST_SCENARIO_FORMAT_VERSION at the beginning of the file
 
@@ -130,3 +135,13 @@ fn v5_validate_no_start_focused_before(json: &JsonValue) -> Result<(), String> {
 
     Ok(())
 }
+
+fn v6_validate_no_timeout_before(json: &JsonValue) -> Result<(), String> {
+    for_each_script_validate(json, |script| {
+        if script["timeout_sec"].is_number() {
+            return get_wrong_field_version_err("timeout_sec", "6");
+        }
+
+        Ok(())
+    })
+}
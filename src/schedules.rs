@@ -0,0 +1,417 @@
+// Copyright (C) Pavel Grebnev 2025
+// Distributed under the MIT License (license terms are at http://opensource.org/licenses/MIT).
+
+use crate::config;
+use crate::parallel_execution_manager::ExecutionId;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDateTime, TimeZone, Timelike};
+use std::collections::HashMap;
+
+// if a run is started later than this, we assume it was missed (e.g. the computer was asleep)
+const MISSED_RUN_THRESHOLD_SEC: i64 = 60;
+// no expression needs to look further than this, it also protects from dates like February 30
+const MAX_CRON_SEARCH_DAYS: i64 = 366 * 5;
+
+const MONTH_NAMES: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const DAY_OF_WEEK_NAMES: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+#[derive(Debug, PartialEq)]
+pub struct CronExpression {
+    // bit N is set if the value N matches
+    minutes: u64,
+    hours: u32,
+    days_of_month: u32,
+    months: u16,
+    // 0 is Sunday
+    days_of_week: u8,
+    // when both days are restricted, a day matching either of them is enough (as in cron)
+    is_day_of_month_restricted: bool,
+    is_day_of_week_restricted: bool,
+}
+
+pub struct Scheduler {
+    states: Vec<ScheduleState>,
+}
+
+struct ScheduleState {
+    schedule: config::ScriptSchedule,
+    next_run_time: Option<DateTime<Local>>,
+    // a run that should start as soon as the previous run of this schedule finishes
+    has_postponed_run: bool,
+    last_execution_id: Option<ExecutionId>,
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Self { states: Vec::new() }
+    }
+
+    // keeps the state of the schedules that didn't change, so editing one schedule
+    // doesn't reset the timers of the others
+    pub fn update_schedules(&mut self, schedules: &[config::ScriptSchedule], now: DateTime<Local>) {
+        if self.states.len() == schedules.len()
+            && self
+                .states
+                .iter()
+                .zip(schedules)
+                .all(|(state, schedule)| state.schedule == *schedule)
+        {
+            return;
+        }
+
+        let mut old_states = std::mem::take(&mut self.states);
+        for schedule in schedules {
+            let old_state_idx = old_states
+                .iter()
+                .position(|state| state.schedule == *schedule);
+            let state = match old_state_idx {
+                Some(idx) => old_states.swap_remove(idx),
+                None => ScheduleState {
+                    schedule: schedule.clone(),
+                    next_run_time: get_next_run_time(&schedule.trigger, None, now),
+                    has_postponed_run: false,
+                    last_execution_id: None,
+                },
+            };
+            self.states.push(state);
+        }
+    }
+
+    // returns the scripts that need to be started now, together with the index of their schedule
+    pub fn take_due_runs(
+        &mut self,
+        now: DateTime<Local>,
+        is_execution_running: impl Fn(ExecutionId) -> bool,
+    ) -> Vec<(usize, config::Guid)> {
+        let mut due_runs = Vec::new();
+        for (idx, state) in self.states.iter_mut().enumerate() {
+            let is_previous_run_active = state.last_execution_id.is_some_and(&is_execution_running);
+
+            if let Some(next_run_time) = state.next_run_time {
+                if next_run_time <= now {
+                    state.next_run_time =
+                        get_next_run_time(&state.schedule.trigger, Some(next_run_time), now);
+
+                    let is_missed = is_previous_run_active
+                        || (now - next_run_time).num_seconds() > MISSED_RUN_THRESHOLD_SEC;
+                    if !is_missed {
+                        due_runs.push((idx, state.schedule.script_uid.clone()));
+                        continue;
+                    }
+
+                    if state.schedule.missed_run_policy == config::MissedRunPolicy::RunOnce {
+                        state.has_postponed_run = true;
+                    }
+                }
+            }
+
+            if state.has_postponed_run && !is_previous_run_active {
+                state.has_postponed_run = false;
+                due_runs.push((idx, state.schedule.script_uid.clone()));
+            }
+        }
+        due_runs
+    }
+
+    pub fn on_run_started(&mut self, schedule_idx: usize, execution_id: ExecutionId) {
+        if let Some(state) = self.states.get_mut(schedule_idx) {
+            state.last_execution_id = Some(execution_id);
+        }
+    }
+
+    // the closest next run for every script that has one
+    pub fn get_next_run_times(&self) -> HashMap<config::Guid, DateTime<Local>> {
+        let mut result: HashMap<config::Guid, DateTime<Local>> = HashMap::new();
+        for state in &self.states {
+            let Some(next_run_time) = state.next_run_time else {
+                continue;
+            };
+            result
+                .entry(state.schedule.script_uid.clone())
+                .and_modify(|time| *time = (*time).min(next_run_time))
+                .or_insert(next_run_time);
+        }
+        result
+    }
+}
+
+pub fn get_next_run_time(
+    trigger: &config::ScheduleTrigger,
+    previous_run_time: Option<DateTime<Local>>,
+    now: DateTime<Local>,
+) -> Option<DateTime<Local>> {
+    match trigger {
+        config::ScheduleTrigger::Interval { interval_sec } => {
+            if *interval_sec == 0 {
+                return None;
+            }
+            let interval = Duration::seconds(i64::try_from(*interval_sec).ok()?);
+            // keep the runs aligned to the first one instead of drifting with every tick
+            let Some(previous_run_time) = previous_run_time else {
+                return Some(now + interval);
+            };
+            let skipped_intervals =
+                (now - previous_run_time).num_seconds() / interval.num_seconds();
+            Some(previous_run_time + interval * (skipped_intervals as i32 + 1))
+        }
+        config::ScheduleTrigger::Cron { expression } => {
+            let expression = parse_cron_expression(expression).ok()?;
+            let mut after = now.naive_local();
+            // skip the times that don't exist because of daylight saving time changes
+            for _ in 0..4 {
+                let next_time = expression.get_next_matching_time(after)?;
+                if let Some(next_time) = Local.from_local_datetime(&next_time).earliest() {
+                    return Some(next_time);
+                }
+                after = next_time;
+            }
+            None
+        }
+    }
+}
+
+pub fn parse_cron_expression(expression: &str) -> Result<CronExpression, String> {
+    let expression = match expression.trim() {
+        "@yearly" | "@annually" => "0 0 1 1 *",
+        "@monthly" => "0 0 1 * *",
+        "@weekly" => "0 0 * * 0",
+        "@daily" | "@midnight" => "0 0 * * *",
+        "@hourly" => "0 * * * *",
+        expression => expression,
+    };
+
+    let fields: Vec<&str> = expression.split_whitespace().collect();
+    let [minutes, hours, days_of_month, months, days_of_week] = fields.as_slice() else {
+        return Err(format!(
+            "Expected 5 fields (minute hour day-of-month month day-of-week), got {}",
+            fields.len()
+        ));
+    };
+
+    let days_of_week_bits = parse_cron_field(days_of_week, 0, 7, &DAY_OF_WEEK_NAMES)
+        .map_err(|error| format!("Day of week: {}", error))?;
+    // 7 is another way to write Sunday
+    let days_of_week_bits = (days_of_week_bits | (days_of_week_bits >> 7)) & 0x7f;
+
+    Ok(CronExpression {
+        minutes: parse_cron_field(minutes, 0, 59, &[])
+            .map_err(|error| format!("Minute: {}", error))?,
+        hours: parse_cron_field(hours, 0, 23, &[]).map_err(|error| format!("Hour: {}", error))?
+            as u32,
+        days_of_month: parse_cron_field(days_of_month, 1, 31, &[])
+            .map_err(|error| format!("Day of month: {}", error))? as u32,
+        months: parse_cron_field(months, 1, 12, &MONTH_NAMES)
+            .map_err(|error| format!("Month: {}", error))? as u16,
+        days_of_week: days_of_week_bits as u8,
+        is_day_of_month_restricted: !days_of_month.starts_with('*'),
+        is_day_of_week_restricted: !days_of_week.starts_with('*'),
+    })
+}
+
+impl CronExpression {
+    pub fn get_next_matching_time(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let start = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let last_date = start.date() + Duration::days(MAX_CRON_SEARCH_DAYS);
+
+        let mut date = start.date();
+        while date <= last_date {
+            if self.matches_date(date) {
+                let (first_hour, first_minute) = if date == start.date() {
+                    (start.hour(), start.minute())
+                } else {
+                    (0, 0)
+                };
+
+                for hour in first_hour..24 {
+                    if self.hours & (1 << hour) == 0 {
+                        continue;
+                    }
+                    let first_minute = if hour == first_hour { first_minute } else { 0 };
+                    for minute in first_minute..60 {
+                        if self.minutes & (1 << minute) != 0 {
+                            return date.and_hms_opt(hour, minute, 0);
+                        }
+                    }
+                }
+            }
+            date = date.succ_opt()?;
+        }
+
+        None
+    }
+
+    fn matches_date(&self, date: chrono::NaiveDate) -> bool {
+        if self.months & (1 << date.month()) == 0 {
+            return false;
+        }
+
+        let matches_day_of_month = self.days_of_month & (1 << date.day()) != 0;
+        let matches_day_of_week =
+            self.days_of_week & (1 << date.weekday().num_days_from_sunday()) != 0;
+
+        if self.is_day_of_month_restricted && self.is_day_of_week_restricted {
+            matches_day_of_month || matches_day_of_week
+        } else {
+            matches_day_of_month && matches_day_of_week
+        }
+    }
+}
+
+// parses a field like "*/15", "1-5", "mon-fri" or "0,30" into a bit set
+fn parse_cron_field(field: &str, min: u32, max: u32, names: &[&str]) -> Result<u64, String> {
+    let parse_value = |value: &str| -> Result<u32, String> {
+        let lowercase_value = value.to_lowercase();
+        if let Some(name_idx) = names.iter().position(|name| *name == lowercase_value) {
+            // months are counted from 1, days of week from 0
+            return Ok(name_idx as u32 + min);
+        }
+        match value.parse::<u32>() {
+            Ok(value) if (min..=max).contains(&value) => Ok(value),
+            _ => Err(format!(
+                "\"{}\" is not a number between {} and {}",
+                value, min, max
+            )),
+        }
+    };
+
+    let mut result = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => match step.parse::<u32>() {
+                Ok(step) if step > 0 => (range, step),
+                _ => return Err(format!("\"{}\" is not a valid step", step)),
+            },
+            None => (part, 1),
+        };
+
+        let (first, last) = if range == "*" {
+            (min, max)
+        } else if let Some((first, last)) = range.split_once('-') {
+            (parse_value(first)?, parse_value(last)?)
+        } else {
+            let value = parse_value(range)?;
+            // "5/10" means starting from 5 with a step of 10
+            (value, if part.contains('/') { max } else { value })
+        };
+
+        if first > last {
+            return Err(format!("range \"{}\" is reversed", range));
+        }
+
+        for value in (first..=last).step_by(step as usize) {
+            result |= 1 << value;
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn make_time(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(year, month, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_cron_expression_finds_next_matching_minute() {
+        let expression = parse_cron_expression("*/15 9-17 * * mon-fri").unwrap();
+
+        // Friday evening goes to Monday morning
+        assert_eq!(
+            expression.get_next_matching_time(make_time(2025, 3, 7, 17, 50)),
+            Some(make_time(2025, 3, 10, 9, 0))
+        );
+        assert_eq!(
+            expression.get_next_matching_time(make_time(2025, 3, 10, 9, 0)),
+            Some(make_time(2025, 3, 10, 9, 15))
+        );
+        assert_eq!(
+            expression.get_next_matching_time(make_time(2025, 3, 10, 9, 14)),
+            Some(make_time(2025, 3, 10, 9, 15))
+        );
+    }
+
+    #[test]
+    fn test_cron_days_of_month_and_week_match_either_when_both_are_set() {
+        let expression = parse_cron_expression("30 2 13 * 5").unwrap();
+
+        // Friday the 7th comes before the 13th
+        assert_eq!(
+            expression.get_next_matching_time(make_time(2025, 3, 1, 0, 0)),
+            Some(make_time(2025, 3, 7, 2, 30))
+        );
+        assert_eq!(
+            expression.get_next_matching_time(make_time(2025, 3, 12, 0, 0)),
+            Some(make_time(2025, 3, 13, 2, 30))
+        );
+
+        let sunday = parse_cron_expression("@weekly").unwrap();
+        assert_eq!(sunday, parse_cron_expression("0 0 * * 7").unwrap());
+        assert_eq!(
+            parse_cron_expression("0 0 30 feb *")
+                .unwrap()
+                .get_next_matching_time(make_time(2025, 1, 1, 0, 0)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_invalid_cron_expressions_are_rejected() {
+        assert!(parse_cron_expression("* * * *").is_err());
+        assert!(parse_cron_expression("60 * * * *").is_err());
+        assert!(parse_cron_expression("* * * * */0").is_err());
+        assert!(parse_cron_expression("5-1 * * * *").is_err());
+        assert!(parse_cron_expression("* * * foo *").is_err());
+    }
+
+    #[test]
+    fn test_missed_runs_follow_the_policy() {
+        let script_uid = config::Guid::new();
+        let make_schedule = |missed_run_policy| config::ScriptSchedule {
+            script_uid: script_uid.clone(),
+            trigger: config::ScheduleTrigger::Interval { interval_sec: 60 },
+            missed_run_policy,
+        };
+        let start_time = Local::now();
+        let mut scheduler = Scheduler::new();
+        scheduler.update_schedules(
+            &[
+                make_schedule(config::MissedRunPolicy::Skip),
+                make_schedule(config::MissedRunPolicy::RunOnce),
+            ],
+            start_time,
+        );
+
+        assert!(scheduler
+            .take_due_runs(start_time + Duration::seconds(30), |_| false)
+            .is_empty());
+        // on time, both run
+        assert_eq!(
+            scheduler
+                .take_due_runs(start_time + Duration::seconds(61), |_| false)
+                .len(),
+            2
+        );
+        // woke up after a long sleep, only the second one runs, and only once
+        let wake_up_time = start_time + Duration::seconds(60 * 10 + 5);
+        assert_eq!(
+            scheduler
+                .take_due_runs(wake_up_time, |_| false)
+                .iter()
+                .map(|(idx, _)| *idx)
+                .collect::<Vec<_>>(),
+            vec![1]
+        );
+        assert_eq!(
+            scheduler.get_next_run_times().get(&script_uid),
+            Some(&(start_time + Duration::seconds(60 * 11)))
+        );
+    }
+}
@@ -0,0 +1,41 @@
+// Copyright (C) Pavel Grebnev 2025
+// Distributed under the MIT License (license terms are at http://opensource.org/licenses/MIT).
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static NEXT_DIRECTORY_IDX: AtomicUsize = AtomicUsize::new(0);
+
+// an empty directory for the files of a test, removed with everything in it when the test ends,
+// including when an assertion fails
+pub struct TestDirectory {
+    path: PathBuf,
+}
+
+impl TestDirectory {
+    pub fn new(test_name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!(
+            "scripter_test_{}_{}_{}",
+            test_name,
+            std::process::id(),
+            NEXT_DIRECTORY_IDX.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+        Self { path }
+    }
+}
+
+impl std::ops::Deref for TestDirectory {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TestDirectory {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
@@ -22,6 +22,7 @@ pub struct IconCaches {
     pub idle: Handle,
     pub in_progress: Handle,
     pub succeeded: Handle,
+    pub warning: Handle,
     pub failed: Handle,
     pub skipped: Handle,
 
@@ -52,6 +53,9 @@ impl IconCaches {
                     .into_iter()
                     .as_slice(),
             ),
+            warning: Handle::from_bytes(
+                include_bytes!("../res/icons/warning.png").iter().as_slice(),
+            ),
             failed: Handle::from_bytes(
                 include_bytes!("../res/icons/negative.png")
                     .into_iter()